@@ -11,7 +11,7 @@
 //!   cargo run --example categories -- --token YOUR_TOKEN delete --id CATEGORY_ID
 
 use clap::{Parser, Subcommand};
-use sure_client_rs::{Auth, CategoryId, SureClient};
+use sure_client_rs::{Auth, CategoryId, HexColor, Icon, SureClient};
 use url::Url;
 
 #[derive(Parser)]
@@ -64,11 +64,11 @@ enum Commands {
 
         /// Color in hex format (e.g., "#FF5733")
         #[arg(long)]
-        color: String,
+        color: HexColor,
 
         /// Lucide icon name (optional)
         #[arg(long)]
-        icon: Option<String>,
+        icon: Option<Icon>,
 
         /// Parent category ID for subcategories (UUID, optional)
         #[arg(long)]
@@ -86,11 +86,11 @@ enum Commands {
 
         /// New color in hex format (optional)
         #[arg(long)]
-        color: Option<String>,
+        color: Option<HexColor>,
 
         /// New Lucide icon name (optional)
         #[arg(long)]
-        icon: Option<String>,
+        icon: Option<Icon>,
 
         /// New parent category ID (UUID, optional)
         #[arg(long)]
@@ -104,14 +104,6 @@ enum Commands {
     },
 }
 
-fn validate_and_normalize_hex_code(code: &str) -> Option<String> {
-    let code = code.trim();
-    (code.len() == 7
-        && code.starts_with('#')
-        && code.chars().skip(1).all(|c| c.is_ascii_hexdigit()))
-    .then(|| code.to_uppercase())
-}
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
@@ -206,13 +198,6 @@ async fn main() -> anyhow::Result<()> {
                 None
             };
 
-            if validate_and_normalize_hex_code(&color).is_none() {
-                return Err(anyhow::anyhow!(
-                    "Invalid color code: {}. Must be in hex format like '#FF5733'.",
-                    color
-                ));
-            }
-
             let category = client
                 .create_category()
                 .name(name)
@@ -10,7 +10,7 @@
 //!   cargo run --example merchants -- --token YOUR_TOKEN delete --id MERCHANT_ID
 
 use clap::{Parser, Subcommand};
-use sure_client_rs::{Auth, MerchantId, SureClient};
+use sure_client_rs::{Auth, HexColor, MerchantId, SureClient};
 use url::Url;
 
 #[derive(Parser)]
@@ -55,7 +55,7 @@ enum Commands {
 
         /// Color in hex format (e.g., "#FF5733", optional)
         #[arg(long)]
-        color: Option<String>,
+        color: Option<HexColor>,
     },
     /// Update an existing merchant
     Update {
@@ -69,7 +69,7 @@ enum Commands {
 
         /// New color in hex format (optional)
         #[arg(long)]
-        color: Option<String>,
+        color: Option<HexColor>,
     },
     /// Delete a merchant
     Delete {
@@ -0,0 +1,326 @@
+//! Daily account balances rebuilt from valuations and transactions
+//!
+//! Sure stores an account's balance as valuation entries: an opening anchor,
+//! reconciliations along the way and a current anchor. Between them the
+//! balance moves with the account's transactions. [`balance_history`] rolls
+//! from one valuation through the transactions to produce a balance for every
+//! day, and flags each later valuation that disagrees with the rolled balance.
+
+use std::collections::BTreeMap;
+
+use bon::Builder;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::models::account::AccountClassification;
+use crate::models::transaction::Transaction;
+use crate::models::valuation::{Valuation, ValuationKind};
+use crate::types::ValuationId;
+
+/// Default [`BalanceOptions::tolerance`] (0.01)
+pub const DEFAULT_DISCREPANCY_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Which valuation the balance is rolled from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RollDirection {
+    /// Start at the earliest valuation, usually the opening anchor, and add
+    /// each day's transactions going forward
+    #[default]
+    Forward,
+    /// Start at the latest valuation, usually the current anchor, and take
+    /// each day's transactions back off going backward
+    Backward,
+}
+
+/// Tuning for [`balance_history`]
+#[derive(Debug, Clone, PartialEq, Eq, Builder)]
+pub struct BalanceOptions {
+    /// Direction to roll in (default: forward)
+    #[builder(default)]
+    pub direction: RollDirection,
+    /// Whether the account is an asset or a liability; a liability's balance
+    /// is the amount owed, so spending raises it (default: asset)
+    #[builder(default = AccountClassification::Asset)]
+    pub classification: AccountClassification,
+    /// Largest difference between a valuation and the rolled balance that is
+    /// not flagged (default: 0.01)
+    #[builder(default = DEFAULT_DISCREPANCY_TOLERANCE)]
+    pub tolerance: Decimal,
+    /// First day of the history (default: the earliest valuation or
+    /// transaction)
+    pub start: Option<NaiveDate>,
+    /// Last day of the history (default: the latest valuation or transaction)
+    pub end: Option<NaiveDate>,
+}
+
+impl Default for BalanceOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// A valuation that disagrees with the balance rolled up to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceDiscrepancy {
+    /// Date of the valuation
+    pub date: NaiveDate,
+    /// The valuation
+    pub valuation_id: ValuationId,
+    /// Kind of the valuation
+    pub kind: ValuationKind,
+    /// Balance recorded by the valuation
+    pub recorded: Decimal,
+    /// Balance rolled from the previous valuation through the transactions
+    pub rolled: Decimal,
+}
+
+impl BalanceDiscrepancy {
+    /// How far the recorded balance is above the rolled balance
+    pub fn difference(&self) -> Decimal {
+        self.recorded.saturating_sub(self.rolled)
+    }
+}
+
+/// An account's end-of-day balances
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceHistory {
+    /// Balance at the end of each day
+    pub balances: BTreeMap<NaiveDate, Decimal>,
+    /// Valuations that disagree with the rolled balance, in roll order
+    pub discrepancies: Vec<BalanceDiscrepancy>,
+}
+
+impl BalanceHistory {
+    /// The balance at the end of `date`, or of the closest earlier day in the
+    /// history
+    pub fn balance_on(&self, date: NaiveDate) -> Option<Decimal> {
+        self.balances
+            .range(..=date)
+            .next_back()
+            .map(|(_, balance)| *balance)
+    }
+
+    /// Each day and its end-of-day balance, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = (NaiveDate, Decimal)> + '_ {
+        self.balances
+            .iter()
+            .map(|(date, balance)| (*date, *balance))
+    }
+
+    /// Returns `true` if every valuation agreed with the rolled balance
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// Rebuild an account's daily balances from its valuations and transactions
+///
+/// `valuations` and `transactions` should all belong to the same account. A
+/// valuation is the balance at the end of its day, including that day's
+/// transactions; when several share a day, the most recently created wins.
+///
+/// A forward roll covers the first valuation onwards and a backward roll the
+/// last valuation and before, so days outside that span are left out. At
+/// every other valuation the rolled balance is compared with the recorded
+/// one, mismatches beyond [`BalanceOptions::tolerance`] are flagged, and the
+/// roll continues from the recorded balance. Without valuations the history
+/// is empty.
+pub fn balance_history(
+    valuations: &[Valuation],
+    transactions: &[Transaction],
+    options: &BalanceOptions,
+) -> BalanceHistory {
+    let liability = options.classification == AccountClassification::Liability;
+    let mut flows: BTreeMap<NaiveDate, Decimal> = BTreeMap::new();
+    for transaction in transactions {
+        let amount = transaction.signed_amount();
+        let change = if liability {
+            Decimal::ZERO.saturating_sub(amount)
+        } else {
+            amount
+        };
+        let flow = flows.entry(transaction.date.date_naive()).or_default();
+        *flow = flow.saturating_add(change);
+    }
+
+    let mut anchors: BTreeMap<NaiveDate, &Valuation> = BTreeMap::new();
+    for valuation in valuations {
+        let anchor = anchors.entry(valuation.date).or_insert(valuation);
+        if valuation.created_at > anchor.created_at {
+            *anchor = valuation;
+        }
+    }
+
+    let (Some((&first_anchor, _)), Some((&last_anchor, _))) =
+        (anchors.first_key_value(), anchors.last_key_value())
+    else {
+        return BalanceHistory::default();
+    };
+    let first = options
+        .start
+        .or_else(|| flows.keys().next().copied())
+        .map_or(first_anchor, |date| date.min(first_anchor));
+    let last = options
+        .end
+        .or_else(|| flows.keys().next_back().copied())
+        .map_or(last_anchor, |date| date.max(last_anchor));
+
+    let flow_on = |date: &NaiveDate| flows.get(date).copied().unwrap_or_default();
+    let mut history = BalanceHistory::default();
+    let mut check = |date: NaiveDate, rolled: Decimal| match anchors.get(&date) {
+        Some(valuation) => {
+            if valuation.amount.saturating_sub(rolled).abs() > options.tolerance {
+                history.discrepancies.push(BalanceDiscrepancy {
+                    date,
+                    valuation_id: valuation.id,
+                    kind: valuation.kind,
+                    recorded: valuation.amount,
+                    rolled,
+                });
+            }
+            valuation.amount
+        }
+        None => rolled,
+    };
+
+    let mut balances = BTreeMap::new();
+    match options.direction {
+        RollDirection::Forward => {
+            let mut date = first_anchor;
+            let mut balance = anchors.get(&date).map_or(Decimal::ZERO, |v| v.amount);
+            balances.insert(date, balance);
+            while let Some(next) = date.succ_opt().filter(|next| *next <= last) {
+                balance = check(next, balance.saturating_add(flow_on(&next)));
+                balances.insert(next, balance);
+                date = next;
+            }
+        }
+        RollDirection::Backward => {
+            let mut date = last_anchor;
+            let mut balance = anchors.get(&date).map_or(Decimal::ZERO, |v| v.amount);
+            balances.insert(date, balance);
+            while let Some(previous) = date.pred_opt().filter(|previous| *previous >= first) {
+                balance = check(previous, balance.saturating_sub(flow_on(&date)));
+                balances.insert(previous, balance);
+                date = previous;
+            }
+        }
+    }
+
+    let in_range = |date: &NaiveDate| {
+        options.start.is_none_or(|start| *date >= start)
+            && options.end.is_none_or(|end| *date <= end)
+    };
+    history.balances = balances;
+    history.balances.retain(|date, _| in_range(date));
+    history.discrepancies.retain(|d| in_range(&d.date));
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use uuid::Uuid;
+
+    const ACCOUNT: u128 = 1;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).expect("valid date")
+    }
+
+    fn valuation(id: u128, day: u32, amount: i64, kind: &str) -> Valuation {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::from_u128(id),
+            "date": date(day),
+            "amount": Decimal::new(amount, 2).to_string(),
+            "currency": "NZD",
+            "kind": kind,
+            "account": {
+                "id": Uuid::from_u128(ACCOUNT),
+                "name": "Everyday",
+                "account_type": "depository"
+            },
+            "created_at": "2024-03-01T00:00:00Z",
+            "updated_at": "2024-03-01T00:00:00Z"
+        }))
+        .expect("valid valuation fixture")
+    }
+
+    fn transaction(id: u128, day: u32, signed_amount_cents: i64) -> Transaction {
+        test_fixtures::transaction_with(
+            id,
+            &date(day).to_string(),
+            "Transaction",
+            signed_amount_cents,
+            serde_json::json!({
+                "account": {
+                    "id": Uuid::from_u128(ACCOUNT),
+                    "name": "Everyday",
+                    "account_type": "depository"
+                }
+            }),
+        )
+    }
+
+    fn fixtures() -> (Vec<Valuation>, Vec<Transaction>) {
+        let valuations = vec![
+            valuation(10, 1, 10000, "opening_anchor"),
+            // $10 more than the transactions explain
+            valuation(11, 4, 8000, "reconciliation"),
+        ];
+        let transactions = vec![
+            transaction(20, 2, -2000),
+            transaction(21, 3, -1000),
+            transaction(22, 5, 5000),
+        ];
+        (valuations, transactions)
+    }
+
+    #[test]
+    fn rolls_forward_and_flags_reconciliation_mismatches() {
+        let (valuations, transactions) = fixtures();
+        let history = balance_history(&valuations, &transactions, &BalanceOptions::default());
+
+        let balances: Vec<_> = history.iter().collect();
+        assert_eq!(
+            balances,
+            [
+                (date(1), Decimal::new(10000, 2)),
+                (date(2), Decimal::new(8000, 2)),
+                (date(3), Decimal::new(7000, 2)),
+                (date(4), Decimal::new(8000, 2)),
+                (date(5), Decimal::new(13000, 2)),
+            ]
+        );
+        assert_eq!(history.discrepancies.len(), 1);
+        let discrepancy = history.discrepancies.first().expect("one discrepancy");
+        assert_eq!(discrepancy.date, date(4));
+        assert_eq!(discrepancy.difference(), Decimal::new(1000, 2));
+        assert_eq!(history.balance_on(date(9)), Some(Decimal::new(13000, 2)));
+    }
+
+    #[test]
+    fn rolls_backward_from_the_latest_valuation() {
+        let (valuations, transactions) = fixtures();
+        let options = BalanceOptions::builder()
+            .direction(RollDirection::Backward)
+            .build();
+        let history = balance_history(&valuations, &transactions, &options);
+
+        // Transactions after the latest valuation are outside a backward roll
+        assert_eq!(history.balances.keys().next_back(), Some(&date(4)));
+        assert_eq!(history.balance_on(date(2)), Some(Decimal::new(9000, 2)));
+        let discrepancy = history.discrepancies.first().expect("one discrepancy");
+        assert_eq!(discrepancy.kind, ValuationKind::OpeningAnchor);
+        assert_eq!(discrepancy.rolled, Decimal::new(11000, 2));
+
+        let liability = BalanceOptions::builder()
+            .classification(AccountClassification::Liability)
+            .build();
+        let history = balance_history(&valuations, &transactions, &liability);
+        assert_eq!(history.balance_on(date(3)), Some(Decimal::new(13000, 2)));
+    }
+}
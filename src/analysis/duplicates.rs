@@ -0,0 +1,415 @@
+//! Duplicate transaction detection
+//!
+//! Bank re-syncs and overlapping imports leave doubled rows behind, and a
+//! pending transaction is sometimes kept alongside its posted version. Two
+//! transactions are candidate duplicates when they are in the same account,
+//! have the same signed amount and currency, and are at most
+//! [`DuplicateOptions::max_days_apart`] days apart. Each candidate pair is
+//! scored from how similar the names are and how close the dates are, and
+//! pairs at or above [`DuplicateOptions::min_confidence`] are joined into
+//! [`DuplicateCluster`]s.
+//!
+//! Name similarity ignores case, punctuation, numbers and words banks add to
+//! pending rows (such as `PENDING` or `EFTPOS`), and compares the shorter name
+//! against the longer one, so a truncated pending name still matches.
+
+use std::collections::{HashMap, HashSet};
+
+use bon::Builder;
+use chrono::NaiveDate;
+
+use crate::models::DeleteResponse;
+use crate::models::batch::BatchReport;
+use crate::models::transaction::{Transaction, UpdateTransactionData};
+use crate::normalise::normalise_name;
+use crate::types::{Patch, TransactionId};
+
+/// Default [`DuplicateOptions::max_days_apart`]
+pub const DEFAULT_MAX_DAYS_APART: u32 = 3;
+
+/// Default [`DuplicateOptions::min_confidence`]
+pub const DEFAULT_MIN_CONFIDENCE: f64 = 0.6;
+
+/// Share of the confidence score that comes from name similarity; the rest
+/// comes from how close the dates are
+const NAME_WEIGHT: f64 = 0.7;
+
+/// Words banks add to transaction names that say nothing about the payee
+const NOISE_WORDS: &[&str] = &[
+    "pending",
+    "authorisation",
+    "authorization",
+    "pos",
+    "eftpos",
+    "purchase",
+    "debit",
+    "credit",
+    "card",
+    "visa",
+    "mastercard",
+];
+
+/// Tuning for [`find_duplicates`]
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct DuplicateOptions {
+    /// Most days two duplicates may be apart (default: 3)
+    #[builder(default = DEFAULT_MAX_DAYS_APART)]
+    pub max_days_apart: u32,
+    /// Lowest confidence, from 0 to 1, for a pair to be reported (default: 0.6)
+    #[builder(default = DEFAULT_MIN_CONFIDENCE)]
+    pub min_confidence: f64,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// A group of transactions that look like copies of each other
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateCluster {
+    /// The transaction to keep: the one with the most categorisation, then the
+    /// latest date, so a posted row wins over its pending row
+    pub survivor: Transaction,
+    /// The transactions to remove, oldest first
+    pub duplicates: Vec<Transaction>,
+    /// Confidence, from 0 to 1, of the weakest pair joining the cluster
+    pub confidence: f64,
+}
+
+impl DuplicateCluster {
+    /// IDs of the transactions to remove
+    pub fn duplicate_ids(&self) -> Vec<TransactionId> {
+        self.duplicates.iter().map(|t| t.id).collect()
+    }
+
+    /// Update that merges the duplicates' notes and tags into the survivor
+    ///
+    /// Tags are combined, keeping the survivor's order first. Notes from the
+    /// duplicates that the survivor's notes do not already contain are
+    /// appended on new lines. The update is empty when there is nothing to
+    /// merge.
+    pub fn merge_update(&self) -> UpdateTransactionData {
+        let mut update = UpdateTransactionData::default();
+
+        let mut tag_ids: Vec<_> = self.survivor.tags.iter().map(|tag| tag.id).collect();
+        let survivor_tag_count = tag_ids.len();
+        for tag in self.duplicates.iter().flat_map(|t| &t.tags) {
+            if !tag_ids.contains(&tag.id) {
+                tag_ids.push(tag.id);
+            }
+        }
+        if tag_ids.len() > survivor_tag_count {
+            update.tag_ids = Patch::Set(tag_ids);
+        }
+
+        let mut notes: Vec<&str> = self
+            .survivor
+            .notes
+            .as_deref()
+            .map(str::trim)
+            .filter(|notes| !notes.is_empty())
+            .into_iter()
+            .collect();
+        let survivor_note_count = notes.len();
+        for note in self.duplicates.iter().filter_map(|t| t.notes.as_deref()) {
+            let note = note.trim();
+            if !note.is_empty() && !notes.iter().any(|existing| existing.contains(note)) {
+                notes.push(note);
+            }
+        }
+        if notes.len() > survivor_note_count {
+            update.notes = Patch::Set(notes.join("\n"));
+        }
+
+        update
+    }
+}
+
+/// How [`SureClient::resolve_duplicates`](crate::SureClient::resolve_duplicates)
+/// treats a cluster
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DuplicateResolution {
+    /// Merge the duplicates' notes and tags into the survivor, then delete them
+    #[default]
+    Merge,
+    /// Delete the duplicates without touching the survivor
+    Delete,
+}
+
+/// Outcome of resolving a [`DuplicateCluster`]
+#[derive(Debug)]
+pub struct DuplicateResolutionReport {
+    /// The survivor as updated by the merge, if anything needed merging
+    pub merged: Option<Transaction>,
+    /// Per-duplicate results of the deletes
+    pub deleted: BatchReport<DeleteResponse>,
+}
+
+/// Find clusters of likely duplicate transactions
+///
+/// Clusters are returned most confident first.
+pub fn find_duplicates(
+    transactions: &[Transaction],
+    options: &DuplicateOptions,
+) -> Vec<DuplicateCluster> {
+    let mut groups: HashMap<_, Vec<usize>> = HashMap::new();
+    for (index, transaction) in transactions.iter().enumerate() {
+        groups
+            .entry((
+                transaction.account.id,
+                transaction.signed_amount_cents,
+                transaction.currency,
+            ))
+            .or_default()
+            .push(index);
+    }
+
+    let names: Vec<_> = transactions.iter().map(|t| name_tokens(&t.name)).collect();
+    let mut clusters = Clusters::new(transactions.len());
+    for mut indices in groups.into_values().filter(|indices| indices.len() > 1) {
+        indices.sort_by_key(|&index| transactions.get(index).map(|t| t.date));
+        for (position, &a) in indices.iter().enumerate() {
+            for &b in indices.iter().skip(position.saturating_add(1)) {
+                let (Some(first), Some(second)) = (transactions.get(a), transactions.get(b)) else {
+                    continue;
+                };
+                let days = days_between(first.date.date_naive(), second.date.date_naive());
+                if days > u64::from(options.max_days_apart) {
+                    // Sorted by date, so later transactions are further away
+                    break;
+                }
+                let (Some(first_name), Some(second_name)) = (names.get(a), names.get(b)) else {
+                    continue;
+                };
+                let confidence = pair_confidence(
+                    name_similarity(first_name, second_name),
+                    days,
+                    options.max_days_apart,
+                );
+                if confidence >= options.min_confidence {
+                    clusters.join(a, b, confidence);
+                }
+            }
+        }
+    }
+
+    let mut result: Vec<_> = clusters
+        .into_groups()
+        .into_iter()
+        .filter_map(|(members, confidence)| {
+            let mut members: Vec<_> = members
+                .into_iter()
+                .filter_map(|index| transactions.get(index))
+                .collect();
+            let survivor_position = members
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| {
+                    richness(a)
+                        .cmp(&richness(b))
+                        .then_with(|| a.date.cmp(&b.date))
+                        .then_with(|| b.created_at.cmp(&a.created_at))
+                })
+                .map(|(position, _)| position)?;
+            let survivor = members.remove(survivor_position).clone();
+            members.sort_by_key(|t| t.date);
+            Some(DuplicateCluster {
+                survivor,
+                duplicates: members.into_iter().cloned().collect(),
+                confidence,
+            })
+        })
+        .collect();
+    result.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.survivor.date.cmp(&b.survivor.date))
+    });
+    result
+}
+
+/// Union-find over transaction indices, tracking the weakest joining pair
+struct Clusters {
+    parents: Vec<usize>,
+    confidence: Vec<f64>,
+}
+
+impl Clusters {
+    fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            confidence: vec![1.0; len],
+        }
+    }
+
+    fn root(&mut self, mut index: usize) -> usize {
+        while let Some(&parent) = self.parents.get(index) {
+            if parent == index {
+                break;
+            }
+            // Path halving keeps the trees shallow
+            let grandparent = self.parents.get(parent).copied().unwrap_or(parent);
+            if let Some(slot) = self.parents.get_mut(index) {
+                *slot = grandparent;
+            }
+            index = grandparent;
+        }
+        index
+    }
+
+    fn join(&mut self, a: usize, b: usize, confidence: f64) {
+        let (a, b) = (self.root(a), self.root(b));
+        let weakest = [a, b]
+            .iter()
+            .filter_map(|&root| self.confidence.get(root).copied())
+            .fold(confidence, f64::min);
+        if let Some(slot) = self.parents.get_mut(b) {
+            *slot = a;
+        }
+        if let Some(slot) = self.confidence.get_mut(a) {
+            *slot = weakest;
+        }
+    }
+
+    /// Groups with more than one member, with their confidence
+    fn into_groups(mut self) -> Vec<(Vec<usize>, f64)> {
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for index in 0..self.parents.len() {
+            let root = self.root(index);
+            groups.entry(root).or_default().push(index);
+        }
+        groups
+            .into_iter()
+            .filter(|(_, members)| members.len() > 1)
+            .map(|(root, members)| {
+                let confidence = self.confidence.get(root).copied().unwrap_or(1.0);
+                (members, confidence)
+            })
+            .collect()
+    }
+}
+
+/// Words of a name that identify the payee
+fn name_tokens(name: &str) -> HashSet<String> {
+    normalise_name(name)
+        .split(' ')
+        .filter(|word| !word.is_empty())
+        .filter(|word| !word.chars().all(|c| c.is_ascii_digit()))
+        .filter(|word| !NOISE_WORDS.contains(word))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Share of the shorter name's words found in the longer name
+fn name_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let shorter = a.len().min(b.len());
+    if shorter == 0 {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    ratio(shared, shorter)
+}
+
+/// Combine name similarity and date distance into a score from 0 to 1
+fn pair_confidence(name_similarity: f64, days: u64, max_days_apart: u32) -> f64 {
+    let closeness = 1.0 - ratio_u64(days, u64::from(max_days_apart).saturating_add(1));
+    NAME_WEIGHT.mul_add(name_similarity, (1.0 - NAME_WEIGHT) * closeness)
+}
+
+/// How much categorisation a transaction carries, for picking the survivor
+fn richness(transaction: &Transaction) -> usize {
+    usize::from(transaction.category.is_some())
+        .saturating_add(usize::from(transaction.merchant.is_some()))
+        .saturating_add(usize::from(
+            transaction
+                .notes
+                .as_deref()
+                .is_some_and(|n| !n.trim().is_empty()),
+        ))
+        .saturating_add(transaction.tags.len())
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    ratio_u64(
+        u64::try_from(numerator).unwrap_or(u64::MAX),
+        u64::try_from(denominator).unwrap_or(u64::MAX),
+    )
+}
+
+#[expect(clippy::cast_precision_loss, reason = "counts are far below 2^52")]
+fn ratio_u64(numerator: u64, denominator: u64) -> f64 {
+    numerator as f64 / denominator as f64
+}
+
+/// Absolute number of days between two dates
+const fn days_between(a: NaiveDate, b: NaiveDate) -> u64 {
+    b.signed_duration_since(a).num_days().unsigned_abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::transaction;
+    use uuid::Uuid;
+
+    fn ids(cluster: &DuplicateCluster) -> Vec<u128> {
+        cluster
+            .duplicate_ids()
+            .iter()
+            .map(|id| id.as_uuid().as_u128())
+            .collect()
+    }
+
+    #[test]
+    fn clusters_resynced_and_pending_rows() {
+        let transactions = [
+            transaction(1, "2024-03-02", "COUNTDOWN PONSONBY", -8_450),
+            transaction(2, "2024-03-02", "Countdown Ponsonby", -8_450),
+            transaction(3, "2024-03-01", "PENDING - EFTPOS COUNTDOWN 4921", -8_450),
+            // Same amount, same day, different shop
+            transaction(4, "2024-03-02", "Z Energy", -8_450),
+            // Same shop and amount, but a week later
+            transaction(5, "2024-03-09", "Countdown Ponsonby", -8_450),
+        ];
+
+        let clusters = find_duplicates(&transactions, &DuplicateOptions::default());
+
+        assert_eq!(clusters.len(), 1);
+        let cluster = clusters.first().expect("one cluster");
+        assert_eq!(cluster.duplicates.len(), 2);
+        assert!(ids(cluster).contains(&3));
+        assert!(cluster.confidence >= DEFAULT_MIN_CONFIDENCE);
+        assert!(cluster.confidence < 1.0);
+    }
+
+    #[test]
+    fn survivor_keeps_categorisation_and_merges_notes_and_tags() {
+        let mut posted = transaction(1, "2024-03-02", "Spotify", -1_699);
+        posted.notes = Some("Family plan".to_string());
+        posted.tags = serde_json::from_value(serde_json::json!([
+            { "id": Uuid::from_u128(0x20), "name": "Subscriptions", "color": "#000000" }
+        ]))
+        .expect("valid tags");
+        let mut resynced = transaction(2, "2024-03-02", "SPOTIFY", -1_699);
+        resynced.notes = Some("Shared with flatmates".to_string());
+        resynced.tags = serde_json::from_value(serde_json::json!([
+            { "id": Uuid::from_u128(0x20), "name": "Subscriptions", "color": "#000000" },
+            { "id": Uuid::from_u128(0x21), "name": "Shared", "color": "#ffffff" }
+        ]))
+        .expect("valid tags");
+
+        let clusters = find_duplicates(&[resynced, posted], &DuplicateOptions::default());
+        let cluster = clusters.first().expect("one cluster");
+
+        assert_eq!(cluster.survivor.id.as_uuid().as_u128(), 2);
+        assert_eq!(ids(cluster), [1]);
+        assert_eq!(
+            cluster.merge_update(),
+            UpdateTransactionData::builder()
+                .notes("Shared with flatmates\nFamily plan".to_string())
+                .build()
+        );
+    }
+}
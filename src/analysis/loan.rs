@@ -0,0 +1,393 @@
+//! Amortisation schedules for loans and mortgages
+//!
+//! [`LoanTerms`] are read from an account's [`LoanAttributes`]: the amount
+//! borrowed, the annual interest rate (as a percentage, e.g. `6.5`) and the
+//! term in months. [`amortisation_schedule`] projects the monthly payments
+//! from those terms, splitting each into interest and principal, and
+//! [`LoanSchedule::compare`] checks the projection against the balance Sure
+//! actually reports.
+//!
+//! The projection assumes the rate stays as it is, so for a variable rate
+//! loan it only holds until the rate next changes.
+
+use bon::Builder;
+use chrono::{Months, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::models::account::LoanAttributes;
+
+/// Decimal places amounts are rounded to
+const MONEY_SCALE: u32 = 2;
+
+/// Why [`LoanAttributes`] cannot be used for a schedule
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LoanTermsError {
+    /// A required attribute is not set
+    #[error("loan has no {0}")]
+    Missing(&'static str),
+    /// The term is zero or negative
+    #[error("loan term of {0} months is not positive")]
+    InvalidTerm(i32),
+    /// The interest rate is negative
+    #[error("interest rate of {0}% is negative")]
+    InvalidRate(Decimal),
+    /// The amount borrowed is zero or negative
+    #[error("initial balance of {0} is not positive")]
+    InvalidPrincipal(Decimal),
+}
+
+/// The terms a loan is repaid on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoanTerms {
+    /// Amount borrowed
+    pub principal: Decimal,
+    /// Annual interest rate as a percentage (e.g. `6.5` for 6.5%)
+    pub annual_rate: Decimal,
+    /// Number of monthly payments
+    pub term_months: u32,
+    /// Date the loan was drawn; the first payment is due a month later
+    pub start: NaiveDate,
+}
+
+impl LoanTerms {
+    /// Read the terms from a loan account's attributes
+    ///
+    /// # Errors
+    /// Returns `LoanTermsError::Missing` if `initial_balance`,
+    /// `interest_rate` or `term_months` is not set, or another
+    /// [`LoanTermsError`] if one of them is out of range.
+    pub fn from_attributes(
+        attributes: &LoanAttributes,
+        start: NaiveDate,
+    ) -> Result<Self, LoanTermsError> {
+        let principal = attributes
+            .initial_balance
+            .ok_or(LoanTermsError::Missing("initial balance"))?;
+        let annual_rate = attributes
+            .interest_rate
+            .ok_or(LoanTermsError::Missing("interest rate"))?;
+        let term = attributes
+            .term_months
+            .ok_or(LoanTermsError::Missing("term"))?;
+
+        if principal <= Decimal::ZERO {
+            return Err(LoanTermsError::InvalidPrincipal(principal));
+        }
+        if annual_rate.is_sign_negative() && !annual_rate.is_zero() {
+            return Err(LoanTermsError::InvalidRate(annual_rate));
+        }
+        let term_months = u32::try_from(term)
+            .ok()
+            .filter(|months| *months > 0)
+            .ok_or(LoanTermsError::InvalidTerm(term))?;
+
+        Ok(Self {
+            principal,
+            annual_rate,
+            term_months,
+            start,
+        })
+    }
+
+    /// Interest rate charged each month, as a fraction
+    pub fn monthly_rate(&self) -> Decimal {
+        self.annual_rate
+            .checked_div(Decimal::from(1200))
+            .unwrap_or_default()
+    }
+
+    /// The regular monthly payment that repays the loan over its term
+    pub fn payment(&self) -> Decimal {
+        let rate = self.monthly_rate();
+        let months = Decimal::from(self.term_months);
+        if rate.is_zero() {
+            return self
+                .principal
+                .checked_div(months)
+                .unwrap_or(self.principal)
+                .round_dp(MONEY_SCALE);
+        }
+
+        // principal * rate * growth / (growth - 1), where growth is
+        // (1 + rate) ^ term. When growth overflows the payment tends to the
+        // interest alone, and the final payment clears the balance.
+        let interest_only = self.principal.saturating_mul(rate);
+        let growth = (0..self.term_months).try_fold(Decimal::ONE, |growth, _| {
+            growth.checked_mul(Decimal::ONE.saturating_add(rate))
+        });
+        growth
+            .and_then(|growth| {
+                interest_only
+                    .checked_mul(growth)?
+                    .checked_div(growth.checked_sub(Decimal::ONE)?)
+            })
+            .unwrap_or(interest_only)
+            .round_dp(MONEY_SCALE)
+    }
+}
+
+/// A one-off extra repayment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LumpSum {
+    /// Date the repayment is made; it is applied with the next scheduled
+    /// payment on or after this date
+    pub date: NaiveDate,
+    /// Amount repaid
+    pub amount: Decimal,
+}
+
+/// Extra repayments for [`amortisation_schedule`]
+///
+/// Extra repayments go straight to principal and the regular payment stays
+/// the same, so the loan is paid off sooner.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Builder)]
+pub struct LoanOptions {
+    /// Amount paid on top of every regular payment (default: none)
+    #[builder(default)]
+    pub extra_payment: Decimal,
+    /// One-off extra repayments (default: none)
+    #[builder(default)]
+    pub lump_sums: Vec<LumpSum>,
+}
+
+/// One month of an amortisation schedule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoanPayment {
+    /// Payment number, starting at 1
+    pub period: u32,
+    /// Date the payment is due
+    pub date: NaiveDate,
+    /// Part of the regular payment covering interest
+    pub interest: Decimal,
+    /// Part of the regular payment repaying principal
+    pub principal: Decimal,
+    /// Extra repayment made with this payment
+    pub extra: Decimal,
+    /// Balance owed after the payment
+    pub balance: Decimal,
+}
+
+impl LoanPayment {
+    /// The regular payment: interest plus principal
+    pub fn payment(&self) -> Decimal {
+        self.interest.saturating_add(self.principal)
+    }
+
+    /// Everything paid, including the extra repayment
+    pub fn total(&self) -> Decimal {
+        self.payment().saturating_add(self.extra)
+    }
+}
+
+/// The projected payments of a loan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoanSchedule {
+    /// Terms the schedule was projected from
+    pub terms: LoanTerms,
+    /// Regular monthly payment
+    pub payment: Decimal,
+    /// Every payment until the loan is repaid
+    pub payments: Vec<LoanPayment>,
+}
+
+impl LoanSchedule {
+    /// Date of the final payment
+    pub fn payoff_date(&self) -> Option<NaiveDate> {
+        self.payments.last().map(|payment| payment.date)
+    }
+
+    /// Interest paid over the life of the loan
+    pub fn total_interest(&self) -> Decimal {
+        self.payments
+            .iter()
+            .fold(Decimal::ZERO, |sum, p| sum.saturating_add(p.interest))
+    }
+
+    /// Everything paid over the life of the loan
+    pub fn total_paid(&self) -> Decimal {
+        self.payments
+            .iter()
+            .fold(Decimal::ZERO, |sum, p| sum.saturating_add(p.total()))
+    }
+
+    /// The projected balance at the end of `date`
+    pub fn balance_on(&self, date: NaiveDate) -> Decimal {
+        self.payments
+            .iter()
+            .take_while(|payment| payment.date <= date)
+            .last()
+            .map_or(self.terms.principal, |payment| payment.balance)
+    }
+
+    /// Compare the projected balance on `as_of` with the balance owed
+    pub fn compare(&self, actual: Decimal, as_of: NaiveDate) -> LoanProgress {
+        LoanProgress {
+            as_of,
+            projected: self.balance_on(as_of),
+            actual,
+        }
+    }
+}
+
+/// How a loan's actual balance compares with its schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoanProgress {
+    /// Date of the comparison
+    pub as_of: NaiveDate,
+    /// Balance the schedule projects
+    pub projected: Decimal,
+    /// Balance actually owed
+    pub actual: Decimal,
+}
+
+impl LoanProgress {
+    /// How much more is owed than projected; negative when ahead of schedule
+    pub fn difference(&self) -> Decimal {
+        self.actual.saturating_sub(self.projected)
+    }
+
+    /// Returns `true` if no more is owed than projected
+    pub fn is_on_track(&self) -> bool {
+        self.actual <= self.projected
+    }
+}
+
+/// A loan's schedule and how its actual balance compares
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoanProjection {
+    /// Projected payments
+    pub schedule: LoanSchedule,
+    /// Actual balance against the projection
+    pub progress: LoanProgress,
+}
+
+/// Project the monthly payments of a loan
+///
+/// Interest is charged monthly on the outstanding balance and rounded to
+/// cents. Each regular payment covers that interest and repays the rest as
+/// principal; the final payment is adjusted to clear the balance exactly.
+/// Extra repayments from `options` come off the balance after the regular
+/// payment.
+pub fn amortisation_schedule(terms: &LoanTerms, options: &LoanOptions) -> LoanSchedule {
+    let rate = terms.monthly_rate();
+    let regular = terms.payment();
+    let mut lump_sums: Vec<&LumpSum> = options.lump_sums.iter().collect();
+    lump_sums.sort_by_key(|lump| lump.date);
+    let mut lump_sums = lump_sums.into_iter().peekable();
+
+    let mut balance = terms.principal;
+    let mut payments = Vec::new();
+    for period in 1..=terms.term_months {
+        let Some(date) = terms.start.checked_add_months(Months::new(period)) else {
+            break;
+        };
+        let interest = balance.saturating_mul(rate).round_dp(MONEY_SCALE);
+        let principal = if period == terms.term_months {
+            balance
+        } else {
+            regular
+                .saturating_sub(interest)
+                .clamp(Decimal::ZERO, balance)
+        };
+        balance = balance.saturating_sub(principal);
+
+        let mut extra = options.extra_payment;
+        while let Some(lump) = lump_sums.next_if(|lump| lump.date <= date) {
+            extra = extra.saturating_add(lump.amount);
+        }
+        let extra = extra.clamp(Decimal::ZERO, balance);
+        balance = balance.saturating_sub(extra);
+
+        payments.push(LoanPayment {
+            period,
+            date,
+            interest,
+            principal,
+            extra,
+            balance,
+        });
+        if balance.is_zero() {
+            break;
+        }
+    }
+
+    LoanSchedule {
+        terms: terms.clone(),
+        payment: regular,
+        payments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(principal: i64, annual_rate: i64, term_months: i32) -> LoanTerms {
+        let attributes = LoanAttributes {
+            subtype: None,
+            rate_type: None,
+            interest_rate: Some(Decimal::from(annual_rate)),
+            term_months: Some(term_months),
+            initial_balance: Some(Decimal::from(principal)),
+            locked_attributes: None,
+        };
+        let start = NaiveDate::from_ymd_opt(2024, 1, 15).expect("valid date");
+        LoanTerms::from_attributes(&attributes, start).expect("valid terms")
+    }
+
+    #[test]
+    fn repays_the_loan_over_its_term() {
+        let terms = terms(10_000, 12, 12);
+        let schedule = amortisation_schedule(&terms, &LoanOptions::default());
+
+        assert_eq!(schedule.payment, Decimal::new(88_849, 2));
+        assert_eq!(schedule.payments.len(), 12);
+        let first = schedule.payments.first().expect("first payment");
+        assert_eq!(first.interest, Decimal::from(100));
+        assert_eq!(first.principal, Decimal::new(78_849, 2));
+        assert_eq!(schedule.payoff_date(), NaiveDate::from_ymd_opt(2025, 1, 15));
+        assert_eq!(
+            schedule.payments.last().map(|p| p.balance),
+            Some(Decimal::ZERO)
+        );
+        assert_eq!(
+            schedule.total_paid(),
+            Decimal::from(10_000).saturating_add(schedule.total_interest())
+        );
+
+        let progress = schedule.compare(Decimal::from(9_500), first.date);
+        assert_eq!(progress.difference(), Decimal::new(28_849, 2));
+        assert!(!progress.is_on_track());
+    }
+
+    #[test]
+    fn extra_repayments_shorten_the_loan() {
+        let loan = terms(10_000, 12, 12);
+        let standard = amortisation_schedule(&loan, &LoanOptions::default());
+        let options = LoanOptions::builder()
+            .extra_payment(Decimal::from(500))
+            .lump_sums(vec![LumpSum {
+                date: NaiveDate::from_ymd_opt(2024, 2, 1).expect("valid date"),
+                amount: Decimal::from(2_000),
+            }])
+            .build();
+        let faster = amortisation_schedule(&loan, &options);
+
+        assert_eq!(
+            faster.payments.first().map(|p| p.extra),
+            Some(Decimal::from(2_500))
+        );
+        assert!(faster.payments.len() < standard.payments.len());
+        assert!(faster.total_interest() < standard.total_interest());
+        assert_eq!(
+            faster.payments.last().map(|p| p.balance),
+            Some(Decimal::ZERO)
+        );
+
+        let interest_free = amortisation_schedule(&terms(1_200, 0, 12), &LoanOptions::default());
+        assert_eq!(interest_free.payment, Decimal::from(100));
+        assert_eq!(interest_free.total_interest(), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,17 @@
+//! Client-side analyses over accounts and transaction history
+//!
+//! These work on data already fetched from the API, so they can be run over
+//! any slice of transactions; the matching [`SureClient`](crate::SureClient)
+//! methods fetch the data and run them in one call.
+//!
+//! - [`recurring`] detects subscriptions and other recurring series.
+//! - [`duplicates`] finds transactions that were imported or synced twice.
+//! - [`balance`] rebuilds an account's daily balances from its valuations.
+//! - [`net_worth`] totals every account in one currency.
+//! - [`loan`] projects the amortisation schedule of a loan.
+
+pub mod balance;
+pub mod duplicates;
+pub mod loan;
+pub mod net_worth;
+pub mod recurring;
@@ -0,0 +1,221 @@
+//! Net worth across accounts held in several currencies
+//!
+//! [`net_worth`] converts every account balance into one reporting currency
+//! with an [`FxRateProvider`] and nets liabilities against assets. The totals
+//! are also broken down by account kind, institution and original currency.
+
+use std::collections::BTreeMap;
+
+use iso_currency::Currency;
+use rust_decimal::Decimal;
+
+use crate::fx::FxRateProvider;
+use crate::models::account::{Account, AccountClassification, AccountKind, AccountStatus};
+use crate::types::AccountId;
+
+/// Assets and liabilities of a group of accounts, in the reporting currency
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetWorthTotals {
+    /// Sum of asset balances
+    pub assets: Decimal,
+    /// Sum of liability balances, as a positive amount owed
+    pub liabilities: Decimal,
+    /// Number of accounts in the group
+    pub accounts: usize,
+}
+
+impl NetWorthTotals {
+    /// Assets less liabilities
+    pub fn net(&self) -> Decimal {
+        self.assets.saturating_sub(self.liabilities)
+    }
+
+    fn add(&mut self, liability: bool, amount: Decimal) {
+        if liability {
+            self.liabilities = self.liabilities.saturating_add(amount);
+        } else {
+            self.assets = self.assets.saturating_add(amount);
+        }
+        self.accounts = self.accounts.saturating_add(1);
+    }
+}
+
+/// An account left out of the totals because its currency has no rate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnconvertedBalance {
+    /// The account
+    pub account_id: AccountId,
+    /// Name of the account
+    pub name: String,
+    /// Balance in the account's currency
+    pub balance: Decimal,
+    /// Currency of the account
+    pub currency: Currency,
+}
+
+/// Net worth in a single reporting currency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetWorth {
+    /// Currency every amount is reported in
+    pub currency: Currency,
+    /// Totals over every converted account
+    pub total: NetWorthTotals,
+    /// Totals for each kind of account
+    pub by_kind: BTreeMap<AccountKind, NetWorthTotals>,
+    /// Totals for each institution; accounts without one are under `None`
+    pub by_institution: BTreeMap<Option<String>, NetWorthTotals>,
+    /// Totals for each currency the accounts are held in, after conversion
+    pub by_currency: BTreeMap<Currency, NetWorthTotals>,
+    /// Accounts that could not be converted and are not in any total
+    pub unconverted: Vec<UnconvertedBalance>,
+}
+
+impl NetWorth {
+    /// Assets less liabilities over every converted account
+    pub fn net(&self) -> Decimal {
+        self.total.net()
+    }
+
+    /// Returns `true` if every account was converted
+    pub fn is_complete(&self) -> bool {
+        self.unconverted.is_empty()
+    }
+}
+
+/// Calculate net worth in `currency`
+///
+/// Liabilities are accounts classified as such, or of a liability kind when
+/// the classification is not recognised; their balance is the amount owed.
+/// Accounts that Sure leaves out of its own totals (disabled, draft, or
+/// pending deletion) are skipped. Each converted balance is rounded to the
+/// reporting currency's minor unit, and accounts whose currency has no rate
+/// are listed in [`NetWorth::unconverted`] instead.
+pub fn net_worth(accounts: &[Account], currency: Currency, rates: &dyn FxRateProvider) -> NetWorth {
+    let scale = u32::from(currency.exponent().unwrap_or(2));
+    let mut worth = NetWorth {
+        currency,
+        total: NetWorthTotals::default(),
+        by_kind: BTreeMap::new(),
+        by_institution: BTreeMap::new(),
+        by_currency: BTreeMap::new(),
+        unconverted: Vec::new(),
+    };
+
+    for account in accounts.iter().filter(|account| is_counted(account)) {
+        let Some(amount) = rates.convert(account.balance, account.currency, currency) else {
+            worth.unconverted.push(UnconvertedBalance {
+                account_id: account.id,
+                name: account.name.clone(),
+                balance: account.balance,
+                currency: account.currency,
+            });
+            continue;
+        };
+        let amount = amount.round_dp(scale);
+        let liability = is_liability(account);
+
+        worth.total.add(liability, amount);
+        worth
+            .by_kind
+            .entry(account.kind)
+            .or_default()
+            .add(liability, amount);
+        worth
+            .by_institution
+            .entry(account.institution_name.clone())
+            .or_default()
+            .add(liability, amount);
+        worth
+            .by_currency
+            .entry(account.currency)
+            .or_default()
+            .add(liability, amount);
+    }
+
+    worth
+}
+
+fn is_counted(account: &Account) -> bool {
+    let excluded = matches!(
+        account.status,
+        Some(AccountStatus::Disabled | AccountStatus::Draft | AccountStatus::PendingDeletion)
+    );
+    !excluded && account.is_active != Some(false)
+}
+
+const fn is_liability(account: &Account) -> bool {
+    match account.classification {
+        AccountClassification::Liability => true,
+        AccountClassification::Asset => false,
+        AccountClassification::Other(_) => matches!(
+            account.kind,
+            AccountKind::CreditCard | AccountKind::Loan | AccountKind::OtherLiability
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fx::StaticRates;
+    use uuid::Uuid;
+
+    fn account(
+        id: u128,
+        kind: &str,
+        classification: &str,
+        balance: &str,
+        currency: &str,
+        institution: Option<&str>,
+    ) -> Account {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::from_u128(id),
+            "name": format!("Account {id}"),
+            "balance": balance,
+            "currency": currency,
+            "classification": classification,
+            "account_type": kind,
+            "institution_name": institution,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        }))
+        .expect("valid account fixture")
+    }
+
+    #[test]
+    fn nets_liabilities_in_the_reporting_currency() {
+        let accounts = [
+            account(1, "depository", "asset", "1000.00", "NZD", Some("Kiwibank")),
+            account(2, "investment", "asset", "500.00", "USD", None),
+            account(3, "depository", "asset", "200.00", "AUD", Some("Kiwibank")),
+            account(4, "credit_card", "liability", "300.00", "NZD", Some("ANZ")),
+            account(5, "loan", "equity", "100.00", "AUD", Some("ANZ")),
+            account(6, "depository", "asset", "50.00", "EUR", None),
+        ];
+        let rates = StaticRates::new()
+            .with_rate(Currency::USD, Currency::NZD, Decimal::new(16, 1))
+            .with_rate(Currency::AUD, Currency::NZD, Decimal::new(11, 1));
+
+        let worth = net_worth(&accounts, Currency::NZD, &rates);
+
+        // 1000 + 800 + 220 in assets, 300 + 110 owed
+        assert_eq!(worth.total.assets, Decimal::from(2020));
+        assert_eq!(worth.total.liabilities, Decimal::from(410));
+        assert_eq!(worth.net(), Decimal::from(1610));
+
+        let kiwibank = worth.by_institution.get(&Some("Kiwibank".to_string()));
+        assert_eq!(kiwibank.map(NetWorthTotals::net), Some(Decimal::from(1220)));
+        let anz = worth.by_institution.get(&Some("ANZ".to_string()));
+        assert_eq!(anz.map(|t| t.accounts), Some(2));
+        let loans = worth.by_kind.get(&AccountKind::Loan);
+        assert_eq!(loans.map(|t| t.liabilities), Some(Decimal::from(110)));
+        let aud = worth.by_currency.get(&Currency::AUD);
+        assert_eq!(aud.map(NetWorthTotals::net), Some(Decimal::from(110)));
+
+        assert!(!worth.is_complete());
+        assert_eq!(
+            worth.unconverted.first().map(|u| u.currency),
+            Some(Currency::EUR)
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Recurring transaction and subscription detection
+//!
+//! Transactions are grouped by merchant, or by normalised name when they have
+//! no merchant, along with currency and nature. A group
+//! becomes a [`RecurringSeries`] when enough of its transactions have similar
+//! amounts and fall at a regular [`Cadence`]. Each series records when the next
+//! occurrence is expected and flags missed occurrences and price increases.
+
+use std::collections::HashMap;
+
+use bon::Builder;
+use chrono::{Days, Months, NaiveDate};
+use iso_currency::Currency;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::models::transaction::{Transaction, TransactionNature};
+use crate::normalise::normalise_name;
+use crate::types::{MerchantId, TransactionId};
+
+/// Default [`RecurringOptions::min_occurrences`]
+pub const DEFAULT_MIN_OCCURRENCES: usize = 3;
+
+/// Default [`RecurringOptions::amount_tolerance`] (25%)
+pub const DEFAULT_AMOUNT_TOLERANCE: Decimal = Decimal::from_parts(25, 0, 0, false, 2);
+
+/// Default [`RecurringOptions::price_increase_threshold`] (2%)
+pub const DEFAULT_PRICE_INCREASE_THRESHOLD: Decimal = Decimal::from_parts(2, 0, 0, false, 2);
+
+/// Largest number of consecutive missed occurrences a series may contain
+const MAX_MISSED_PERIODS: u32 = 12;
+
+/// How often a recurring series occurs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Cadence {
+    /// Every 7 days
+    Weekly,
+    /// Every 14 days
+    Fortnightly,
+    /// Once a calendar month
+    Monthly,
+    /// Once a year
+    Annual,
+}
+
+impl Cadence {
+    const ALL: [Self; 4] = [Self::Weekly, Self::Fortnightly, Self::Monthly, Self::Annual];
+
+    /// Typical number of days between occurrences
+    pub const fn nominal_days(self) -> u64 {
+        match self {
+            Self::Weekly => 7,
+            Self::Fortnightly => 14,
+            Self::Monthly => 30,
+            Self::Annual => 365,
+        }
+    }
+
+    /// How many days early or late an occurrence may be and still be on time
+    pub const fn slack_days(self) -> u64 {
+        match self {
+            Self::Weekly => 2,
+            Self::Fortnightly => 3,
+            Self::Monthly => 4,
+            Self::Annual => 15,
+        }
+    }
+
+    /// The date `periods` occurrences after `date`
+    ///
+    /// Monthly and annual cadences step by calendar months, clamping to the
+    /// end of shorter months. Returns `None` if the date is out of range.
+    pub fn advance(self, date: NaiveDate, periods: u32) -> Option<NaiveDate> {
+        match self {
+            Self::Weekly | Self::Fortnightly => date.checked_add_days(Days::new(
+                self.nominal_days().saturating_mul(periods.into()),
+            )),
+            Self::Monthly => date.checked_add_months(Months::new(periods)),
+            Self::Annual => date.checked_add_months(Months::new(periods.saturating_mul(12))),
+        }
+    }
+
+    /// The cadence whose interval is within slack of `days`
+    fn of_interval(days: u64) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|cadence| days.abs_diff(cadence.nominal_days()) <= cadence.slack_days())
+    }
+
+    /// Number of periods from `from` to `to`, if `to` falls on an occurrence
+    fn periods_between(self, from: NaiveDate, to: NaiveDate) -> Option<u32> {
+        (1..=MAX_MISSED_PERIODS.saturating_add(1)).find(|&periods| {
+            self.advance(from, periods)
+                .is_some_and(|expected| days_between(expected, to) <= self.slack_days())
+        })
+    }
+}
+
+/// Something unusual about a recurring series
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SeriesFlag {
+    /// No transaction was found for an expected occurrence
+    Missed {
+        /// Date the occurrence was expected
+        expected: NaiveDate,
+    },
+    /// The next occurrence is late as of [`RecurringOptions::as_of`]
+    Overdue {
+        /// Date the occurrence was expected
+        expected: NaiveDate,
+    },
+    /// An occurrence cost more than the one before it
+    PriceIncrease {
+        /// The more expensive transaction
+        transaction_id: TransactionId,
+        /// Date of the more expensive transaction
+        date: NaiveDate,
+        /// Amount of the previous occurrence
+        previous: Decimal,
+        /// Amount of this occurrence
+        amount: Decimal,
+    },
+}
+
+/// A detected recurring series, such as a subscription or salary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurringSeries {
+    /// Merchant name, or the name of the latest transaction
+    pub label: String,
+    /// Merchant the series was grouped by, if any
+    pub merchant_id: Option<MerchantId>,
+    /// Whether the series is income or an expense
+    pub nature: TransactionNature,
+    /// Currency of every occurrence
+    pub currency: Currency,
+    /// How often the series occurs
+    pub cadence: Cadence,
+    /// Transactions in the series, oldest first
+    pub transaction_ids: Vec<TransactionId>,
+    /// Date of the first occurrence
+    pub first_date: NaiveDate,
+    /// Date of the latest occurrence
+    pub last_date: NaiveDate,
+    /// Date the next occurrence is expected
+    pub next_expected_date: NaiveDate,
+    /// Absolute amount of the latest occurrence
+    pub latest_amount: Decimal,
+    /// Mean absolute amount over the series
+    pub average_amount: Decimal,
+    /// Latest amount minus the first amount; positive when the price went up
+    pub amount_drift: Decimal,
+    /// Missed, overdue and price-increased occurrences, oldest first
+    pub flags: Vec<SeriesFlag>,
+}
+
+impl RecurringSeries {
+    /// Returns `true` if any flag was raised for the series
+    pub fn is_flagged(&self) -> bool {
+        !self.flags.is_empty()
+    }
+}
+
+/// Tuning for [`detect_recurring`]
+#[derive(Debug, Clone, PartialEq, Eq, Builder)]
+pub struct RecurringOptions {
+    /// Fewest occurrences that make a series (default: 3)
+    #[builder(default = DEFAULT_MIN_OCCURRENCES)]
+    pub min_occurrences: usize,
+    /// How far, as a fraction of the median, an amount may be from the median
+    /// amount and still belong to the series (default: 0.25)
+    #[builder(default = DEFAULT_AMOUNT_TOLERANCE)]
+    pub amount_tolerance: Decimal,
+    /// Fractional rise over the previous occurrence that is flagged as a price
+    /// increase (default: 0.02)
+    #[builder(default = DEFAULT_PRICE_INCREASE_THRESHOLD)]
+    pub price_increase_threshold: Decimal,
+    /// Date to check for overdue occurrences; no check is made when unset
+    pub as_of: Option<NaiveDate>,
+}
+
+impl Default for RecurringOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// What transactions are grouped by
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SeriesKey {
+    Merchant(MerchantId),
+    Name(String),
+}
+
+/// One transaction reduced to what detection needs
+struct Occurrence<'a> {
+    transaction: &'a Transaction,
+    date: NaiveDate,
+    amount: Decimal,
+}
+
+/// Detect recurring series in a set of transactions
+///
+/// Transfers are ignored. Series are returned largest first by average
+/// amount, so the most expensive subscriptions lead.
+pub fn detect_recurring(
+    transactions: &[Transaction],
+    options: &RecurringOptions,
+) -> Vec<RecurringSeries> {
+    let mut groups: HashMap<(SeriesKey, Currency, TransactionNature), Vec<Occurrence<'_>>> =
+        HashMap::new();
+    for transaction in transactions.iter().filter(|t| t.transfer.is_none()) {
+        let key = match &transaction.merchant {
+            Some(merchant) => SeriesKey::Merchant(merchant.id),
+            None => SeriesKey::Name(normalise_name(&transaction.name)),
+        };
+        let nature = if transaction.signed_amount_cents < 0 {
+            TransactionNature::Expense
+        } else {
+            TransactionNature::Income
+        };
+        groups
+            .entry((key, transaction.currency, nature))
+            .or_default()
+            .push(Occurrence {
+                transaction,
+                date: transaction.date.date_naive(),
+                amount: transaction.signed_amount().abs(),
+            });
+    }
+
+    let mut series: Vec<_> = groups
+        .into_iter()
+        .filter_map(|((key, currency, nature), occurrences)| {
+            let merchant_id = match key {
+                SeriesKey::Merchant(id) => Some(id),
+                SeriesKey::Name(_) => None,
+            };
+            detect_series(occurrences, merchant_id, currency, nature, options)
+        })
+        .collect();
+    series.sort_by(|a, b| {
+        b.average_amount
+            .cmp(&a.average_amount)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    series
+}
+
+/// Turn one group into a series, if it is regular enough
+fn detect_series(
+    mut occurrences: Vec<Occurrence<'_>>,
+    merchant_id: Option<MerchantId>,
+    currency: Currency,
+    nature: TransactionNature,
+    options: &RecurringOptions,
+) -> Option<RecurringSeries> {
+    if occurrences.len() < options.min_occurrences.max(2) {
+        return None;
+    }
+
+    // One-off purchases from a merchant with a subscription should not break
+    // the series, so keep only amounts close to the median.
+    let median_amount = median(occurrences.iter().map(|o| o.amount).collect())?;
+    let tolerance = median_amount.saturating_mul(options.amount_tolerance);
+    occurrences.retain(|o| o.amount.saturating_sub(median_amount).abs() <= tolerance);
+    if occurrences.len() < options.min_occurrences.max(2) {
+        return None;
+    }
+    occurrences.sort_by_key(|o| o.date);
+
+    let intervals: Vec<u64> = occurrences
+        .windows(2)
+        .filter_map(|pair| match pair {
+            [previous, current] => Some(days_between(previous.date, current.date)),
+            _ => None,
+        })
+        .collect();
+    let cadence = Cadence::of_interval(median(intervals)?)?;
+
+    let mut flags = Vec::new();
+    let mut irregular = 0_usize;
+    for pair in occurrences.windows(2) {
+        let [previous, current] = pair else {
+            continue;
+        };
+        match cadence.periods_between(previous.date, current.date) {
+            Some(periods) => flags.extend(
+                (1..periods)
+                    .filter_map(|missed| cadence.advance(previous.date, missed))
+                    .map(|expected| SeriesFlag::Missed { expected }),
+            ),
+            None => irregular = irregular.saturating_add(1),
+        }
+        let threshold = previous
+            .amount
+            .saturating_mul(Decimal::ONE.saturating_add(options.price_increase_threshold));
+        if current.amount > threshold {
+            flags.push(SeriesFlag::PriceIncrease {
+                transaction_id: current.transaction.id,
+                date: current.date,
+                previous: previous.amount,
+                amount: current.amount,
+            });
+        }
+    }
+    // Allow the odd early or late payment, but not a mostly irregular group
+    if irregular.saturating_mul(3) > occurrences.len().saturating_sub(1) {
+        return None;
+    }
+
+    let first = occurrences.first()?;
+    let last = occurrences.last()?;
+    let next_expected_date = cadence.advance(last.date, 1)?;
+    let overdue = options.as_of.is_some_and(|as_of| {
+        as_of > next_expected_date && days_between(next_expected_date, as_of) > cadence.slack_days()
+    });
+    if overdue {
+        flags.push(SeriesFlag::Overdue {
+            expected: next_expected_date,
+        });
+    }
+
+    let total = occurrences
+        .iter()
+        .fold(Decimal::ZERO, |sum, o| sum.saturating_add(o.amount));
+    let average_amount = total
+        .checked_div(Decimal::from(occurrences.len()))?
+        .round_dp(u32::from(currency.exponent().unwrap_or(2)));
+
+    Some(RecurringSeries {
+        label: last
+            .transaction
+            .merchant
+            .as_ref()
+            .map_or_else(|| last.transaction.name.clone(), |m| m.name.clone()),
+        merchant_id,
+        nature,
+        currency,
+        cadence,
+        transaction_ids: occurrences.iter().map(|o| o.transaction.id).collect(),
+        first_date: first.date,
+        last_date: last.date,
+        next_expected_date,
+        latest_amount: last.amount,
+        average_amount,
+        amount_drift: last.amount.saturating_sub(first.amount),
+        flags,
+    })
+}
+
+/// Absolute number of days between two dates
+const fn days_between(a: NaiveDate, b: NaiveDate) -> u64 {
+    b.signed_duration_since(a).num_days().unsigned_abs()
+}
+
+/// Lower median of a list of values
+fn median<T: Ord + Copy>(mut values: Vec<T>) -> Option<T> {
+    values.sort_unstable();
+    values.get(values.len().saturating_sub(1) / 2).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::transaction;
+    use uuid::Uuid;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().expect("valid date")
+    }
+
+    #[test]
+    fn classifies_cadences_from_intervals() {
+        assert_eq!(Cadence::of_interval(6), Some(Cadence::Weekly));
+        assert_eq!(Cadence::of_interval(14), Some(Cadence::Fortnightly));
+        assert_eq!(Cadence::of_interval(28), Some(Cadence::Monthly));
+        assert_eq!(Cadence::of_interval(31), Some(Cadence::Monthly));
+        assert_eq!(Cadence::of_interval(366), Some(Cadence::Annual));
+        assert_eq!(Cadence::of_interval(21), None);
+        assert_eq!(
+            Cadence::Monthly.advance(date("2024-01-31"), 1),
+            Some(date("2024-02-29"))
+        );
+    }
+
+    #[test]
+    fn detects_monthly_subscription_with_missed_and_increased_occurrences() {
+        let transactions = [
+            transaction(1, "2024-01-15", "NETFLIX.COM", -1_799),
+            transaction(2, "2024-02-15", "Netflix.com", -1_799),
+            // March is missing
+            transaction(3, "2024-04-16", "NETFLIX.COM", -1_799),
+            transaction(4, "2024-05-15", "NETFLIX.COM", -1_999),
+            // A one-off purchase at the same name is not part of the series
+            transaction(5, "2024-05-20", "Netflix.com", -9_900),
+            transaction(6, "2024-02-03", "Corner Dairy", -450),
+            transaction(7, "2024-04-22", "Corner Dairy", -620),
+        ];
+        let options = RecurringOptions::builder()
+            .as_of(date("2024-07-01"))
+            .build();
+
+        let series = detect_recurring(&transactions, &options);
+
+        assert_eq!(series.len(), 1);
+        let netflix = series.first().expect("netflix series");
+        assert_eq!(netflix.cadence, Cadence::Monthly);
+        assert_eq!(netflix.nature, TransactionNature::Expense);
+        assert_eq!(netflix.transaction_ids.len(), 4);
+        assert_eq!(netflix.next_expected_date, date("2024-06-15"));
+        assert_eq!(netflix.amount_drift, Decimal::new(200, 2));
+        assert_eq!(
+            netflix.flags,
+            [
+                SeriesFlag::Missed {
+                    expected: date("2024-03-15")
+                },
+                SeriesFlag::PriceIncrease {
+                    transaction_id: TransactionId::new(Uuid::from_u128(4)),
+                    date: date("2024-05-15"),
+                    previous: Decimal::new(1_799, 2),
+                    amount: Decimal::new(1_999, 2),
+                },
+                SeriesFlag::Overdue {
+                    expected: date("2024-06-15")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_irregular_groups() {
+        let transactions = [
+            transaction(1, "2024-01-02", "Petrol", -8_000),
+            transaction(2, "2024-01-12", "Petrol", -8_200),
+            transaction(3, "2024-01-15", "Petrol", -7_900),
+            transaction(4, "2024-02-20", "Petrol", -8_100),
+        ];
+
+        assert!(detect_recurring(&transactions, &RecurringOptions::default()).is_empty());
+    }
+}
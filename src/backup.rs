@@ -0,0 +1,856 @@
+//! Family backups that do not depend on database dumps
+//!
+//! A [`Backup`] is a snapshot of everything the client can read from a Sure
+//! instance: accounts, categories, merchants, tags, transactions, valuations
+//! and chats. It is written as a single JSON document tagged with
+//! [`BACKUP_VERSION`], so archives written by a newer format are rejected
+//! rather than half-read.
+//!
+//! [`SureClient::backup`](crate::SureClient::backup) takes the snapshot and
+//! [`SureClient::restore`](crate::SureClient::restore) recreates it in an
+//! empty instance. Restored entities get new IDs; the [`IdMapping`] in the
+//! [`RestoreReport`] records which new ID each archived ID became, and every
+//! reference between entities is remapped through it.
+//!
+//! [`SureClient::migrate`](crate::SureClient::migrate) copies a family from
+//! one instance to another the same way, but persists the [`IdMapping`] to a
+//! file as it goes, with a journal next to it that records every entity as
+//! soon as it is created. An interrupted migration resumes where it stopped,
+//! and running it again only copies what is new.
+//!
+//! Some data cannot be restored through the API:
+//! - Sure renders only the subtype of an account's type-specific attributes,
+//!   so other attributes (e.g. a loan's interest rate) are not in the archive.
+//! - Transfers are restored as two unlinked transactions.
+//! - Chats are restored with their titles only. Their messages are kept in
+//!   the archive, but posting them again would ask the assistant to answer them.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::error::{ApiError, BackupError};
+use crate::models::account::{
+    Account, AccountClassification, AccountKind, AccountableAttributes, CreditCardAttributes,
+    DepositoryAttributes, InvestmentAttributes, LoanAttributes, OtherAssetAttributes,
+    OtherLiabilityAttributes, PropertyAttributes,
+};
+use crate::models::category::CategoryDetail;
+use crate::models::chat::ChatDetail;
+use crate::models::merchant::MerchantDetail;
+use crate::models::tag::TagDetail;
+use crate::models::transaction::{CreateTransactionData, Transaction};
+use crate::models::valuation::{Valuation, ValuationKind};
+use crate::types::{AccountId, CategoryId, MerchantId, TagId, TransactionId, ValuationId};
+
+/// Version of the archive format written by this client
+pub const BACKUP_VERSION: u64 = 1;
+
+/// A snapshot of a family's data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backup {
+    /// Archive format version; see [`BACKUP_VERSION`]
+    pub version: u64,
+    /// When the snapshot was taken
+    pub created_at: DateTime<Utc>,
+    /// Accounts
+    pub accounts: Vec<Account>,
+    /// Categories, including subcategories
+    pub categories: Vec<CategoryDetail>,
+    /// Merchants
+    pub merchants: Vec<MerchantDetail>,
+    /// Tags; missing from archives written before tags were backed up
+    #[serde(default)]
+    pub tags: Vec<TagDetail>,
+    /// Transactions
+    pub transactions: Vec<Transaction>,
+    /// Valuations
+    pub valuations: Vec<Valuation>,
+    /// Chats, with their messages
+    pub chats: Vec<ChatDetail>,
+}
+
+impl Backup {
+    /// Write the archive as JSON
+    ///
+    /// # Errors
+    /// Returns an I/O or JSON error if the archive cannot be written.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), BackupError> {
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Read an archive written by [`Backup::write`]
+    ///
+    /// # Errors
+    /// Returns `BackupError::UnsupportedVersion` if the archive was written in
+    /// a different format version, or a JSON error if it cannot be parsed.
+    pub fn read<R: Read>(reader: R) -> Result<Self, BackupError> {
+        let value: serde_json::Value = serde_json::from_reader(reader)?;
+        let found = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or_default();
+        if found != BACKUP_VERSION {
+            return Err(BackupError::UnsupportedVersion {
+                found,
+                supported: BACKUP_VERSION,
+            });
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Categories ordered so that every parent comes before its subcategories
+    pub fn categories_parents_first(&self) -> Vec<&CategoryDetail> {
+        let (mut ordered, children): (Vec<_>, Vec<_>) = self
+            .categories
+            .iter()
+            .partition(|category| category.parent.is_none());
+        ordered.extend(children);
+        ordered
+    }
+
+    /// The archived opening anchor of an account, the most recently created
+    /// if there are several
+    pub fn opening_anchor(&self, account_id: &AccountId) -> Option<&Valuation> {
+        self.valuations
+            .iter()
+            .filter(|valuation| {
+                valuation.account.id == *account_id
+                    && valuation.kind == ValuationKind::OpeningAnchor
+            })
+            .max_by_key(|valuation| valuation.created_at)
+    }
+
+    /// The balance to create `account` with, so that its archived
+    /// transactions replayed on top end at the archived balance
+    ///
+    /// This is the amount of the account's archived opening anchor. An archive
+    /// without one falls back to the archived balance less the account's
+    /// archived transactions.
+    pub fn opening_balance(&self, account: &Account) -> Decimal {
+        if let Some(anchor) = self.opening_anchor(&account.id) {
+            return anchor.amount;
+        }
+        // A liability's balance is the amount owed, so spending raised it
+        let liability = account.classification == AccountClassification::Liability;
+        self.transactions
+            .iter()
+            .filter(|transaction| transaction.account.id == account.id)
+            .fold(account.balance, |balance, transaction| {
+                let amount = transaction.signed_amount();
+                if liability {
+                    balance.saturating_add(amount)
+                } else {
+                    balance.saturating_sub(amount)
+                }
+            })
+    }
+}
+
+/// The kind of entity a backup or migration copies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    /// An account
+    Account,
+    /// A category
+    Category,
+    /// A merchant
+    Merchant,
+    /// A tag
+    Tag,
+    /// A transaction
+    Transaction,
+    /// A valuation
+    Valuation,
+    /// A chat
+    Chat,
+}
+
+/// Which new ID each copied entity received.
+///
+/// Keys are IDs in the source (or archive), values are IDs in the target.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdMapping {
+    /// Accounts
+    #[serde(default)]
+    pub accounts: HashMap<AccountId, AccountId>,
+    /// Categories
+    #[serde(default)]
+    pub categories: HashMap<CategoryId, CategoryId>,
+    /// Merchants
+    #[serde(default)]
+    pub merchants: HashMap<MerchantId, MerchantId>,
+    /// Tags
+    #[serde(default)]
+    pub tags: HashMap<TagId, TagId>,
+    /// Transactions
+    #[serde(default)]
+    pub transactions: HashMap<TransactionId, TransactionId>,
+    /// Valuations
+    #[serde(default)]
+    pub valuations: HashMap<ValuationId, ValuationId>,
+    /// Chats
+    #[serde(default)]
+    pub chats: HashMap<Uuid, Uuid>,
+}
+
+impl IdMapping {
+    /// Load a mapping saved by [`IdMapping::save`], or start an empty one if
+    /// the file does not exist yet
+    ///
+    /// # Errors
+    /// Returns `BackupError::Io` if the file exists but cannot be read, or
+    /// `BackupError::Json` if it is not a valid mapping.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BackupError> {
+        match std::fs::read(path) {
+            Ok(contents) => Ok(serde_json::from_slice(&contents)?),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Save the mapping as JSON.
+    ///
+    /// The mapping is written to a temporary file next to `path` and then
+    /// renamed over it, so an interrupted save never leaves a truncated file.
+    ///
+    /// # Errors
+    /// Returns `BackupError::Io` if the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), BackupError> {
+        let path = path.as_ref();
+        let temporary = with_suffix(path, ".tmp");
+
+        std::fs::write(&temporary, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// Record that the entity `source` was copied to `target`
+    pub(crate) fn insert(&mut self, entity: EntityKind, source: Uuid, target: Uuid) {
+        match entity {
+            EntityKind::Account => {
+                self.accounts
+                    .insert(AccountId::new(source), AccountId::new(target));
+            }
+            EntityKind::Category => {
+                self.categories
+                    .insert(CategoryId::new(source), CategoryId::new(target));
+            }
+            EntityKind::Merchant => {
+                self.merchants
+                    .insert(MerchantId::new(source), MerchantId::new(target));
+            }
+            EntityKind::Tag => {
+                self.tags.insert(TagId::new(source), TagId::new(target));
+            }
+            EntityKind::Transaction => {
+                self.transactions
+                    .insert(TransactionId::new(source), TransactionId::new(target));
+            }
+            EntityKind::Valuation => {
+                self.valuations
+                    .insert(ValuationId::new(source), ValuationId::new(target));
+            }
+            EntityKind::Chat => {
+                self.chats.insert(source, target);
+            }
+        }
+    }
+
+    /// Total number of mapped entities
+    pub fn len(&self) -> usize {
+        [
+            self.accounts.len(),
+            self.categories.len(),
+            self.merchants.len(),
+            self.tags.len(),
+            self.transactions.len(),
+            self.valuations.len(),
+            self.chats.len(),
+        ]
+        .into_iter()
+        .fold(0, usize::saturating_add)
+    }
+
+    /// Returns `true` if no entity is mapped
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of entities in `backup` that are already mapped
+    pub fn count_in(&self, backup: &Backup) -> usize {
+        let accounts = backup
+            .accounts
+            .iter()
+            .filter(|account| self.accounts.contains_key(&account.id));
+        let categories = backup
+            .categories
+            .iter()
+            .filter(|category| self.categories.contains_key(&category.id));
+        let merchants = backup
+            .merchants
+            .iter()
+            .filter(|merchant| self.merchants.contains_key(&merchant.id));
+        let tags = backup
+            .tags
+            .iter()
+            .filter(|tag| self.tags.contains_key(&tag.id));
+        let transactions = backup
+            .transactions
+            .iter()
+            .filter(|transaction| self.transactions.contains_key(&transaction.id));
+        let valuations = backup
+            .valuations
+            .iter()
+            .filter(|valuation| self.valuations.contains_key(&valuation.id));
+        let chats = backup
+            .chats
+            .iter()
+            .filter(|chat| self.chats.contains_key(&chat.id));
+
+        [
+            accounts.count(),
+            categories.count(),
+            merchants.count(),
+            tags.count(),
+            transactions.count(),
+            valuations.count(),
+            chats.count(),
+        ]
+        .into_iter()
+        .fold(0, usize::saturating_add)
+    }
+
+    /// The creation request for a transaction in the target, with its
+    /// account, category, merchant and tags remapped.
+    ///
+    /// A category, merchant or tag that was not copied is dropped rather than
+    /// failing the transaction.
+    ///
+    /// # Errors
+    /// Returns `CopyFailureReason::MissingAccount` if the transaction's
+    /// account was not copied.
+    pub fn transaction_request(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<CreateTransactionData, CopyFailureReason> {
+        let account_id = self
+            .accounts
+            .get(&transaction.account.id)
+            .copied()
+            .ok_or(CopyFailureReason::MissingAccount(transaction.account.id))?;
+        let tag_ids: Vec<TagId> = transaction
+            .tags
+            .iter()
+            .filter_map(|tag| self.tags.get(&tag.id).copied())
+            .collect();
+
+        Ok(CreateTransactionData::builder()
+            .account_id(account_id)
+            .date(transaction.date)
+            .amount(transaction.signed_amount().abs())
+            .name(transaction.name.clone())
+            .maybe_notes(transaction.notes.clone())
+            .currency(transaction.currency)
+            .maybe_category_id(
+                transaction
+                    .category
+                    .as_ref()
+                    .and_then(|category| self.categories.get(&category.id).copied()),
+            )
+            .maybe_merchant_id(
+                transaction
+                    .merchant
+                    .as_ref()
+                    .and_then(|merchant| self.merchants.get(&merchant.id).copied()),
+            )
+            .maybe_nature(transaction.classification.nature())
+            .maybe_tag_ids((!tag_ids.is_empty()).then_some(tag_ids))
+            .build())
+    }
+}
+
+/// `path` with `suffix` appended to its file name
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// One line of a [`MappingJournal`]
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalEntry {
+    entity: EntityKind,
+    source: Uuid,
+    target: Uuid,
+}
+
+/// Append-only record of the entities a migration copies, kept next to its
+/// mapping file as `<mapping file>.journal`.
+///
+/// Each entity is appended as soon as it is copied, so an interrupted
+/// migration knows about everything it created, not just what was in the
+/// mapping file when it was last saved.
+#[derive(Debug)]
+pub(crate) struct MappingJournal {
+    path: PathBuf,
+    file: Mutex<File>,
+    /// First error raised while appending. Appending never fails the request
+    /// that created the entity; the error is reported at the next checkpoint.
+    error: Mutex<Option<std::io::Error>>,
+}
+
+impl MappingJournal {
+    /// Load the mapping saved at `mapping_path` together with the entities
+    /// journaled since it was saved, and open the journal for appending.
+    ///
+    /// The combined mapping is saved straight away and the journal emptied,
+    /// so a line cut short by an interruption is never followed by new ones.
+    pub(crate) fn resume(mapping_path: &Path) -> Result<(IdMapping, Self), BackupError> {
+        let mut mapping = IdMapping::load(mapping_path)?;
+        let path = with_suffix(mapping_path, ".journal");
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+        let mut lines = contents.lines().peekable();
+        while let Some(line) = lines.next() {
+            match serde_json::from_str::<JournalEntry>(line) {
+                Ok(entry) => mapping.insert(entry.entity, entry.source, entry.target),
+                // Only the last line can be cut short by an interruption
+                Err(_) if lines.peek().is_none() => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        let file = OpenOptions::new().append(true).create(true).open(&path)?;
+        let journal = Self {
+            path,
+            file: Mutex::new(file),
+            error: Mutex::new(None),
+        };
+        journal.checkpoint(&mapping, mapping_path)?;
+        Ok((mapping, journal))
+    }
+
+    /// Append that the entity `source` was copied to `target`
+    pub(crate) fn record(&self, entity: EntityKind, source: Uuid, target: Uuid) {
+        let entry = JournalEntry {
+            entity,
+            source,
+            target,
+        };
+        let mut line = serde_json::to_vec(&entry).expect("journal entries serialise");
+        line.push(b'\n');
+        let written = lock(&self.file).write_all(&line);
+        if let Err(error) = written {
+            lock(&self.error).get_or_insert(error);
+        }
+    }
+
+    /// Save `mapping` to `mapping_path` and empty the journal, whose entries
+    /// the saved mapping now holds
+    ///
+    /// # Errors
+    /// Returns the first error raised while appending to the journal, or
+    /// `BackupError::Io` if the mapping cannot be saved or the journal emptied.
+    pub(crate) fn checkpoint(
+        &self,
+        mapping: &IdMapping,
+        mapping_path: &Path,
+    ) -> Result<(), BackupError> {
+        let error = lock(&self.error).take();
+        if let Some(error) = error {
+            return Err(error.into());
+        }
+        mapping.save(mapping_path)?;
+        lock(&self.file).set_len(0)?;
+        Ok(())
+    }
+
+    /// Save `mapping` a last time and remove the journal
+    ///
+    /// # Errors
+    /// Returns the same errors as [`checkpoint`](Self::checkpoint), or
+    /// `BackupError::Io` if the journal cannot be removed.
+    pub(crate) fn finish(
+        self,
+        mapping: &IdMapping,
+        mapping_path: &Path,
+    ) -> Result<(), BackupError> {
+        self.checkpoint(mapping, mapping_path)?;
+        let Self { path, file, .. } = self;
+        drop(file);
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Why an entity could not be copied
+#[derive(Debug, Error)]
+pub enum CopyFailureReason {
+    /// The API rejected the request that would have created the entity
+    #[error(transparent)]
+    Api(#[from] ApiError),
+
+    /// The entity belongs to an account that was not copied
+    #[error("Account {0} was not copied")]
+    MissingAccount(AccountId),
+}
+
+/// An entity that could not be copied
+#[derive(Debug)]
+pub struct CopyFailure {
+    /// Kind of entity
+    pub entity: EntityKind,
+    /// ID of the entity in the source
+    pub source_id: Uuid,
+    /// Why it was not copied
+    pub reason: CopyFailureReason,
+}
+
+/// Report for a restore
+#[derive(Debug, Default)]
+pub struct RestoreReport {
+    /// New ID of every restored entity
+    pub mapping: IdMapping,
+    /// Entities that could not be restored
+    pub failures: Vec<CopyFailure>,
+}
+
+/// Report for a migration between two instances
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    /// New ID in the target of every entity copied so far, including by
+    /// earlier runs
+    pub mapping: IdMapping,
+    /// Number of entities copied or matched by this run
+    pub copied: usize,
+    /// Number of entities skipped because an earlier run had copied them
+    pub already_copied: usize,
+    /// Entities that could not be copied
+    pub failures: Vec<CopyFailure>,
+}
+
+/// The type-specific attributes used to recreate an account.
+///
+/// Only the account kind and subtype are known, so every other attribute is
+/// left unset. A subtype the client does not recognise is dropped.
+pub fn account_attributes(account: &Account) -> AccountableAttributes {
+    let subtype = account.subtype.as_deref();
+    match account.kind {
+        AccountKind::Depository => {
+            AccountableAttributes::Depository(with_subtype::<DepositoryAttributes>(subtype))
+        }
+        AccountKind::Investment => {
+            AccountableAttributes::Investment(with_subtype::<InvestmentAttributes>(subtype))
+        }
+        AccountKind::Property => {
+            AccountableAttributes::Property(with_subtype::<PropertyAttributes>(subtype))
+        }
+        AccountKind::OtherAsset => {
+            AccountableAttributes::OtherAsset(with_subtype::<OtherAssetAttributes>(subtype))
+        }
+        AccountKind::CreditCard => {
+            AccountableAttributes::CreditCard(with_subtype::<CreditCardAttributes>(subtype))
+        }
+        AccountKind::Loan => AccountableAttributes::Loan(with_subtype::<LoanAttributes>(subtype)),
+        AccountKind::OtherLiability => {
+            AccountableAttributes::OtherLiability(with_subtype::<OtherLiabilityAttributes>(subtype))
+        }
+    }
+}
+
+/// Attributes with only `subtype` set, or with nothing set if the subtype is
+/// not one the attributes accept
+fn with_subtype<T: DeserializeOwned>(subtype: Option<&str>) -> T {
+    serde_json::from_value(serde_json::json!({ "subtype": subtype }))
+        .or_else(|_| serde_json::from_value(serde_json::json!({})))
+        .expect("account attributes have only optional fields")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::account::{DepositorySubtype, LoanSubtype};
+    use crate::test_fixtures;
+
+    fn account(kind: &str, subtype: Option<&str>) -> Account {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::from_u128(1),
+            "name": "Everyday",
+            "balance": "NZ$1,200.50",
+            "currency": "NZD",
+            "classification": "asset",
+            "account_type": kind,
+            "subtype": subtype,
+            "created_at": "2024-03-01T00:00:00Z",
+            "updated_at": "2024-03-01T00:00:00Z"
+        }))
+        .expect("valid account fixture")
+    }
+
+    fn backup() -> Backup {
+        Backup {
+            version: BACKUP_VERSION,
+            created_at: "2024-03-01T00:00:00Z".parse().expect("valid timestamp"),
+            accounts: vec![account("depository", Some("checking"))],
+            categories: Vec::new(),
+            merchants: Vec::new(),
+            tags: Vec::new(),
+            transactions: Vec::new(),
+            valuations: Vec::new(),
+            chats: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn round_trips_archive_and_rejects_other_versions() {
+        let mut output = Vec::new();
+        backup().write(&mut output).expect("writes");
+
+        let restored = Backup::read(output.as_slice()).expect("reads");
+        assert_eq!(restored.accounts, backup().accounts);
+
+        let mut older: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+        if let Some(archive) = older.as_object_mut() {
+            archive.remove("tags");
+        }
+        let restored = Backup::read(older.to_string().as_bytes()).expect("reads without tags");
+        assert!(restored.tags.is_empty());
+
+        let mut newer: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+        if let Some(archive) = newer.as_object_mut() {
+            archive.insert("version".to_string(), 2.into());
+        }
+        assert!(matches!(
+            Backup::read(newer.to_string().as_bytes()),
+            Err(BackupError::UnsupportedVersion {
+                found: 2,
+                supported: BACKUP_VERSION
+            })
+        ));
+    }
+
+    #[test]
+    fn opening_balance_and_transactions_add_up_to_the_archived_balance() {
+        let spent = |id: u128, signed_amount_cents: i64| {
+            test_fixtures::transaction_with(
+                id,
+                "2024-03-02",
+                "Transaction",
+                signed_amount_cents,
+                serde_json::json!({
+                    "account": { "id": Uuid::from_u128(1), "name": "Everyday", "account_type": "depository" }
+                }),
+            )
+        };
+        let mut archive = backup();
+        archive.transactions = vec![spent(10, -450), spent(11, 10000), spent(12, -2500)];
+        let account = archive.accounts.first().expect("one account").clone();
+
+        let opening = archive.opening_balance(&account);
+        let replayed = archive
+            .transactions
+            .iter()
+            .fold(opening, |balance, transaction| {
+                balance.saturating_add(transaction.signed_amount())
+            });
+        assert_eq!(opening, Decimal::new(113_000, 2));
+        assert_eq!(replayed, account.balance);
+
+        archive.valuations = vec![
+            serde_json::from_value(serde_json::json!({
+                "id": Uuid::from_u128(20),
+                "date": "2024-03-01",
+                "amount": "NZ$1,130.00",
+                "currency": "NZD",
+                "kind": "opening_anchor",
+                "account": { "id": Uuid::from_u128(1), "name": "Everyday", "account_type": "depository" },
+                "created_at": "2024-03-01T00:00:00Z",
+                "updated_at": "2024-03-01T00:00:00Z"
+            }))
+            .expect("valid valuation fixture"),
+        ];
+        assert_eq!(
+            archive.opening_anchor(&account.id).map(|anchor| anchor.id),
+            Some(ValuationId::new(Uuid::from_u128(20)))
+        );
+        assert_eq!(archive.opening_balance(&account), Decimal::new(113_000, 2));
+    }
+
+    #[test]
+    fn recreates_attributes_from_subtype() {
+        assert_eq!(
+            account_attributes(&account("depository", Some("checking"))),
+            AccountableAttributes::Depository(DepositoryAttributes {
+                subtype: Some(DepositorySubtype::Checking),
+                locked_attributes: None,
+            })
+        );
+        assert!(matches!(
+            account_attributes(&account("loan", Some("mortgage"))),
+            AccountableAttributes::Loan(LoanAttributes {
+                subtype: Some(LoanSubtype::Mortgage),
+                ..
+            })
+        ));
+        assert!(matches!(
+            account_attributes(&account("loan", Some("payday"))),
+            AccountableAttributes::Loan(LoanAttributes { subtype: None, .. })
+        ));
+    }
+
+    #[test]
+    fn saves_and_loads_mapping() {
+        let path = std::env::temp_dir().join(format!("sure-mapping-{}.json", Uuid::new_v4()));
+        assert!(IdMapping::load(&path).expect("missing file").is_empty());
+
+        let mut mapping = IdMapping::default();
+        mapping.chats.insert(Uuid::from_u128(1), Uuid::from_u128(2));
+        mapping.accounts.insert(
+            AccountId::new(Uuid::from_u128(3)),
+            AccountId::new(Uuid::from_u128(4)),
+        );
+        mapping.save(&path).expect("saves");
+
+        let loaded = IdMapping::load(&path).expect("loads");
+        std::fs::remove_file(&path).expect("removes");
+        assert_eq!(loaded, mapping);
+        assert_eq!(loaded.len(), 2);
+    }
+
+    #[test]
+    fn resumes_from_journal_after_interruption() {
+        let path = std::env::temp_dir().join(format!("sure-mapping-{}.json", Uuid::new_v4()));
+        let journal_path = with_suffix(&path, ".journal");
+
+        let (mapping, journal) = MappingJournal::resume(&path).expect("starts");
+        assert!(mapping.is_empty());
+        journal.record(
+            EntityKind::Account,
+            Uuid::from_u128(1),
+            Uuid::from_u128(101),
+        );
+        journal.record(
+            EntityKind::Transaction,
+            Uuid::from_u128(2),
+            Uuid::from_u128(102),
+        );
+        // Interrupted before the next checkpoint, halfway through a line
+        drop(journal);
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&journal_path)
+            .expect("journal exists");
+        file.write_all(br#"{"entity":"chat","sou"#)
+            .expect("appends");
+        drop(file);
+
+        let (mapping, journal) = MappingJournal::resume(&path).expect("resumes");
+        assert_eq!(
+            mapping.accounts.get(&AccountId::new(Uuid::from_u128(1))),
+            Some(&AccountId::new(Uuid::from_u128(101)))
+        );
+        assert_eq!(
+            mapping
+                .transactions
+                .get(&TransactionId::new(Uuid::from_u128(2))),
+            Some(&TransactionId::new(Uuid::from_u128(102)))
+        );
+        assert_eq!(mapping.len(), 2);
+        assert_eq!(IdMapping::load(&path).expect("saved on resume"), mapping);
+        assert_eq!(
+            std::fs::read_to_string(&journal_path).expect("journal exists"),
+            ""
+        );
+
+        journal.finish(&mapping, &path).expect("finishes");
+        assert!(!journal_path.exists());
+        std::fs::remove_file(&path).expect("removes");
+    }
+
+    #[test]
+    fn rejects_journal_corrupted_before_its_last_line() {
+        let path = std::env::temp_dir().join(format!("sure-mapping-{}.json", Uuid::new_v4()));
+        let journal_path = with_suffix(&path, ".journal");
+        std::fs::write(
+            &journal_path,
+            "not json\n{\"entity\":\"chat\",\"source\":\"00000000-0000-0000-0000-000000000001\",\"target\":\"00000000-0000-0000-0000-000000000002\"}\n",
+        )
+        .expect("writes");
+
+        let resumed = MappingJournal::resume(&path);
+        std::fs::remove_file(&journal_path).expect("removes");
+        assert!(matches!(resumed, Err(BackupError::Json(_))));
+    }
+
+    #[test]
+    fn remaps_transaction_references() {
+        let transaction = test_fixtures::transaction_with(
+            10,
+            "2024-03-01",
+            "Coffee",
+            -450,
+            serde_json::json!({
+                "account": { "id": Uuid::from_u128(1), "name": "Everyday", "account_type": "depository" },
+                "category": { "id": Uuid::from_u128(2), "name": "Dining", "color": "#e99537", "icon": "utensils" },
+                "merchant": { "id": Uuid::from_u128(3), "name": "Coffee Co" },
+                "tags": [
+                    { "id": Uuid::from_u128(4), "name": "Work", "color": "#805dee" },
+                    { "id": Uuid::from_u128(5), "name": "Trip", "color": "#4da568" }
+                ]
+            }),
+        );
+
+        let mut mapping = IdMapping::default();
+        assert!(matches!(
+            mapping.transaction_request(&transaction),
+            Err(CopyFailureReason::MissingAccount(_))
+        ));
+
+        mapping.accounts.insert(
+            AccountId::new(Uuid::from_u128(1)),
+            AccountId::new(Uuid::from_u128(101)),
+        );
+        mapping.categories.insert(
+            CategoryId::new(Uuid::from_u128(2)),
+            CategoryId::new(Uuid::from_u128(102)),
+        );
+        mapping.tags.insert(
+            TagId::new(Uuid::from_u128(4)),
+            TagId::new(Uuid::from_u128(104)),
+        );
+        let request = mapping
+            .transaction_request(&transaction)
+            .expect("account is mapped");
+
+        assert_eq!(request.account_id, AccountId::new(Uuid::from_u128(101)));
+        assert_eq!(
+            request.category_id,
+            Some(CategoryId::new(Uuid::from_u128(102)))
+        );
+        assert_eq!(request.merchant_id, None);
+        assert_eq!(
+            request.tag_ids,
+            Some(vec![TagId::new(Uuid::from_u128(104))])
+        );
+        assert_eq!(request.amount, Decimal::new(450, 2));
+    }
+}
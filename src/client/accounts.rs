@@ -3,12 +3,11 @@ use crate::models::account::{
     CreateAccountRequest, UpdateAccountData, UpdateAccountRequest,
 };
 use crate::models::{DeleteResponse, PaginatedResponse};
-use crate::types::AccountId;
+use crate::types::{AccountId, Patch};
 use crate::{ApiError, error::ApiResult};
 use bon::bon;
 use reqwest::Method;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
 use url::Url;
 
 use super::SureClient;
@@ -61,15 +60,59 @@ impl SureClient {
             )));
         }
 
-        let mut query_params = HashMap::new();
+        let mut query_params = Vec::new();
 
-        query_params.insert("page", page.to_string());
-        query_params.insert("per_page", per_page.to_string());
+        query_params.push(("page", page.to_string()));
+        query_params.push(("per_page", per_page.to_string()));
 
         self.execute_request(Method::GET, "/api/v1/accounts", Some(&query_params), None)
             .await
     }
 
+    /// List every account
+    ///
+    /// Walks every page of [`get_accounts`](Self::get_accounts) using the
+    /// maximum page size and collects the results.
+    ///
+    /// # Returns
+    /// All accounts, in the order the API returns them.
+    ///
+    /// # Errors
+    /// Returns `ApiError::Unauthorized` if the API key is invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let accounts = client.get_all_accounts().await?;
+    /// println!("{} accounts", accounts.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub async fn get_all_accounts(&self) -> ApiResult<Vec<AccountDetail>> {
+        let mut accounts = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let response = self
+                .get_accounts()
+                .page(page)
+                .per_page(MAX_PER_PAGE)
+                .call()
+                .await?;
+            let is_empty = response.items.accounts.is_empty();
+            accounts.extend(response.items.accounts);
+
+            if is_empty || page >= response.pagination.total_pages {
+                return Ok(accounts);
+            }
+            page = page.saturating_add(1);
+        }
+    }
+
     /// Get a specific account by ID
     ///
     /// Retrieves detailed information about a single account.
@@ -193,17 +236,17 @@ impl SureClient {
     /// Update an account
     ///
     /// Updates an existing account with new values. Only fields provided in the
-    /// request will be updated. If updating attributes, the entire attributes object
+    /// request will be updated; pass `Patch::Clear` to clear a nullable field. If updating attributes, the entire attributes object
     /// must be provided as it replaces the existing attributes.
     ///
     /// # Arguments
     /// * `id` - The account ID to update
-    /// * `name` - Optional new account name
-    /// * `balance` - Optional new balance
-    /// * `institution_name` - Optional new institution name
-    /// * `institution_domain` - Optional new institution domain
-    /// * `notes` - Optional new notes
-    /// * `attributes` - Optional new account-specific attributes (replaces existing)
+    /// * `name` - New account name
+    /// * `balance` - New balance
+    /// * `institution_name` - New institution name, or `Patch::Clear` to remove it
+    /// * `institution_domain` - New institution domain, or `Patch::Clear` to remove it
+    /// * `notes` - New notes, or `Patch::Clear` to remove them
+    /// * `attributes` - New account-specific attributes (replaces existing)
     ///
     /// # Returns
     /// The updated account.
@@ -254,9 +297,9 @@ impl SureClient {
         id: &AccountId,
         name: Option<String>,
         balance: Option<Decimal>,
-        institution_name: Option<String>,
-        institution_domain: Option<Url>,
-        notes: Option<String>,
+        #[builder(default, into)] institution_name: Patch<String>,
+        #[builder(default, into)] institution_domain: Patch<Url>,
+        #[builder(default, into)] notes: Patch<String>,
         attributes: Option<AccountableAttributes>,
     ) -> ApiResult<AccountDetail> {
         let request = UpdateAccountRequest {
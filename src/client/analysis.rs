@@ -0,0 +1,439 @@
+use bon::bon;
+use chrono::{NaiveDate, Utc};
+use iso_currency::Currency;
+
+use crate::analysis::balance::{BalanceHistory, BalanceOptions, RollDirection, balance_history};
+use crate::analysis::duplicates::{
+    DuplicateCluster, DuplicateOptions, DuplicateResolution, DuplicateResolutionReport,
+    find_duplicates,
+};
+use crate::analysis::loan::{LoanOptions, LoanProjection, LoanTerms, amortisation_schedule};
+use crate::analysis::net_worth::{NetWorth, net_worth};
+use crate::analysis::recurring::{RecurringOptions, RecurringSeries, detect_recurring};
+use crate::error::{ApiError, ApiResult};
+use crate::fx::FxRateProvider;
+use crate::models::account::{AccountKind, LoanAttributes};
+use crate::models::transaction::TransactionFilter;
+use crate::models::valuation::ValuationKind;
+use crate::types::AccountId;
+
+use super::SureClient;
+use super::batch::{BatchConfig, DEFAULT_CONCURRENCY, DEFAULT_RATE_LIMIT_RETRIES, run_batch};
+
+#[bon]
+impl SureClient {
+    /// Find recurring transactions such as subscriptions and salaries
+    ///
+    /// Fetches every transaction matching `filter` and runs
+    /// [`detect_recurring`] over them. A longer date range finds more series
+    /// and makes annual ones detectable.
+    ///
+    /// # Arguments
+    /// * `filter` - Selects the transactions to analyse (required)
+    /// * `options` - Detection thresholds (default: [`RecurringOptions::default`])
+    ///
+    /// # Returns
+    /// The detected series, largest average amount first.
+    ///
+    /// # Errors
+    /// Returns any error raised while listing the matching transactions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use chrono::{Months, Utc};
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::analysis::recurring::RecurringOptions;
+    /// use sure_client_rs::models::transaction::TransactionFilter;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let now = Utc::now();
+    /// let filter = TransactionFilter {
+    ///     start_date: now.checked_sub_months(Months::new(18)),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let series = client.find_recurring_transactions()
+    ///     .filter(&filter)
+    ///     .options(RecurringOptions::builder().as_of(now.date_naive()).build())
+    ///     .call()
+    ///     .await?;
+    ///
+    /// for s in &series {
+    ///     println!("{} {:?} {} next {}", s.label, s.cadence, s.average_amount, s.next_expected_date);
+    ///     for flag in &s.flags {
+    ///         println!("  {flag:?}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn find_recurring_transactions(
+        &self,
+        filter: &TransactionFilter,
+        #[builder(default)] options: RecurringOptions,
+    ) -> ApiResult<Vec<RecurringSeries>> {
+        let transactions = self.get_all_transactions(filter).await?;
+        Ok(detect_recurring(&transactions, &options))
+    }
+
+    /// Find transactions that were imported or synced more than once
+    ///
+    /// Fetches every transaction matching `filter` and runs
+    /// [`find_duplicates`] over them. Nothing is changed; pass each cluster
+    /// worth fixing to [`resolve_duplicates`](Self::resolve_duplicates).
+    ///
+    /// # Arguments
+    /// * `filter` - Selects the transactions to check (required)
+    /// * `options` - Matching thresholds (default: [`DuplicateOptions::default`])
+    ///
+    /// # Returns
+    /// Clusters of likely duplicates, most confident first.
+    ///
+    /// # Errors
+    /// Returns any error raised while listing the matching transactions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::analysis::duplicates::DuplicateResolution;
+    /// use sure_client_rs::models::transaction::TransactionFilter;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let clusters = client.find_duplicate_transactions()
+    ///     .filter(&TransactionFilter::default())
+    ///     .call()
+    ///     .await?;
+    ///
+    /// for cluster in clusters.iter().filter(|c| c.confidence > 0.9) {
+    ///     println!("Keeping {} ({})", cluster.survivor.name, cluster.survivor.date);
+    ///     client.resolve_duplicates()
+    ///         .cluster(cluster)
+    ///         .resolution(DuplicateResolution::Merge)
+    ///         .call()
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn find_duplicate_transactions(
+        &self,
+        filter: &TransactionFilter,
+        #[builder(default)] options: DuplicateOptions,
+    ) -> ApiResult<Vec<DuplicateCluster>> {
+        let transactions = self.get_all_transactions(filter).await?;
+        Ok(find_duplicates(&transactions, &options))
+    }
+
+    /// Resolve a cluster of duplicate transactions
+    ///
+    /// With [`DuplicateResolution::Merge`], the duplicates' notes and tags are
+    /// first merged into the survivor with
+    /// [`update_transaction`](Self::update_transaction) (see
+    /// [`DuplicateCluster::merge_update`]). The duplicates are then removed
+    /// with [`delete_transaction`](Self::delete_transaction). The survivor is
+    /// never deleted.
+    ///
+    /// # Arguments
+    /// * `cluster` - The cluster to resolve (required)
+    /// * `resolution` - Whether to merge before deleting (default: merge)
+    /// * `concurrency` - Maximum number of delete requests in flight (default: 4)
+    /// * `max_rate_limit_retries` - Retries for rate-limited delete requests (default: 3)
+    ///
+    /// # Returns
+    /// The updated survivor, if a merge was sent, and the result of each delete.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `concurrency` is zero.
+    /// Returns any error raised while updating the survivor, in which case
+    /// nothing is deleted.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::analysis::duplicates::DuplicateResolution;
+    /// use sure_client_rs::models::transaction::TransactionFilter;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let clusters = client.find_duplicate_transactions()
+    ///     .filter(&TransactionFilter::default())
+    ///     .call()
+    ///     .await?;
+    ///
+    /// if let Some(cluster) = clusters.first() {
+    ///     let report = client.resolve_duplicates()
+    ///         .cluster(cluster)
+    ///         .resolution(DuplicateResolution::Delete)
+    ///         .call()
+    ///         .await?;
+    ///     println!("Deleted all duplicates: {}", report.deleted.is_success());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn resolve_duplicates(
+        &self,
+        cluster: &DuplicateCluster,
+        #[builder(default)] resolution: DuplicateResolution,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<DuplicateResolutionReport> {
+        let merged = match resolution {
+            DuplicateResolution::Merge => {
+                let update = cluster.merge_update();
+                if update.is_empty() {
+                    None
+                } else {
+                    Some(
+                        self.submit_transaction_update(&cluster.survivor.id, update)
+                            .await?,
+                    )
+                }
+            }
+            DuplicateResolution::Delete => None,
+        };
+
+        let config = BatchConfig::new(concurrency, false, max_rate_limit_retries);
+        let deleted = run_batch(cluster.duplicate_ids(), config, |id| async move {
+            self.delete_transaction(&id).await
+        })
+        .await?;
+
+        Ok(DuplicateResolutionReport { merged, deleted })
+    }
+
+    /// Rebuild an account's daily balance history
+    ///
+    /// Fetches the account, its valuations and its transactions and runs
+    /// [`balance_history`] over them, treating liabilities as amounts owed.
+    ///
+    /// # Arguments
+    /// * `account_id` - The account to rebuild (required)
+    /// * `direction` - Whether to roll from the first or last valuation (default: forward)
+    /// * `start_date` - First day of the history (default: the earliest data)
+    /// * `end_date` - Last day of the history (default: the latest data)
+    ///
+    /// # Returns
+    /// The end-of-day balances and any valuations that disagree with them.
+    ///
+    /// # Errors
+    /// Returns `ApiError::NotFound` if the account doesn't exist.
+    /// Returns any error raised while listing the valuations or transactions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{AccountId, SureClient};
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let account_id = AccountId::new(Uuid::new_v4());
+    /// let history = client.get_balance_history()
+    ///     .account_id(&account_id)
+    ///     .call()
+    ///     .await?;
+    ///
+    /// for (date, balance) in history.iter() {
+    ///     println!("{date} {balance}");
+    /// }
+    /// for discrepancy in &history.discrepancies {
+    ///     println!("{} is off by {}", discrepancy.date, discrepancy.difference());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn get_balance_history(
+        &self,
+        account_id: &AccountId,
+        #[builder(default)] direction: RollDirection,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> ApiResult<BalanceHistory> {
+        let account = self.get_account(account_id).await?;
+        let valuations = self
+            .get_all_valuations()
+            .account_id(account_id)
+            .call()
+            .await?;
+        let filter = TransactionFilter {
+            account_id: Some(*account_id),
+            ..Default::default()
+        };
+        let transactions = self.get_all_transactions(&filter).await?;
+
+        let options = BalanceOptions::builder()
+            .direction(direction)
+            .classification(account.classification)
+            .maybe_start(start_date)
+            .maybe_end(end_date)
+            .build();
+        Ok(balance_history(&valuations, &transactions, &options))
+    }
+
+    /// Calculate net worth in a single currency
+    ///
+    /// Fetches every account and runs [`net_worth`] over them, converting
+    /// each balance with `rates`.
+    ///
+    /// # Arguments
+    /// * `currency` - Currency to report in (required)
+    /// * `rates` - Exchange rates for the accounts' currencies (required)
+    ///
+    /// # Returns
+    /// Net worth with breakdowns by account kind, institution and currency,
+    /// and any accounts whose currency had no rate.
+    ///
+    /// # Errors
+    /// Returns any error raised while listing the accounts.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use iso_currency::Currency;
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::fx::CsvRates;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let rates = CsvRates::load("rates.csv")?;
+    /// let worth = client.get_net_worth()
+    ///     .currency(Currency::NZD)
+    ///     .rates(&rates)
+    ///     .call()
+    ///     .await?;
+    ///
+    /// println!("Net worth: {} {}", worth.net(), worth.currency);
+    /// for (kind, totals) in &worth.by_kind {
+    ///     println!("  {kind}: {}", totals.net());
+    /// }
+    /// for missing in &worth.unconverted {
+    ///     println!("  No {} rate for {}", missing.currency, missing.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn get_net_worth(
+        &self,
+        currency: Currency,
+        rates: &dyn FxRateProvider,
+    ) -> ApiResult<NetWorth> {
+        let accounts = self.get_all_accounts().await?;
+        Ok(net_worth(&accounts, currency, rates))
+    }
+
+    /// Project a loan's amortisation schedule and check it against the
+    /// account
+    ///
+    /// Builds [`LoanTerms`] from `attributes` and runs
+    /// [`amortisation_schedule`], then compares the projected balance with the
+    /// balance [`get_account`](Self::get_account) reports. When `start_date`
+    /// or the initial balance is not given, the account's opening balance
+    /// valuation supplies them.
+    ///
+    /// # Arguments
+    /// * `account_id` - The loan account (required)
+    /// * `attributes` - The loan's rate, term and initial balance (required)
+    /// * `start_date` - Date the loan was drawn (default: the opening balance date)
+    /// * `as_of` - Date to compare the balances on (default: today)
+    /// * `options` - Extra repayments (default: none)
+    ///
+    /// # Returns
+    /// The projected schedule and how the actual balance compares.
+    ///
+    /// # Errors
+    /// Returns `ApiError::NotFound` if the account doesn't exist.
+    /// Returns `ApiError::InvalidParameter` if the account is not a loan, or
+    /// the terms are incomplete or invalid.
+    /// Returns any error raised while listing the account's valuations.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use rust_decimal::Decimal;
+    /// use sure_client_rs::{AccountId, SureClient};
+    /// use sure_client_rs::analysis::loan::LoanOptions;
+    /// use sure_client_rs::models::account::LoanAttributes;
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let account_id = AccountId::new(Uuid::new_v4());
+    /// let attributes = LoanAttributes {
+    ///     subtype: None,
+    ///     rate_type: None,
+    ///     interest_rate: Some(Decimal::new(579, 2)),
+    ///     term_months: Some(360),
+    ///     initial_balance: None,
+    ///     locked_attributes: None,
+    /// };
+    ///
+    /// let projection = client.project_loan()
+    ///     .account_id(&account_id)
+    ///     .attributes(&attributes)
+    ///     .options(LoanOptions::builder().extra_payment(Decimal::from(200)).build())
+    ///     .call()
+    ///     .await?;
+    ///
+    /// let schedule = &projection.schedule;
+    /// println!("Payment {} until {:?}", schedule.payment, schedule.payoff_date());
+    /// println!("Total interest {}", schedule.total_interest());
+    /// println!("Owing {} more than projected", projection.progress.difference());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn project_loan(
+        &self,
+        account_id: &AccountId,
+        attributes: &LoanAttributes,
+        start_date: Option<NaiveDate>,
+        as_of: Option<NaiveDate>,
+        #[builder(default)] options: LoanOptions,
+    ) -> ApiResult<LoanProjection> {
+        let account = self.get_account(account_id).await?;
+        if account.kind != AccountKind::Loan {
+            return Err(ApiError::InvalidParameter(format!(
+                "{} is not a loan account",
+                account.name
+            )));
+        }
+
+        let opening = if start_date.is_none() || attributes.initial_balance.is_none() {
+            self.get_all_valuations()
+                .account_id(account_id)
+                .kind(ValuationKind::OpeningAnchor)
+                .call()
+                .await?
+                .into_iter()
+                .min_by_key(|valuation| valuation.date)
+        } else {
+            None
+        };
+        let start = start_date
+            .or_else(|| opening.as_ref().map(|valuation| valuation.date))
+            .ok_or_else(|| {
+                ApiError::InvalidParameter(format!(
+                    "{} has no opening balance; pass start_date",
+                    account.name
+                ))
+            })?;
+        let attributes = LoanAttributes {
+            initial_balance: attributes
+                .initial_balance
+                .or_else(|| opening.map(|valuation| valuation.amount)),
+            ..attributes.clone()
+        };
+        let terms = LoanTerms::from_attributes(&attributes, start)
+            .map_err(|error| ApiError::InvalidParameter(error.to_string()))?;
+
+        let schedule = amortisation_schedule(&terms, &options);
+        let as_of = as_of.unwrap_or_else(|| Utc::now().date_naive());
+        let progress = schedule.compare(account.balance, as_of);
+        Ok(LoanProjection { schedule, progress })
+    }
+}
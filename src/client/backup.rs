@@ -0,0 +1,603 @@
+use bon::bon;
+use chrono::Utc;
+
+use crate::ApiError;
+use crate::backup::{
+    BACKUP_VERSION, Backup, CopyFailure, CopyFailureReason, EntityKind, IdMapping, MappingJournal,
+    RestoreReport, account_attributes,
+};
+use crate::error::{ApiResult, BackupError};
+use crate::models::batch::BatchOutcome;
+use crate::models::chat::ChatDetail;
+use crate::models::transaction::{Transaction, TransactionFilter};
+use crate::models::valuation::{Valuation, ValuationKind};
+use crate::style::{ColorPalette, Icon};
+use crate::types::AccountId;
+
+use super::SureClient;
+use super::batch::{BatchConfig, DEFAULT_CONCURRENCY, DEFAULT_RATE_LIMIT_RETRIES, run_batch};
+
+/// Page size used while listing chats; the largest the API allows
+const CHAT_PAGE_SIZE: u32 = 100;
+
+#[bon]
+impl SureClient {
+    /// Take a backup of the family
+    ///
+    /// Reads every account, category, merchant, tag, transaction, valuation
+    /// and chat into a [`Backup`]. Chats are left out when AI features are
+    /// disabled.
+    ///
+    /// # Returns
+    /// The snapshot, ready to be written with [`Backup::write`].
+    ///
+    /// # Errors
+    /// Returns `ApiError::Unauthorized` if the API key is invalid.
+    /// Returns `ApiError::Network` if a request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let backup = client.backup().call().await?;
+    ///
+    /// let file = std::io::BufWriter::new(std::fs::File::create("sure-backup.json")?);
+    /// backup.write(file)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn backup(&self) -> ApiResult<Backup> {
+        let created_at = Utc::now();
+        let accounts = self.get_all_accounts().await?;
+        let categories = self.get_all_categories().await?;
+        let merchants = self.get_all_merchants().await?;
+        let tags = self.get_all_tags().await?;
+        let transactions = self
+            .get_all_transactions(&TransactionFilter::default())
+            .await?;
+
+        let valuations = self.get_all_valuations().call().await?;
+
+        let chats = self.get_all_chat_details().await?;
+
+        Ok(Backup {
+            version: BACKUP_VERSION,
+            created_at,
+            accounts,
+            categories,
+            merchants,
+            tags,
+            transactions,
+            valuations,
+            chats,
+        })
+    }
+
+    /// Restore a backup into an empty instance
+    ///
+    /// Recreates the archived entities with new IDs, remapping the account,
+    /// category, merchant and tags of every transaction and the account of
+    /// every valuation. Categories, merchants and tags that already exist with
+    /// the same name (and parent) are reused rather than duplicated. Each
+    /// account is created with its [opening balance](Backup::opening_balance),
+    /// and the opening anchor Sure creates with it is moved to the archived
+    /// anchor's date, so the restored transactions add up to the archived
+    /// balance. Reconciliations are restored; current anchors are left to
+    /// Sure. See [`crate::backup`] for what cannot be restored.
+    ///
+    /// # Arguments
+    /// * `backup` - The archive to restore (required)
+    /// * `concurrency` - Maximum number of transaction requests in flight (default: 4)
+    /// * `max_rate_limit_retries` - Retries for rate-limited transaction requests (default: 3)
+    ///
+    /// # Returns
+    /// A report mapping every archived ID to its new ID, and listing the
+    /// entities that could not be restored.
+    ///
+    /// # Errors
+    /// Returns `BackupError::TargetNotEmpty` if the instance already has accounts.
+    /// Returns `BackupError::Api` if the existing categories, merchants or tags
+    /// cannot be listed. Failures to create individual entities are reported per entity.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::backup::Backup;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::io::BufReader::new(std::fs::File::open("sure-backup.json")?);
+    /// let backup = Backup::read(file)?;
+    ///
+    /// let report = client.restore().backup(&backup).call().await?;
+    /// for failure in &report.failures {
+    ///     println!("{:?} {} not restored: {}", failure.entity, failure.source_id, failure.reason);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn restore(
+        &self,
+        backup: &Backup,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> Result<RestoreReport, BackupError> {
+        let existing = self.get_accounts().per_page(1).call().await?;
+        if existing.pagination.total_count > 0 {
+            return Err(BackupError::TargetNotEmpty(existing.pagination.total_count));
+        }
+
+        let config = BatchConfig::new(concurrency, false, max_rate_limit_retries);
+        let mut mapping = IdMapping::default();
+        let failures = self.copy_backup(backup, &mut mapping, config).await?;
+
+        Ok(RestoreReport { mapping, failures })
+    }
+}
+
+impl SureClient {
+    /// Every chat with its messages, or none if AI features are disabled
+    async fn get_all_chat_details(&self) -> ApiResult<Vec<ChatDetail>> {
+        let mut chats = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let response = match self
+                .get_chats()
+                .page(page)
+                .per_page(CHAT_PAGE_SIZE)
+                .call()
+                .await
+            {
+                Ok(response) => response,
+                Err(ApiError::Forbidden { .. }) => return Ok(Vec::new()),
+                Err(error) => return Err(error),
+            };
+
+            for summary in &response.items.chats {
+                chats.push(self.get_chat(&summary.id).await?);
+            }
+
+            if response.items.chats.is_empty() || page >= response.pagination.total_pages {
+                return Ok(chats);
+            }
+            page = page.saturating_add(1);
+        }
+    }
+
+    /// Create every entity of `backup` that is not yet in `mapping`, recording
+    /// the new IDs in `mapping`.
+    ///
+    /// Entities already in `mapping` are skipped, so copying the same backup
+    /// again only creates what is missing.
+    pub(crate) async fn copy_backup(
+        &self,
+        backup: &Backup,
+        mapping: &mut IdMapping,
+        config: BatchConfig,
+    ) -> ApiResult<Vec<CopyFailure>> {
+        let mut failures = Vec::new();
+        self.copy_categories(backup, mapping, None, &mut failures)
+            .await?;
+        self.copy_merchants(backup, mapping, None, &mut failures)
+            .await?;
+        self.copy_tags(backup, mapping, None, &mut failures).await?;
+        self.copy_accounts(backup, mapping, None, &mut failures)
+            .await;
+        self.copy_transactions(&backup.transactions, mapping, None, config, &mut failures)
+            .await?;
+        self.copy_valuations(backup, mapping, None, &mut failures)
+            .await;
+        self.copy_chats(backup, mapping, None, &mut failures).await;
+        Ok(failures)
+    }
+
+    /// Copy categories, parents first, reusing existing categories with the
+    /// same name and parent
+    pub(crate) async fn copy_categories(
+        &self,
+        backup: &Backup,
+        mapping: &mut IdMapping,
+        journal: Option<&MappingJournal>,
+        failures: &mut Vec<CopyFailure>,
+    ) -> ApiResult<()> {
+        let mut categories = self.get_all_categories().await?;
+        let mut palette = ColorPalette::new().excluding(
+            categories
+                .iter()
+                .filter_map(|category| category.color.parse().ok()),
+        );
+        for category in backup.categories_parents_first() {
+            if mapping.categories.contains_key(&category.id) {
+                continue;
+            }
+            let parent_id = category
+                .parent
+                .as_ref()
+                .and_then(|parent| mapping.categories.get(&parent.id).copied());
+            let existing = categories.iter().find(|candidate| {
+                candidate.name == category.name
+                    && candidate.parent.as_ref().map(|parent| parent.id) == parent_id
+            });
+
+            if let Some(existing) = existing {
+                record(
+                    mapping,
+                    journal,
+                    EntityKind::Category,
+                    *category.id.as_uuid(),
+                    *existing.id.as_uuid(),
+                );
+                continue;
+            }
+            match self
+                .create_category()
+                .name(category.name.clone())
+                .color(category.color.parse().unwrap_or_else(|_| palette.pick()))
+                .lucide_icon(Icon::custom(category.icon.clone()))
+                .maybe_parent_id(parent_id)
+                .call()
+                .await
+            {
+                Ok(created) => {
+                    record(
+                        mapping,
+                        journal,
+                        EntityKind::Category,
+                        *category.id.as_uuid(),
+                        *created.id.as_uuid(),
+                    );
+                    categories.push(created);
+                }
+                Err(error) => failures.push(CopyFailure {
+                    entity: EntityKind::Category,
+                    source_id: *category.id.as_uuid(),
+                    reason: error.into(),
+                }),
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy merchants, reusing existing merchants with the same name
+    pub(crate) async fn copy_merchants(
+        &self,
+        backup: &Backup,
+        mapping: &mut IdMapping,
+        journal: Option<&MappingJournal>,
+        failures: &mut Vec<CopyFailure>,
+    ) -> ApiResult<()> {
+        let mut merchants = self.get_all_merchants().await?;
+        for merchant in &backup.merchants {
+            if mapping.merchants.contains_key(&merchant.id) {
+                continue;
+            }
+            if let Some(existing) = merchants
+                .iter()
+                .find(|candidate| candidate.name == merchant.name)
+            {
+                record(
+                    mapping,
+                    journal,
+                    EntityKind::Merchant,
+                    *merchant.id.as_uuid(),
+                    *existing.id.as_uuid(),
+                );
+                continue;
+            }
+            match self
+                .create_merchant()
+                .name(merchant.name.clone())
+                .maybe_color(
+                    merchant
+                        .color
+                        .as_deref()
+                        .and_then(|color| color.parse().ok()),
+                )
+                .call()
+                .await
+            {
+                Ok(created) => {
+                    record(
+                        mapping,
+                        journal,
+                        EntityKind::Merchant,
+                        *merchant.id.as_uuid(),
+                        *created.id.as_uuid(),
+                    );
+                    merchants.push(created);
+                }
+                Err(error) => failures.push(CopyFailure {
+                    entity: EntityKind::Merchant,
+                    source_id: *merchant.id.as_uuid(),
+                    reason: error.into(),
+                }),
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy tags, reusing existing tags with the same name
+    pub(crate) async fn copy_tags(
+        &self,
+        backup: &Backup,
+        mapping: &mut IdMapping,
+        journal: Option<&MappingJournal>,
+        failures: &mut Vec<CopyFailure>,
+    ) -> ApiResult<()> {
+        let mut tags = self.get_all_tags().await?;
+        for tag in &backup.tags {
+            if mapping.tags.contains_key(&tag.id) {
+                continue;
+            }
+            if let Some(existing) = tags.iter().find(|candidate| candidate.name == tag.name) {
+                record(
+                    mapping,
+                    journal,
+                    EntityKind::Tag,
+                    *tag.id.as_uuid(),
+                    *existing.id.as_uuid(),
+                );
+                continue;
+            }
+            match self
+                .create_tag()
+                .name(tag.name.clone())
+                .maybe_color(tag.color.parse().ok())
+                .call()
+                .await
+            {
+                Ok(created) => {
+                    record(
+                        mapping,
+                        journal,
+                        EntityKind::Tag,
+                        *tag.id.as_uuid(),
+                        *created.id.as_uuid(),
+                    );
+                    tags.push(created);
+                }
+                Err(error) => failures.push(CopyFailure {
+                    entity: EntityKind::Tag,
+                    source_id: *tag.id.as_uuid(),
+                    reason: error.into(),
+                }),
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy accounts, with the attributes [`account_attributes`] can recover
+    pub(crate) async fn copy_accounts(
+        &self,
+        backup: &Backup,
+        mapping: &mut IdMapping,
+        journal: Option<&MappingJournal>,
+        failures: &mut Vec<CopyFailure>,
+    ) {
+        for account in &backup.accounts {
+            if mapping.accounts.contains_key(&account.id) {
+                continue;
+            }
+            match self
+                .create_account()
+                .name(account.name.clone())
+                .balance(backup.opening_balance(account))
+                .attributes(account_attributes(account))
+                .currency(account.currency)
+                .maybe_institution_name(account.institution_name.clone())
+                .maybe_institution_domain(
+                    account
+                        .institution_domain
+                        .as_deref()
+                        .and_then(|domain| domain.parse().ok()),
+                )
+                .maybe_notes(account.notes.clone())
+                .call()
+                .await
+            {
+                Ok(created) => {
+                    record(
+                        mapping,
+                        journal,
+                        EntityKind::Account,
+                        *account.id.as_uuid(),
+                        *created.id.as_uuid(),
+                    );
+                }
+                Err(error) => failures.push(CopyFailure {
+                    entity: EntityKind::Account,
+                    source_id: *account.id.as_uuid(),
+                    reason: error.into(),
+                }),
+            }
+        }
+    }
+
+    /// Copy transactions in a batch, remapping their references
+    pub(crate) async fn copy_transactions(
+        &self,
+        transactions: &[Transaction],
+        mapping: &mut IdMapping,
+        journal: Option<&MappingJournal>,
+        config: BatchConfig,
+        failures: &mut Vec<CopyFailure>,
+    ) -> ApiResult<()> {
+        let mut source_ids = Vec::new();
+        let mut requests = Vec::new();
+        for transaction in transactions {
+            if mapping.transactions.contains_key(&transaction.id) {
+                continue;
+            }
+            match mapping.transaction_request(transaction) {
+                Ok(request) => {
+                    source_ids.push(transaction.id);
+                    requests.push((transaction.id, request));
+                }
+                Err(reason) => failures.push(CopyFailure {
+                    entity: EntityKind::Transaction,
+                    source_id: *transaction.id.as_uuid(),
+                    reason,
+                }),
+            }
+        }
+
+        let report = run_batch(requests, config, |(source_id, data)| async move {
+            let created = self.submit_transaction(data).await?;
+            // Journal from inside the batch, so a transaction is recorded even
+            // if the run stops before the batch finishes
+            if let Some(journal) = journal {
+                journal.record(
+                    EntityKind::Transaction,
+                    *source_id.as_uuid(),
+                    *created.id.as_uuid(),
+                );
+            }
+            Ok(created)
+        })
+        .await?;
+        for (source_id, outcome) in source_ids.into_iter().zip(report.results) {
+            match outcome {
+                BatchOutcome::Succeeded(created) => {
+                    mapping.transactions.insert(source_id, created.id);
+                }
+                BatchOutcome::Failed(error) => failures.push(CopyFailure {
+                    entity: EntityKind::Transaction,
+                    source_id: *source_id.as_uuid(),
+                    reason: error.into(),
+                }),
+                BatchOutcome::Skipped => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy reconciliations, and move the opening anchor Sure created with
+    /// each account to the archived anchor's date; current anchors are left to
+    /// Sure
+    pub(crate) async fn copy_valuations(
+        &self,
+        backup: &Backup,
+        mapping: &mut IdMapping,
+        journal: Option<&MappingJournal>,
+        failures: &mut Vec<CopyFailure>,
+    ) {
+        for valuation in &backup.valuations {
+            let copied_kind = match valuation.kind {
+                ValuationKind::Reconciliation => true,
+                ValuationKind::OpeningAnchor => backup
+                    .opening_anchor(&valuation.account.id)
+                    .is_some_and(|anchor| anchor.id == valuation.id),
+                ValuationKind::CurrentAnchor => false,
+            };
+            if !copied_kind || mapping.valuations.contains_key(&valuation.id) {
+                continue;
+            }
+            let Some(account_id) = mapping.accounts.get(&valuation.account.id).copied() else {
+                failures.push(CopyFailure {
+                    entity: EntityKind::Valuation,
+                    source_id: *valuation.id.as_uuid(),
+                    reason: CopyFailureReason::MissingAccount(valuation.account.id),
+                });
+                continue;
+            };
+            let copied = if valuation.kind == ValuationKind::OpeningAnchor {
+                self.restore_opening_anchor(account_id, valuation).await
+            } else {
+                self.create_valuation()
+                    .account_id(account_id)
+                    .amount(valuation.amount)
+                    .date(valuation.date)
+                    .maybe_notes(valuation.notes.clone())
+                    .call()
+                    .await
+            };
+            match copied {
+                Ok(created) => {
+                    record(
+                        mapping,
+                        journal,
+                        EntityKind::Valuation,
+                        *valuation.id.as_uuid(),
+                        *created.id.as_uuid(),
+                    );
+                }
+                Err(error) => failures.push(CopyFailure {
+                    entity: EntityKind::Valuation,
+                    source_id: *valuation.id.as_uuid(),
+                    reason: error.into(),
+                }),
+            }
+        }
+    }
+
+    /// Move the opening anchor Sure created with an account to the archived
+    /// anchor's date and amount
+    async fn restore_opening_anchor(
+        &self,
+        account_id: AccountId,
+        archived: &Valuation,
+    ) -> ApiResult<Valuation> {
+        let anchor = self
+            .get_all_valuations()
+            .account_id(&account_id)
+            .kind(ValuationKind::OpeningAnchor)
+            .call()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ApiError::NotFound {
+                message: format!("account {account_id} has no opening anchor"),
+            })?;
+        self.update_valuation()
+            .id(&anchor.id)
+            .amount(archived.amount)
+            .date(archived.date)
+            .maybe_notes(archived.notes.clone())
+            .call()
+            .await
+    }
+
+    /// Copy chats by title; messages are not replayed
+    pub(crate) async fn copy_chats(
+        &self,
+        backup: &Backup,
+        mapping: &mut IdMapping,
+        journal: Option<&MappingJournal>,
+        failures: &mut Vec<CopyFailure>,
+    ) {
+        for chat in &backup.chats {
+            if mapping.chats.contains_key(&chat.id) {
+                continue;
+            }
+            match self.create_chat().title(chat.title.clone()).call().await {
+                Ok(created) => {
+                    record(mapping, journal, EntityKind::Chat, chat.id, created.id);
+                }
+                Err(error) => failures.push(CopyFailure {
+                    entity: EntityKind::Chat,
+                    source_id: chat.id,
+                    reason: error.into(),
+                }),
+            }
+        }
+    }
+}
+
+/// Map `source` to `target`, appending the pair to the journal if there is one
+fn record(
+    mapping: &mut IdMapping,
+    journal: Option<&MappingJournal>,
+    entity: EntityKind,
+    source: uuid::Uuid,
+    target: uuid::Uuid,
+) {
+    mapping.insert(entity, source, target);
+    if let Some(journal) = journal {
+        journal.record(entity, source, target);
+    }
+}
@@ -0,0 +1,203 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt as _};
+
+use crate::ApiError;
+use crate::error::ApiResult;
+use crate::models::batch::{BatchOutcome, BatchReport};
+
+/// Default number of requests a batch keeps in flight
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Default number of times a rate-limited request is retried
+pub const DEFAULT_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Initial backoff after a rate-limited request; doubled on every retry
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Settings shared by all batch operations
+#[derive(Debug, Clone, Copy)]
+pub struct BatchConfig {
+    /// Maximum number of requests in flight at once
+    pub concurrency: usize,
+    /// Stop sending new requests after the first failure
+    pub stop_on_error: bool,
+    /// How many times to retry a request that was rate limited
+    pub max_rate_limit_retries: u32,
+    /// Delay before the first retry of a rate-limited request
+    pub rate_limit_backoff: Duration,
+}
+
+impl BatchConfig {
+    /// Creates a config with the default rate-limit backoff
+    pub const fn new(concurrency: usize, stop_on_error: bool, max_rate_limit_retries: u32) -> Self {
+        Self {
+            concurrency,
+            stop_on_error,
+            max_rate_limit_retries,
+            rate_limit_backoff: RATE_LIMIT_BACKOFF,
+        }
+    }
+}
+
+/// Runs `op` for every item with bounded concurrency, collecting a per-item report.
+///
+/// Results are reported in input order. Requests rejected with
+/// `ApiError::RateLimited` are retried with exponential backoff. When
+/// `stop_on_error` is set, items that have not started by the time a failure is
+/// observed are reported as [`BatchOutcome::Skipped`]; requests already in
+/// flight are allowed to finish.
+pub async fn run_batch<I, T, F, Fut>(
+    items: Vec<I>,
+    config: BatchConfig,
+    op: F,
+) -> ApiResult<BatchReport<T>>
+where
+    I: Clone,
+    F: Fn(I) -> Fut,
+    Fut: Future<Output = ApiResult<T>>,
+{
+    if config.concurrency == 0 {
+        return Err(ApiError::InvalidParameter(
+            "concurrency must be at least 1".to_string(),
+        ));
+    }
+
+    let halted = AtomicBool::new(false);
+    let halted = &halted;
+    let op = &op;
+
+    let results = stream::iter(items)
+        .map(|item| async move {
+            if halted.load(Ordering::SeqCst) {
+                return BatchOutcome::Skipped;
+            }
+
+            match with_rate_limit_retry(&config, || op(item.clone())).await {
+                Ok(value) => BatchOutcome::Succeeded(value),
+                Err(error) => {
+                    if config.stop_on_error {
+                        halted.store(true, Ordering::SeqCst);
+                    }
+                    BatchOutcome::Failed(error)
+                }
+            }
+        })
+        .buffered(config.concurrency)
+        .collect()
+        .await;
+
+    Ok(BatchReport { results })
+}
+
+/// Calls `op`, retrying with exponential backoff while the API reports rate limiting
+async fn with_rate_limit_retry<T, F, Fut>(config: &BatchConfig, op: F) -> ApiResult<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = ApiResult<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Err(ApiError::RateLimited { .. }) if attempt < config.max_rate_limit_retries => {
+                let delay = config
+                    .rate_limit_backoff
+                    .saturating_mul(2_u32.saturating_pow(attempt));
+                tokio::time::sleep(delay).await;
+                attempt = attempt.saturating_add(1);
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    fn config(concurrency: usize, stop_on_error: bool) -> BatchConfig {
+        BatchConfig {
+            concurrency,
+            stop_on_error,
+            max_rate_limit_retries: 2,
+            rate_limit_backoff: Duration::ZERO,
+        }
+    }
+
+    fn fail_on_odd(n: u32) -> ApiResult<u32> {
+        if n % 2 == 1 {
+            Err(ApiError::InvalidParameter(format!("odd: {n}")))
+        } else {
+            Ok(n)
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_results_in_input_order() {
+        let report = run_batch(vec![0, 1, 2, 3], config(3, false), |n| async move {
+            fail_on_odd(n)
+        })
+        .await
+        .expect("valid config");
+
+        assert_eq!(report.results.len(), 4);
+        assert_eq!(
+            report.succeeded().map(|(i, _)| i).collect::<Vec<_>>(),
+            [0, 2]
+        );
+        assert_eq!(report.failed().map(|(i, _)| i).collect::<Vec<_>>(), [1, 3]);
+        assert!(!report.is_success(), "odd items should have failed");
+    }
+
+    #[tokio::test]
+    async fn stop_on_error_skips_remaining_items() {
+        let report = run_batch(vec![0, 1, 2, 4, 6], config(1, true), |n| async move {
+            fail_on_odd(n)
+        })
+        .await
+        .expect("valid config");
+
+        assert!(
+            report
+                .results
+                .first()
+                .is_some_and(BatchOutcome::is_succeeded)
+        );
+        assert!(report.results.get(1).is_some_and(BatchOutcome::is_failed));
+        assert_eq!(report.skipped_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limited_requests() {
+        let calls = AtomicU32::new(0);
+        let report = run_batch(vec![()], config(1, false), |()| {
+            let attempt = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt < 2 {
+                    Err(ApiError::RateLimited {
+                        message: "slow down".to_string(),
+                    })
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await
+        .expect("valid config");
+
+        assert!(report.is_success(), "third attempt should succeed");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn rejects_zero_concurrency() {
+        let result = run_batch(vec![1], config(0, false), |n| async move { fail_on_odd(n) }).await;
+        assert!(
+            matches!(result, Err(ApiError::InvalidParameter(_))),
+            "zero concurrency must be rejected"
+        );
+    }
+}
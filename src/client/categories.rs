@@ -4,14 +4,17 @@ use reqwest::Method;
 use crate::ApiError;
 use crate::error::ApiResult;
 use crate::models::category::{
-    CategoryCollection, CategoryDetail, CreateCategoryData, CreateCategoryRequest,
-    UpdateCategoryData, UpdateCategoryRequest,
+    CategoryCollection, CategoryDetail, CategoryMergePlan, CategoryMergeReport, CreateCategoryData,
+    CreateCategoryRequest, UpdateCategoryData, UpdateCategoryRequest,
 };
+use crate::models::category_tree::{CategoryTree, parse_category_path};
+use crate::models::transaction::{TransactionFilter, UpdateTransactionData};
 use crate::models::{DeleteResponse, PaginatedResponse};
-use crate::types::CategoryId;
-use std::collections::HashMap;
+use crate::style::{ColorPalette, HexColor, Icon};
+use crate::types::{CategoryId, Patch};
 
 use super::SureClient;
+use super::batch::{BatchConfig, DEFAULT_CONCURRENCY, DEFAULT_RATE_LIMIT_RETRIES, run_batch};
 
 const MAX_PER_PAGE: u32 = 100;
 
@@ -42,7 +45,7 @@ impl SureClient {
         #[builder(default = false)] roots_only: bool,
         parent_id: Option<&CategoryId>,
     ) -> ApiResult<PaginatedResponse<CategoryCollection>> {
-        let mut query_params = HashMap::new();
+        let mut query_params = Vec::new();
 
         if per_page > MAX_PER_PAGE {
             return Err(ApiError::InvalidParameter(format!(
@@ -50,12 +53,12 @@ impl SureClient {
             )));
         }
 
-        query_params.insert("page", page.to_string());
-        query_params.insert("per_page", per_page.to_string());
-        query_params.insert("roots_only", roots_only.to_string());
+        query_params.push(("page", page.to_string()));
+        query_params.push(("per_page", per_page.to_string()));
+        query_params.push(("roots_only", roots_only.to_string()));
 
         if let Some(parent_id) = parent_id {
-            query_params.insert("parent_id", parent_id.to_string());
+            query_params.push(("parent_id", parent_id.to_string()));
         }
 
         self.execute_request(Method::GET, "/api/v1/categories", Some(&query_params), None)
@@ -86,6 +89,50 @@ impl SureClient {
         )
         .await
     }
+
+    /// List every category
+    ///
+    /// Walks every page of [`get_categories`](Self::get_categories) and
+    /// collects the results, including subcategories.
+    ///
+    /// # Returns
+    /// All categories, in the order the API returns them.
+    ///
+    /// # Errors
+    /// Returns `ApiError::Unauthorized` if the bearer token is invalid or expired.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let categories = client.get_all_categories().await?;
+    /// println!("{} categories", categories.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub async fn get_all_categories(&self) -> ApiResult<Vec<CategoryDetail>> {
+        let mut categories = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let response = self
+                .get_categories()
+                .page(page)
+                .per_page(MAX_PER_PAGE)
+                .call()
+                .await?;
+            let is_empty = response.items.categories.is_empty();
+            categories.extend(response.items.categories);
+
+            if is_empty || page >= response.pagination.total_pages {
+                return Ok(categories);
+            }
+            page = page.saturating_add(1);
+        }
+    }
 }
 
 #[bon]
@@ -112,8 +159,8 @@ impl SureClient {
     /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
     /// let category = client.create_category()
     ///     .name("Groceries".to_string())
-    ///     .color("#FF5733".to_string())
-    ///     .lucide_icon("shopping-cart".to_string())
+    ///     .color("#FF5733".parse()?)
+    ///     .lucide_icon("shopping-cart".parse()?)
     ///     .call()
     ///     .await?;
     ///
@@ -125,8 +172,8 @@ impl SureClient {
     pub async fn create_category(
         &self,
         name: String,
-        color: String,
-        lucide_icon: Option<String>,
+        color: HexColor,
+        lucide_icon: Option<Icon>,
         parent_id: Option<CategoryId>,
     ) -> ApiResult<CategoryDetail> {
         let request = CreateCategoryRequest {
@@ -150,11 +197,15 @@ impl SureClient {
     /// Update a category
     ///
     /// Updates an existing category with new values. Only fields provided in the
-    /// request will be updated.
+    /// request will be updated. Passing `Patch::Clear` as `parent_id` turns a
+    /// subcategory back into a root category.
     ///
     /// # Arguments
     /// * `id` - The category ID to update
-    /// * `request` - The category update request containing fields to update
+    /// * `name` - Updated category name
+    /// * `color` - Updated color (hex code)
+    /// * `lucide_icon` - Updated Lucide icon name
+    /// * `parent_id` - New parent category, or `Patch::Clear` to make it a root category
     ///
     /// # Returns
     /// The updated category.
@@ -167,7 +218,7 @@ impl SureClient {
     ///
     /// # Example
     /// ```no_run
-    /// use sure_client_rs::{SureClient, BearerToken, CategoryId};
+    /// use sure_client_rs::{SureClient, BearerToken, HexColor, CategoryId};
     /// use uuid::Uuid;
     ///
     /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
@@ -176,7 +227,7 @@ impl SureClient {
     /// let category = client.update_category()
     ///     .id(&category_id)
     ///     .name("Updated Category Name".to_string())
-    ///     .color("#00FF00".to_string())
+    ///     .color("#00FF00".parse::<HexColor>()?)
     ///     .call()
     ///     .await?;
     ///
@@ -189,9 +240,9 @@ impl SureClient {
         &self,
         id: &CategoryId,
         name: Option<String>,
-        color: Option<String>,
-        lucide_icon: Option<String>,
-        parent_id: Option<CategoryId>,
+        color: Option<HexColor>,
+        lucide_icon: Option<Icon>,
+        #[builder(default, into)] parent_id: Patch<CategoryId>,
     ) -> ApiResult<CategoryDetail> {
         let request = UpdateCategoryRequest {
             category: UpdateCategoryData {
@@ -249,3 +300,289 @@ impl SureClient {
         .await
     }
 }
+
+#[bon]
+impl SureClient {
+    /// Load every category into a [`CategoryTree`]
+    ///
+    /// Walks every page of [`get_categories`](Self::get_categories) and links
+    /// the categories into their hierarchy.
+    ///
+    /// # Returns
+    /// The family's category hierarchy.
+    ///
+    /// # Errors
+    /// Returns any error raised while listing the categories.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let tree = client.get_category_tree().await?;
+    /// for node in tree.walk() {
+    ///     println!("{}{}", "  ".repeat(node.depth), node.category.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub async fn get_category_tree(&self) -> ApiResult<CategoryTree> {
+        Ok(CategoryTree::new(self.get_all_categories().await?))
+    }
+
+    /// Resolve a category path, creating any missing levels
+    ///
+    /// Each level of `path` (such as `"Food > Groceries"`) is looked up in
+    /// `tree` under the level before it. Missing levels are created with
+    /// [`create_category`](Self::create_category), parented to the level above,
+    /// and added to `tree`, so later calls find them without another request.
+    ///
+    /// # Arguments
+    /// * `tree` - Cached categories, from [`get_category_tree`](Self::get_category_tree) (required)
+    /// * `path` - Category names separated by `>` (required)
+    /// * `color` - Colour for created categories (default: the next
+    ///   [`ColorPalette`] colour not already used in `tree`)
+    ///
+    /// # Returns
+    /// The ID of the last category in the path.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `path` contains no names.
+    /// Returns any error raised while creating a category; levels created
+    /// before the error stay in `tree`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut tree = client.get_category_tree().await?;
+    ///
+    /// let groceries = client.ensure_category_path()
+    ///     .tree(&mut tree)
+    ///     .path("Food > Groceries")
+    ///     .call()
+    ///     .await?;
+    /// assert_eq!(tree.path(&groceries).as_deref(), Some("Food > Groceries"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn ensure_category_path(
+        &self,
+        tree: &mut CategoryTree,
+        path: &str,
+        color: Option<HexColor>,
+    ) -> ApiResult<CategoryId> {
+        let names = parse_category_path(path);
+        self.ensure_category_names(tree, &names, color, &mut Vec::new())
+            .await?
+            .ok_or_else(|| ApiError::InvalidParameter(format!("{path:?} is not a category path")))
+    }
+}
+
+#[bon]
+impl SureClient {
+    /// Merge one category into another, then delete it
+    ///
+    /// Every transaction in `from` is moved into `into` with
+    /// [`update_transaction`](Self::update_transaction). Subcategories of
+    /// `from` are moved under `into`, or made root categories when
+    /// `move_subcategories` is off. Sure allows only two category levels, so
+    /// they are also made root categories when `into` is itself a
+    /// subcategory; the plan records which. `from` is deleted with
+    /// [`delete_category`](Self::delete_category) only once every transaction
+    /// and subcategory has moved, so nothing is orphaned.
+    ///
+    /// The merge is resumable: if any request fails, `from` is kept, and
+    /// running the merge again only moves what is still left in it. With
+    /// `dry_run` set, nothing is sent and the report only contains the plan.
+    ///
+    /// # Arguments
+    /// * `from` - The category to merge away (required)
+    /// * `into` - The category that receives its transactions (required)
+    /// * `move_subcategories` - Move subcategories under `into` if it is a root category (default: true)
+    /// * `dry_run` - Only report what would change (default: false)
+    /// * `concurrency` - Maximum number of requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new requests after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited requests (default: 3)
+    ///
+    /// # Returns
+    /// The plan, plus the results of each move and the delete unless this
+    /// was a dry run.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `from` and `into` are the same,
+    /// if `into` is a subcategory of `from`, or if `concurrency` is zero.
+    /// Returns `ApiError::NotFound` if either category does not exist.
+    /// Returns any error raised while listing categories or transactions, or
+    /// while deleting `from`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let tree = client.get_category_tree().await?;
+    /// let (Some(from), Some(into)) = (tree.find_path("Supermarket"), tree.find_path("Food > Groceries")) else {
+    ///     return Ok(());
+    /// };
+    ///
+    /// let preview = client.merge_categories().from(from).into(into).dry_run(true).call().await?;
+    /// println!("Would {}", preview.plan);
+    ///
+    /// let report = client.merge_categories().from(from).into(into).call().await?;
+    /// if !report.is_complete() {
+    ///     println!("Some moves failed; run the merge again to finish");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn merge_categories(
+        &self,
+        from: CategoryId,
+        into: CategoryId,
+        #[builder(default = true)] move_subcategories: bool,
+        #[builder(default = false)] dry_run: bool,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<CategoryMergeReport> {
+        if from == into {
+            return Err(ApiError::InvalidParameter(
+                "cannot merge a category into itself".to_string(),
+            ));
+        }
+        let tree = self.get_category_tree().await?;
+        for id in [&from, &into] {
+            if tree.get(id).is_none() {
+                return Err(ApiError::NotFound {
+                    message: format!("category {id} does not exist"),
+                });
+            }
+        }
+        if tree
+            .ancestry(&into)
+            .iter()
+            .any(|category| category.id == from)
+        {
+            return Err(ApiError::InvalidParameter(
+                "cannot merge a category into one of its subcategories".to_string(),
+            ));
+        }
+
+        let filter = TransactionFilter::builder().category_id(from).build();
+        // The category filter may include subcategories, whose transactions stay put
+        let transaction_ids = self
+            .get_all_transactions(&filter)
+            .await?
+            .into_iter()
+            .filter(|transaction| {
+                transaction
+                    .category
+                    .as_ref()
+                    .is_some_and(|category| category.id == from)
+            })
+            .map(|transaction| transaction.id)
+            .collect();
+        let plan = CategoryMergePlan {
+            from,
+            into,
+            transaction_ids,
+            subcategory_ids: tree.children(&from).iter().map(|c| c.id).collect(),
+            // A subcategory cannot have subcategories of its own
+            move_subcategories: move_subcategories
+                && tree
+                    .get(&into)
+                    .is_some_and(|category| category.parent.is_none()),
+        };
+
+        if dry_run {
+            return Ok(CategoryMergeReport {
+                plan,
+                transactions: None,
+                subcategories: None,
+                deleted: None,
+            });
+        }
+
+        let config = BatchConfig::new(concurrency, stop_on_error, max_rate_limit_retries);
+        let transactions = run_batch(plan.transaction_ids.clone(), config, |id| async move {
+            let update = UpdateTransactionData::builder().category_id(into).build();
+            self.submit_transaction_update(&id, update).await
+        })
+        .await?;
+        let parent_id = if plan.move_subcategories {
+            Patch::Set(into)
+        } else {
+            Patch::Clear
+        };
+        let subcategories = run_batch(plan.subcategory_ids.clone(), config, |id| {
+            let parent_id = parent_id.clone();
+            async move {
+                self.update_category()
+                    .id(&id)
+                    .parent_id(parent_id)
+                    .call()
+                    .await
+            }
+        })
+        .await?;
+
+        let deleted = if transactions.is_success() && subcategories.is_success() {
+            Some(self.delete_category(&from).await?)
+        } else {
+            None
+        };
+
+        Ok(CategoryMergeReport {
+            plan,
+            transactions: Some(transactions),
+            subcategories: Some(subcategories),
+            deleted,
+        })
+    }
+}
+
+impl SureClient {
+    /// Resolve a path of category names, creating missing levels
+    ///
+    /// Created categories are added to `tree` and appended to `created`. They
+    /// are given `color`, or else palette colours not yet used in `tree`.
+    /// Returns `None` if `names` is empty.
+    pub(crate) async fn ensure_category_names(
+        &self,
+        tree: &mut CategoryTree,
+        names: &[&str],
+        color: Option<HexColor>,
+        created: &mut Vec<CategoryDetail>,
+    ) -> ApiResult<Option<CategoryId>> {
+        let mut palette = ColorPalette::new().excluding(
+            tree.walk()
+                .iter()
+                .filter_map(|node| node.category.color.parse().ok()),
+        );
+        let mut current: Option<CategoryId> = None;
+        for name in names {
+            if let Some(existing) = tree.find_child(current.as_ref(), name) {
+                current = Some(existing.id);
+                continue;
+            }
+            let category = self
+                .create_category()
+                .name((*name).to_string())
+                .color(color.unwrap_or_else(|| palette.pick()))
+                .maybe_parent_id(current)
+                .call()
+                .await?;
+            current = Some(category.id);
+            tree.insert(category.clone());
+            created.push(category);
+        }
+        Ok(current)
+    }
+}
@@ -7,7 +7,6 @@ use crate::models::chat::{
 };
 use bon::bon;
 use reqwest::Method;
-use std::collections::HashMap;
 use uuid::Uuid;
 
 use super::SureClient;
@@ -61,10 +60,10 @@ impl SureClient {
             )));
         }
 
-        let mut query_params = HashMap::new();
+        let mut query_params = Vec::new();
 
-        query_params.insert("page", page.to_string());
-        query_params.insert("per_page", per_page.to_string());
+        query_params.push(("page", page.to_string()));
+        query_params.push(("per_page", per_page.to_string()));
 
         self.execute_request(Method::GET, "/api/v1/chats", Some(&query_params), None)
             .await
@@ -2,33 +2,43 @@ use crate::error::{ApiError, ApiResult};
 use crate::models::ErrorResponse;
 use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use reqwest::{Method, Response, StatusCode, header::HeaderMap, header::HeaderValue};
-use std::collections::HashMap;
 
 use super::SureClient;
 
 impl SureClient {
+    /// Build the URL for a request
+    ///
+    /// Query parameters are kept in order and may repeat, as array parameters
+    /// such as `merchant_ids[]` do.
+    fn request_url(
+        &self,
+        path: &str,
+        query_params: Option<&[(&str, String)]>,
+    ) -> ApiResult<reqwest::Url> {
+        // Strip leading / from path if present
+        let path = path.trim_start_matches('/');
+        let url = format!("{}{}", self.base_url, path);
+
+        match query_params {
+            Some(params) => reqwest::Url::parse_with_params(&url, params),
+            None => reqwest::Url::parse(&url),
+        }
+        .map_err(ApiError::UrlParse)
+    }
+
     /// Core request execution logic
     pub(crate) async fn execute_request<T>(
         &self,
         method: Method,
         path: &str,
-        query_params: Option<&HashMap<&str, String>>,
+        query_params: Option<&[(&str, String)]>,
         body: Option<String>,
     ) -> ApiResult<T>
     where
         T: serde::de::DeserializeOwned,
     {
-        // Strip leading / from path if present
-        let path = path.trim_start_matches('/');
-
         // 1. Build URL
-        let url = if let Some(params) = query_params {
-            reqwest::Url::parse_with_params(&format!("{}{}", self.base_url, path), params)
-                .map_err(ApiError::UrlParse)?
-        } else {
-            reqwest::Url::parse(&format!("{}{}", self.base_url, path))
-                .map_err(ApiError::UrlParse)?
-        };
+        let url = self.request_url(path, query_params)?;
 
         //println!("Request URL: {}", url); // Debugging line to print the URL
 
@@ -123,3 +133,34 @@ impl SureClient {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Auth;
+
+    #[test]
+    fn request_url_keeps_repeated_query_parameters() {
+        let client = SureClient::new(
+            reqwest::Client::new(),
+            Auth::api_key("key"),
+            "http://localhost:3000".parse().expect("valid base URL"),
+        );
+        let params = [
+            ("page", "1".to_string()),
+            ("merchant_ids[]", "a".to_string()),
+            ("merchant_ids[]", "b".to_string()),
+        ];
+
+        let url = client
+            .request_url("/api/v1/transactions", Some(&params))
+            .expect("valid URL");
+
+        let merchant_ids: Vec<_> = url
+            .query_pairs()
+            .filter(|(key, _)| key == "merchant_ids[]")
+            .map(|(_, value)| value.into_owned())
+            .collect();
+        assert_eq!(merchant_ids, ["a", "b"]);
+    }
+}
@@ -0,0 +1,195 @@
+use std::io::Write;
+
+use bon::bon;
+
+use crate::error::ExportError;
+use crate::export::journal::{JournalFormat, JournalWriter};
+use crate::export::{
+    AmountStyle, DEFAULT_DATE_FORMAT, ExportFormat, ExportOptions, TransactionWriter,
+};
+use crate::models::transaction::TransactionFilter;
+use crate::models::valuation::Valuation;
+
+use super::SureClient;
+
+/// Page size used while exporting; the largest the API allows
+const EXPORT_PAGE_SIZE: u32 = 100;
+
+#[bon]
+impl SureClient {
+    /// Export every transaction matching a filter
+    ///
+    /// Fetches matching transactions page by page and writes each page as soon
+    /// as it arrives, flushing the writer between pages, so memory use does not
+    /// grow with the size of the export. See [`crate::export`] for the columns.
+    ///
+    /// # Arguments
+    /// * `filter` - Selects the transactions to export (required)
+    /// * `writer` - Destination of the export, e.g. a file (required)
+    /// * `format` - CSV or JSON Lines (default: CSV)
+    /// * `amount_style` - Signed or unsigned amounts (default: signed)
+    /// * `date_format` - `chrono` format of the date column (default: `%Y-%m-%d`)
+    ///
+    /// # Returns
+    /// The number of transactions written.
+    ///
+    /// # Errors
+    /// Returns `ExportError::InvalidDateFormat` if `date_format` is invalid.
+    /// Returns `ExportError::Api` if a page cannot be fetched; rows from
+    /// earlier pages have already been written by then.
+    /// Returns an I/O, CSV or JSON error if a row cannot be written.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::export::{AmountStyle, ExportFormat};
+    /// use sure_client_rs::models::transaction::TransactionFilter;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let filter = TransactionFilter::builder()
+    ///     .start_date(Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap())
+    ///     .end_date(Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap())
+    ///     .build();
+    ///
+    /// // A spreadsheet for the accountants
+    /// let file = std::io::BufWriter::new(std::fs::File::create("fy2025.csv")?);
+    /// let count = client.export_transactions()
+    ///     .filter(&filter)
+    ///     .writer(file)
+    ///     .amount_style(AmountStyle::Unsigned)
+    ///     .date_format("%d/%m/%Y")
+    ///     .call()
+    ///     .await?;
+    ///
+    /// // JSON Lines for the data team
+    /// let file = std::io::BufWriter::new(std::fs::File::create("fy2025.jsonl")?);
+    /// client.export_transactions()
+    ///     .filter(&filter)
+    ///     .writer(file)
+    ///     .format(ExportFormat::JsonLines)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn export_transactions<W: Write>(
+        &self,
+        filter: &TransactionFilter,
+        writer: W,
+        #[builder(default)] format: ExportFormat,
+        #[builder(default)] amount_style: AmountStyle,
+        #[builder(default = DEFAULT_DATE_FORMAT.to_string(), into)] date_format: String,
+    ) -> Result<usize, ExportError> {
+        let options = ExportOptions {
+            format,
+            amount_style,
+            date_format,
+        };
+        let mut output = TransactionWriter::new(writer, options)?;
+        let mut page = 1;
+
+        loop {
+            let response = self
+                .get_transactions_page(filter, page, EXPORT_PAGE_SIZE)
+                .await?;
+            for transaction in &response.items.transactions {
+                output.write(transaction)?;
+            }
+            output.flush()?;
+
+            if response.items.transactions.is_empty() || page >= response.pagination.total_pages {
+                return Ok(output.written());
+            }
+            page = page.saturating_add(1);
+        }
+    }
+
+    /// Export transactions as a plain-text accounting journal
+    ///
+    /// Fetches every account and category and every transaction matching the
+    /// filter, then writes them in date order as a ledger, hledger or
+    /// beancount journal. Each valuation is written after the transactions of
+    /// its date: an opening anchor as an opening balance, any other valuation
+    /// as a balance assertion. See [`crate::export::journal`] for how
+    /// accounts, categories and transfers are mapped.
+    ///
+    /// Unlike [`export_transactions`](Self::export_transactions), the
+    /// transactions are held in memory so they can be sorted, which balance
+    /// assertions need.
+    ///
+    /// # Arguments
+    /// * `filter` - Selects the transactions to export (required)
+    /// * `writer` - Destination of the journal, e.g. a file (required)
+    /// * `format` - Journal dialect (default: ledger)
+    /// * `valuations` - Valuations to write as opening balances and balance assertions (default: none)
+    ///
+    /// # Returns
+    /// The number of entries written: transactions, with each transfer counted
+    /// once, plus valuations.
+    ///
+    /// # Errors
+    /// Returns `ExportError::Api` if accounts, categories or transactions
+    /// cannot be fetched; nothing has been written by then.
+    /// Returns an I/O error if an entry cannot be written.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::export::journal::JournalFormat;
+    /// use sure_client_rs::models::transaction::TransactionFilter;
+    /// use sure_client_rs::ValuationId;
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let statement = ValuationId::new(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap());
+    /// let valuations = vec![client.get_valuation(&statement).await?];
+    ///
+    /// let file = std::io::BufWriter::new(std::fs::File::create("sure.beancount")?);
+    /// let entries = client.export_journal()
+    ///     .filter(&TransactionFilter::default())
+    ///     .writer(file)
+    ///     .format(JournalFormat::Beancount)
+    ///     .valuations(&valuations)
+    ///     .call()
+    ///     .await?;
+    ///
+    /// println!("Wrote {entries} entries");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn export_journal<W: Write>(
+        &self,
+        filter: &TransactionFilter,
+        writer: W,
+        #[builder(default)] format: JournalFormat,
+        #[builder(default)] valuations: &[Valuation],
+    ) -> Result<usize, ExportError> {
+        let accounts = self.get_all_accounts().await?;
+        let categories = self.get_category_tree().await?;
+        let mut transactions = self.get_all_transactions(filter).await?;
+        transactions.sort_by_key(|transaction| transaction.date);
+        let mut valuations: Vec<&Valuation> = valuations.iter().collect();
+        valuations.sort_by_key(|valuation| valuation.date);
+
+        let mut output = JournalWriter::new(writer, format, &accounts).with_categories(&categories);
+        let mut pending = valuations.into_iter().peekable();
+        for transaction in &transactions {
+            let date = transaction.date.date_naive();
+            while let Some(valuation) = pending.next_if(|valuation| valuation.date < date) {
+                output.write_valuation(valuation)?;
+            }
+            output.write_transaction(transaction)?;
+        }
+        for valuation in pending {
+            output.write_valuation(valuation)?;
+        }
+        output.flush()?;
+
+        Ok(output.written())
+    }
+}
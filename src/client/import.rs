@@ -0,0 +1,532 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use bon::bon;
+
+use crate::error::ApiResult;
+use crate::import::ofx::{
+    OfxBalance, OfxImportReport, OfxImportRow, OfxMatch, OfxStatement, OfxValuation,
+    fit_id_from_notes,
+};
+use crate::import::qif::{QifImportReport, QifSection};
+use crate::import::{ImportMatch, ImportReport, ImportRow, MatchAction, classify_candidates};
+use crate::models::category::CategoryDetail;
+use crate::models::transaction::{
+    CreateTransactionData, Transaction, TransactionFilter, UpdateTransactionData,
+};
+use crate::types::{AccountId, CategoryId, Patch, TransactionId};
+
+use super::SureClient;
+use super::batch::{BatchConfig, DEFAULT_CONCURRENCY, DEFAULT_RATE_LIMIT_RETRIES, run_batch};
+
+/// A write request produced by an import
+#[derive(Debug, Clone)]
+enum ImportWrite {
+    Create(CreateTransactionData),
+    Update(TransactionId, UpdateTransactionData),
+}
+
+#[bon]
+impl SureClient {
+    /// Import transactions without creating duplicates
+    ///
+    /// Fetches the transactions already stored for the candidates' accounts and
+    /// date window, and compares them by account, date, signed amount and
+    /// normalised name (see [`TransactionFingerprint`](crate::import::TransactionFingerprint)).
+    /// Rows without a match are created. Matched rows are skipped, or with
+    /// [`MatchAction::Update`] have their notes, category, merchant and tags
+    /// copied onto the stored transaction. Rows that share an account, date and
+    /// amount with a stored transaction but have a different name are reported
+    /// as conflicts and never written.
+    ///
+    /// # Arguments
+    /// * `transactions` - Candidate transactions to import (required)
+    /// * `on_match` - What to do with rows matching a stored transaction (default: skip)
+    /// * `dry_run` - Only classify the rows, without sending any write requests (default: false)
+    /// * `concurrency` - Maximum number of requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new requests after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited requests (default: 3)
+    ///
+    /// # Returns
+    /// A report with one row per candidate, in input order.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `concurrency` is zero.
+    /// Returns any error raised while listing the stored transactions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, AccountId};
+    /// use sure_client_rs::import::MatchAction;
+    /// use sure_client_rs::models::transaction::{CreateTransactionData, TransactionNature};
+    /// use chrono::{TimeZone, Utc};
+    /// use rust_decimal::Decimal;
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let rows = vec![
+    ///     CreateTransactionData::builder()
+    ///         .account_id(AccountId::new(Uuid::new_v4()))
+    ///         .date(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap())
+    ///         .amount(Decimal::new(450, 2))
+    ///         .name("Coffee Co".to_string())
+    ///         .nature(TransactionNature::Expense)
+    ///         .build(),
+    /// ];
+    ///
+    /// let report = client.import_transactions()
+    ///     .transactions(rows)
+    ///     .on_match(MatchAction::Update)
+    ///     .call()
+    ///     .await?;
+    ///
+    /// println!(
+    ///     "{} new, {} matched, {} conflicting",
+    ///     report.new_rows().len(),
+    ///     report.matched_rows().len(),
+    ///     report.conflicting_rows().len()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn import_transactions(
+        &self,
+        transactions: Vec<CreateTransactionData>,
+        #[builder(default)] on_match: MatchAction,
+        #[builder(default = false)] dry_run: bool,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<ImportReport> {
+        let existing = self.existing_transactions_for(&transactions).await?;
+        let statuses = classify_candidates(&transactions, &existing);
+
+        let writes: Vec<(usize, ImportWrite)> = statuses
+            .iter()
+            .zip(&transactions)
+            .enumerate()
+            .filter_map(|(index, (status, candidate))| {
+                let write = match status {
+                    ImportMatch::New => ImportWrite::Create(candidate.clone()),
+                    ImportMatch::Matched(id) if on_match == MatchAction::Update => {
+                        let changes = changes_from_candidate(candidate);
+                        if changes.is_empty() {
+                            return None;
+                        }
+                        ImportWrite::Update(*id, changes)
+                    }
+                    ImportMatch::Matched(_) | ImportMatch::Conflict(_) => return None,
+                };
+                Some((index, write))
+            })
+            .collect();
+
+        let mut rows: Vec<ImportRow> = statuses
+            .into_iter()
+            .map(|status| ImportRow {
+                status,
+                outcome: None,
+            })
+            .collect();
+
+        if dry_run || writes.is_empty() {
+            return Ok(ImportReport { rows });
+        }
+
+        let indices: Vec<usize> = writes.iter().map(|(index, _)| *index).collect();
+        let config = BatchConfig::new(concurrency, stop_on_error, max_rate_limit_retries);
+        let report = run_batch(writes, config, |(_, write)| async move {
+            match write {
+                ImportWrite::Create(data) => self.submit_transaction(data).await,
+                ImportWrite::Update(id, changes) => {
+                    self.submit_transaction_update(&id, changes).await
+                }
+            }
+        })
+        .await?;
+
+        for (index, outcome) in indices.into_iter().zip(report.results) {
+            if let Some(row) = rows.get_mut(index) {
+                row.outcome = Some(outcome);
+            }
+        }
+
+        Ok(ImportReport { rows })
+    }
+
+    /// Import an OFX/QFX statement into an account
+    ///
+    /// Creates a transaction for every entry whose `FITID` has not been
+    /// imported before. Imported transactions record the `FITID` in their notes
+    /// (see [`fit_id_marker`](crate::import::ofx::fit_id_marker)), and entries
+    /// whose `FITID` already appears on a stored transaction in the account are
+    /// skipped, so importing overlapping statements is safe.
+    ///
+    /// With `create_valuation` set, the statement's ledger balance is also
+    /// recorded as a valuation on its `DTASOF` date, so the account balance is
+    /// reconciled to the bank's. The valuation is skipped when an entry failed
+    /// to import, or when the account already has a valuation on that date.
+    ///
+    /// # Arguments
+    /// * `statement` - Statement parsed with [`parse_ofx`](crate::import::ofx::parse_ofx) (required)
+    /// * `account_id` - Account to import into (required)
+    /// * `create_valuation` - Record the ledger balance as a valuation (default: false)
+    /// * `dry_run` - Only classify the entries, without sending any write requests (default: false)
+    /// * `concurrency` - Maximum number of requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new requests after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited requests (default: 3)
+    ///
+    /// # Returns
+    /// A report with one row per entry, in statement order, and what happened
+    /// to the valuation if one was requested.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `concurrency` is zero.
+    /// Returns any error raised while listing the stored transactions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, AccountId};
+    /// use sure_client_rs::import::ofx::parse_ofx;
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let statements = parse_ofx(&std::fs::read_to_string("statement.qfx")?)?;
+    ///
+    /// for statement in &statements {
+    ///     let report = client.import_ofx()
+    ///         .statement(statement)
+    ///         .account_id(AccountId::new(Uuid::new_v4()))
+    ///         .dry_run(true)
+    ///         .call()
+    ///         .await?;
+    ///     println!("Would import {} transactions", report.new_rows().len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn import_ofx(
+        &self,
+        statement: &OfxStatement,
+        account_id: AccountId,
+        #[builder(default = false)] create_valuation: bool,
+        #[builder(default = false)] dry_run: bool,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<OfxImportReport> {
+        let candidates = statement.transactions_for(account_id);
+        let imported: HashMap<String, TransactionId> = self
+            .existing_transactions_for(&candidates)
+            .await?
+            .into_iter()
+            .filter_map(|transaction| {
+                let fit_id = fit_id_from_notes(transaction.notes.as_deref()?)?.to_string();
+                Some((fit_id, transaction.id))
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut rows = Vec::with_capacity(candidates.len());
+        let mut indices = Vec::new();
+        let mut writes = Vec::new();
+        for (index, (entry, candidate)) in statement.transactions.iter().zip(candidates).enumerate()
+        {
+            let status = if let Some(id) = imported.get(&entry.fit_id) {
+                OfxMatch::Imported(*id)
+            } else if seen.insert(entry.fit_id.as_str()) {
+                indices.push(index);
+                writes.push(candidate);
+                OfxMatch::New
+            } else {
+                OfxMatch::Repeated
+            };
+            rows.push(OfxImportRow {
+                fit_id: entry.fit_id.clone(),
+                status,
+                outcome: None,
+            });
+        }
+
+        if dry_run {
+            return Ok(OfxImportReport {
+                rows,
+                valuation: None,
+            });
+        }
+
+        let mut imported_all = true;
+        if !writes.is_empty() {
+            let config = BatchConfig::new(concurrency, stop_on_error, max_rate_limit_retries);
+            let report = run_batch(writes, config, |data| async move {
+                self.submit_transaction(data).await
+            })
+            .await?;
+            imported_all = report.is_success();
+
+            for (index, outcome) in indices.into_iter().zip(report.results) {
+                if let Some(row) = rows.get_mut(index) {
+                    row.outcome = Some(outcome);
+                }
+            }
+        }
+
+        let valuation = match statement.ledger_balance {
+            Some(_) if create_valuation && !imported_all => Some(OfxValuation::Skipped),
+            Some(balance) if create_valuation => {
+                Some(self.reconcile_ofx_balance(account_id, balance).await)
+            }
+            Some(_) | None => None,
+        };
+
+        Ok(OfxImportReport { rows, valuation })
+    }
+
+    /// Import a QIF section into an account
+    ///
+    /// Converts the section's records into transactions (one per split for
+    /// split records) and imports them with
+    /// [`import_transactions`](Self::import_transactions), so re-importing the
+    /// same file does not create duplicates.
+    ///
+    /// QIF category names are resolved against the family's categories,
+    /// ignoring case and punctuation. `Parent:Child` names match a subcategory
+    /// of the named parent. With `create_missing_categories` set, categories
+    /// that do not exist are created first (parents before children);
+    /// otherwise their entries are imported without a category.
+    ///
+    /// # Arguments
+    /// * `section` - Section parsed with [`parse_qif`](crate::import::qif::parse_qif) (required)
+    /// * `account_id` - Account to import into (required)
+    /// * `currency` - Currency of the amounts (defaults to the family currency)
+    /// * `create_missing_categories` - Create categories that do not exist (default: false)
+    /// * `on_match` - What to do with entries matching a stored transaction (default: skip)
+    /// * `dry_run` - Only classify the entries, without creating anything (default: false)
+    /// * `concurrency` - Maximum number of requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new requests after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited requests (default: 3)
+    ///
+    /// # Returns
+    /// The import report, the categories created, and the category names that
+    /// did not resolve.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `concurrency` is zero.
+    /// Returns any error raised while listing categories or stored
+    /// transactions, or while creating a missing category.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, AccountId};
+    /// use sure_client_rs::import::qif::{QifDateOrder, parse_qif};
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let sections = parse_qif(&std::fs::read_to_string("legacy.qif")?, QifDateOrder::MonthFirst)?;
+    ///
+    /// for section in &sections {
+    ///     let report = client.import_qif()
+    ///         .section(section)
+    ///         .account_id(AccountId::new(Uuid::new_v4()))
+    ///         .dry_run(true)
+    ///         .call()
+    ///         .await?;
+    ///     println!("Unknown categories: {:?}", report.unresolved_categories);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn import_qif(
+        &self,
+        section: &QifSection,
+        account_id: AccountId,
+        currency: Option<iso_currency::Currency>,
+        #[builder(default = false)] create_missing_categories: bool,
+        #[builder(default)] on_match: MatchAction,
+        #[builder(default = false)] dry_run: bool,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<QifImportReport> {
+        let entries = section.entries_for(account_id, currency);
+        let names: BTreeSet<&str> = entries
+            .iter()
+            .filter_map(|entry| entry.category.as_deref())
+            .collect();
+        let resolution = self
+            .resolve_category_names(&names, create_missing_categories && !dry_run)
+            .await?;
+
+        let lines = entries.iter().map(|entry| entry.line).collect();
+        let transactions = entries
+            .into_iter()
+            .map(|entry| CreateTransactionData {
+                category_id: entry
+                    .category
+                    .as_deref()
+                    .and_then(|name| resolution.ids.get(name).copied()),
+                ..entry.transaction
+            })
+            .collect();
+
+        let import = self
+            .import_transactions()
+            .transactions(transactions)
+            .on_match(on_match)
+            .dry_run(dry_run)
+            .concurrency(concurrency)
+            .stop_on_error(stop_on_error)
+            .max_rate_limit_retries(max_rate_limit_retries)
+            .call()
+            .await?;
+
+        Ok(QifImportReport {
+            lines,
+            import,
+            created_categories: resolution.created,
+            unresolved_categories: resolution.unresolved,
+        })
+    }
+}
+
+impl SureClient {
+    /// Fetch the stored transactions that candidates could match: every
+    /// transaction in the candidates' accounts within their date window
+    async fn existing_transactions_for(
+        &self,
+        candidates: &[CreateTransactionData],
+    ) -> ApiResult<Vec<Transaction>> {
+        let (Some(start_date), Some(end_date)) = (
+            candidates.iter().map(|c| c.date).min(),
+            candidates.iter().map(|c| c.date).max(),
+        ) else {
+            return Ok(Vec::new());
+        };
+
+        let accounts: HashSet<_> = candidates.iter().map(|c| c.account_id).collect();
+        // Only narrow the query by account when there is a single one; other
+        // accounts are filtered out below.
+        let account_id = match accounts.iter().collect::<Vec<_>>().as_slice() {
+            [only] => Some(**only),
+            _ => None,
+        };
+
+        let filter = TransactionFilter::builder()
+            .maybe_account_id(account_id)
+            .start_date(start_date)
+            .end_date(end_date)
+            .build();
+
+        let mut existing = self.get_all_transactions(&filter).await?;
+        existing.retain(|transaction| accounts.contains(&transaction.account.id));
+        Ok(existing)
+    }
+
+    /// Record an OFX ledger balance as a valuation, unless the account already
+    /// has one on the balance date
+    async fn reconcile_ofx_balance(
+        &self,
+        account_id: AccountId,
+        balance: OfxBalance,
+    ) -> OfxValuation {
+        let existing = self
+            .get_all_valuations()
+            .account_id(&account_id)
+            .start_date(balance.as_of)
+            .end_date(balance.as_of)
+            .call()
+            .await;
+        match existing.map(|valuations| valuations.into_iter().find(|v| v.date == balance.as_of)) {
+            Ok(Some(valuation)) => return OfxValuation::Existing(valuation),
+            Ok(None) => {}
+            Err(error) => return OfxValuation::Failed(error),
+        }
+
+        match self
+            .create_valuation()
+            .account_id(account_id)
+            .amount(balance.amount)
+            .date(balance.as_of)
+            .notes("Reconciled to OFX ledger balance".to_string())
+            .call()
+            .await
+        {
+            Ok(valuation) => OfxValuation::Created(valuation),
+            Err(error) => OfxValuation::Failed(error),
+        }
+    }
+
+    /// Resolve category names such as `Food:Groceries` to category IDs,
+    /// optionally creating the categories that do not exist
+    async fn resolve_category_names(
+        &self,
+        names: &BTreeSet<&str>,
+        create_missing: bool,
+    ) -> ApiResult<CategoryResolution> {
+        let mut tree = self.get_category_tree().await?;
+        let mut resolution = CategoryResolution::default();
+
+        for name in names {
+            let mut segments = name.split(':').map(str::trim).filter(|s| !s.is_empty());
+            // Sure has two category levels, so deeper QIF paths keep the last two
+            let path = match (segments.next_back(), segments.next_back()) {
+                (Some(leaf), Some(parent)) => vec![parent, leaf],
+                (Some(leaf), None) => vec![leaf],
+                (None, _) => continue,
+            };
+
+            let existing = match path.as_slice() {
+                [leaf] => tree.find_by_name(leaf).map(|category| category.id),
+                _ => tree.find_names(&path),
+            };
+            if let Some(id) = existing {
+                resolution.ids.insert((*name).to_string(), id);
+                continue;
+            }
+            if !create_missing {
+                resolution.unresolved.push((*name).to_string());
+                continue;
+            }
+
+            if let Some(id) = self
+                .ensure_category_names(&mut tree, &path, None, &mut resolution.created)
+                .await?
+            {
+                resolution.ids.insert((*name).to_string(), id);
+            }
+        }
+
+        Ok(resolution)
+    }
+}
+
+/// Category names resolved by an import
+#[derive(Debug, Default)]
+struct CategoryResolution {
+    /// Category ID for each resolved name
+    ids: HashMap<String, CategoryId>,
+    /// Categories created while resolving
+    created: Vec<CategoryDetail>,
+    /// Names that did not resolve
+    unresolved: Vec<String>,
+}
+
+/// The changes copied from an import row onto the transaction it matched
+fn changes_from_candidate(candidate: &CreateTransactionData) -> UpdateTransactionData {
+    fn patch<T: Clone>(value: Option<&T>) -> Patch<T> {
+        value.cloned().map_or(Patch::Unchanged, Patch::Set)
+    }
+
+    UpdateTransactionData {
+        notes: patch(candidate.notes.as_ref()),
+        category_id: patch(candidate.category_id.as_ref()),
+        merchant_id: patch(candidate.merchant_id.as_ref()),
+        tag_ids: patch(candidate.tag_ids.as_ref()),
+        ..UpdateTransactionData::default()
+    }
+}
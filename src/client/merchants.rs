@@ -4,14 +4,19 @@ use reqwest::Method;
 use crate::ApiError;
 use crate::error::ApiResult;
 use crate::models::merchant::{
-    CreateMerchantData, CreateMerchantRequest, MerchantCollection, MerchantDetail,
-    UpdateMerchantData, UpdateMerchantRequest,
+    CreateMerchantData, CreateMerchantRequest, MerchantAssignment, MerchantAssignmentReport,
+    MerchantCollection, MerchantDetail, MerchantMergePlan, MerchantMergeReport, UpdateMerchantData,
+    UpdateMerchantRequest,
 };
+use crate::models::transaction::{TransactionFilter, UpdateTransactionData};
 use crate::models::{DeleteResponse, PaginatedResponse};
-use crate::types::MerchantId;
-use std::collections::HashMap;
+use crate::normalise::{MerchantIndex, MerchantNormaliser, normalise_name};
+use crate::style::HexColor;
+use crate::types::{MerchantId, Patch};
+use std::collections::HashSet;
 
 use super::SureClient;
+use super::batch::{BatchConfig, DEFAULT_CONCURRENCY, DEFAULT_RATE_LIMIT_RETRIES, run_batch};
 
 const MAX_PER_PAGE: u32 = 100;
 
@@ -61,15 +66,59 @@ impl SureClient {
             )));
         }
 
-        let mut query_params = HashMap::new();
+        let mut query_params = Vec::new();
 
-        query_params.insert("page", page.to_string());
-        query_params.insert("per_page", per_page.to_string());
+        query_params.push(("page", page.to_string()));
+        query_params.push(("per_page", per_page.to_string()));
 
         self.execute_request(Method::GET, "/api/v1/merchants", Some(&query_params), None)
             .await
     }
 
+    /// List every merchant
+    ///
+    /// Walks every page of [`get_merchants`](Self::get_merchants) using the
+    /// maximum page size and collects the results.
+    ///
+    /// # Returns
+    /// All merchants, in the order the API returns them.
+    ///
+    /// # Errors
+    /// Returns `ApiError::Unauthorized` if the API key is invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let merchants = client.get_all_merchants().await?;
+    /// println!("{} merchants", merchants.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub async fn get_all_merchants(&self) -> ApiResult<Vec<MerchantDetail>> {
+        let mut merchants = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let response = self
+                .get_merchants()
+                .page(page)
+                .per_page(MAX_PER_PAGE)
+                .call()
+                .await?;
+            let is_empty = response.items.merchants.is_empty();
+            merchants.extend(response.items.merchants);
+
+            if is_empty || page >= response.pagination.total_pages {
+                return Ok(merchants);
+            }
+            page = page.saturating_add(1);
+        }
+    }
+
     /// Get a specific merchant by ID
     ///
     /// Retrieves detailed information about a single merchant.
@@ -117,7 +166,7 @@ impl SureClient {
     ///
     /// # Arguments
     /// * `name` - Merchant name (required)
-    /// * `color` - Merchant color (hex code)
+    /// * `color` - Merchant color (for example `#00704a`)
     ///
     /// # Returns
     /// The newly created merchant with full details.
@@ -134,7 +183,7 @@ impl SureClient {
     /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
     /// let merchant = client.create_merchant()
     ///     .name("Starbucks".to_string())
-    ///     .color("#00704A".to_string())
+    ///     .color("#00704A".parse()?)
     ///     .call()
     ///     .await?;
     ///
@@ -146,7 +195,7 @@ impl SureClient {
     pub async fn create_merchant(
         &self,
         name: String,
-        color: Option<String>,
+        color: Option<HexColor>,
     ) -> ApiResult<MerchantDetail> {
         let request = CreateMerchantRequest {
             merchant: CreateMerchantData { name, color },
@@ -163,12 +212,13 @@ impl SureClient {
 
     /// Update a merchant
     ///
-    /// Updates an existing merchant with new values. Only fields provided will be updated.
+    /// Updates an existing merchant with new values. Only fields provided will be updated;
+    /// pass `Patch::Clear` as `color` to remove the merchant's color.
     ///
     /// # Arguments
     /// * `id` - The merchant ID to update
     /// * `name` - Updated merchant name
-    /// * `color` - Updated merchant color (hex code)
+    /// * `color` - Updated merchant color (for example `#00704a`)
     ///
     /// # Returns
     /// The updated merchant.
@@ -181,7 +231,7 @@ impl SureClient {
     ///
     /// # Example
     /// ```no_run
-    /// use sure_client_rs::{SureClient, BearerToken, MerchantId};
+    /// use sure_client_rs::{SureClient, BearerToken, HexColor, MerchantId};
     /// use uuid::Uuid;
     ///
     /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
@@ -190,7 +240,7 @@ impl SureClient {
     /// let merchant = client.update_merchant()
     ///     .id(&merchant_id)
     ///     .name("Updated Merchant Name".to_string())
-    ///     .color("#FF0000".to_string())
+    ///     .color("#FF0000".parse::<HexColor>()?)
     ///     .call()
     ///     .await?;
     ///
@@ -203,7 +253,7 @@ impl SureClient {
         &self,
         id: &MerchantId,
         name: Option<String>,
-        color: Option<String>,
+        #[builder(default, into)] color: Patch<HexColor>,
     ) -> ApiResult<MerchantDetail> {
         let request = UpdateMerchantRequest {
             merchant: UpdateMerchantData { name, color },
@@ -256,3 +306,378 @@ impl SureClient {
         .await
     }
 }
+
+#[bon]
+impl SureClient {
+    /// Load every merchant into a [`MerchantIndex`]
+    ///
+    /// # Arguments
+    /// * `normaliser` - How names are reduced to index keys (default: [`MerchantNormaliser::default`])
+    ///
+    /// # Returns
+    /// An index of all merchants, keyed by canonical name.
+    ///
+    /// # Errors
+    /// Returns any error raised while listing the merchants.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::normalise::MerchantNormaliser;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let index = client.get_merchant_index()
+    ///     .normaliser(MerchantNormaliser::new().with_location("Ponsonby"))
+    ///     .call()
+    ///     .await?;
+    ///
+    /// for group in index.near_duplicates() {
+    ///     let names: Vec<_> = group.iter().map(|m| m.name.as_str()).collect();
+    ///     println!("Near-duplicates: {}", names.join(", "));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn get_merchant_index(
+        &self,
+        #[builder(default)] normaliser: MerchantNormaliser,
+    ) -> ApiResult<MerchantIndex> {
+        Ok(MerchantIndex::new(
+            normaliser,
+            self.get_all_merchants().await?,
+        ))
+    }
+
+    /// Find the merchant for a transaction description, creating it if needed
+    ///
+    /// The description is reduced to a canonical name with the index's
+    /// [`MerchantNormaliser`]. An existing merchant with the same key is
+    /// returned; otherwise a merchant with the canonical name is created with
+    /// [`create_merchant`](Self::create_merchant) and added to `index`, so
+    /// later lookups find it without another request.
+    ///
+    /// # Arguments
+    /// * `index` - Cached merchants, from [`get_merchant_index`](Self::get_merchant_index) (required)
+    /// * `name` - A merchant name or raw transaction description (required)
+    ///
+    /// # Returns
+    /// The existing or newly created merchant.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `name` has no merchant name left
+    /// once normalised, e.g. `"VISA PURCHASE 12/03"`.
+    /// Returns any error raised while creating the merchant.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut index = client.get_merchant_index().call().await?;
+    ///
+    /// let merchant = client.find_or_create_merchant()
+    ///     .index(&mut index)
+    ///     .name("POS W/D COUNTDOWN METL-1234")
+    ///     .call()
+    ///     .await?;
+    /// println!("{}", merchant.name); // "Countdown"
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn find_or_create_merchant(
+        &self,
+        index: &mut MerchantIndex,
+        name: &str,
+    ) -> ApiResult<MerchantDetail> {
+        let canonical = index.normaliser().canonical_name(name).ok_or_else(|| {
+            ApiError::InvalidParameter(format!("{name:?} does not contain a merchant name"))
+        })?;
+        if let Some(merchant) = index.get(&canonical) {
+            return Ok(merchant.clone());
+        }
+
+        let merchant = self.create_merchant().name(canonical).call().await?;
+        index.insert(merchant.clone());
+        Ok(merchant)
+    }
+
+    /// Attach canonical merchants to the transactions matching a filter
+    ///
+    /// Each transaction's name is reduced to a canonical merchant name (see
+    /// [`MerchantNormaliser`]) and looked up in `index`. Missing merchants are
+    /// created one at a time, so two transactions never create the same
+    /// merchant twice. Transactions are then updated with
+    /// [`update_transaction`](Self::update_transaction). With `dry_run` set,
+    /// nothing is created or sent and the report only contains the plan.
+    ///
+    /// # Arguments
+    /// * `filter` - Selects the transactions to update (required)
+    /// * `index` - Cached merchants, from [`get_merchant_index`](Self::get_merchant_index) (required)
+    /// * `overwrite` - Also replace merchants already attached to transactions (default: false)
+    /// * `dry_run` - Only report what would change (default: false)
+    /// * `concurrency` - Maximum number of update requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new updates after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited updates (default: 3)
+    ///
+    /// # Returns
+    /// The chosen merchant for each transaction that changes, plus
+    /// per-transaction results unless this was a dry run.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `concurrency` is zero.
+    /// Returns any error raised while listing the transactions or creating a
+    /// merchant; no transactions are updated in that case.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::models::transaction::TransactionFilter;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut index = client.get_merchant_index().call().await?;
+    /// let filter = TransactionFilter::default();
+    ///
+    /// let preview = client.assign_merchants()
+    ///     .filter(&filter)
+    ///     .index(&mut index)
+    ///     .dry_run(true)
+    ///     .call()
+    ///     .await?;
+    /// for assignment in &preview.assignments {
+    ///     println!("{} -> {}", assignment.transaction_name, assignment.merchant_name);
+    /// }
+    ///
+    /// client.assign_merchants().filter(&filter).index(&mut index).call().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn assign_merchants(
+        &self,
+        filter: &TransactionFilter,
+        index: &mut MerchantIndex,
+        #[builder(default = false)] overwrite: bool,
+        #[builder(default = false)] dry_run: bool,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<MerchantAssignmentReport> {
+        let config = BatchConfig::new(concurrency, stop_on_error, max_rate_limit_retries);
+        let transactions = self.get_all_transactions(filter).await?;
+
+        let mut assignments = Vec::new();
+        // Names this dry run would create, so each is only reported as new once
+        let mut planned = HashSet::new();
+        for transaction in transactions {
+            if transaction.merchant.is_some() && !overwrite {
+                continue;
+            }
+            let Some(canonical) = index.normaliser().canonical_name(&transaction.name) else {
+                continue;
+            };
+
+            let (merchant_name, merchant_id, created) = match index.get(&canonical) {
+                Some(merchant) => (merchant.name.clone(), Some(merchant.id), false),
+                None if dry_run => {
+                    let created = planned.insert(normalise_name(&canonical));
+                    (canonical, None, created)
+                }
+                None => {
+                    let merchant = self.create_merchant().name(canonical).call().await?;
+                    let created = (merchant.name.clone(), Some(merchant.id), true);
+                    index.insert(merchant);
+                    created
+                }
+            };
+            if merchant_id.is_some() && transaction.merchant.as_ref().map(|m| m.id) == merchant_id {
+                continue;
+            }
+            assignments.push(MerchantAssignment {
+                transaction_id: transaction.id,
+                transaction_name: transaction.name,
+                merchant_name,
+                merchant_id,
+                created,
+            });
+        }
+
+        if dry_run {
+            return Ok(MerchantAssignmentReport {
+                assignments,
+                results: None,
+            });
+        }
+
+        let updates = assignments
+            .iter()
+            .map(|assignment| {
+                let update = UpdateTransactionData::builder()
+                    .maybe_merchant_id(assignment.merchant_id)
+                    .build();
+                (assignment.transaction_id, update)
+            })
+            .collect();
+        let results = run_batch(updates, config, |(id, update)| async move {
+            self.submit_transaction_update(&id, update).await
+        })
+        .await?;
+
+        Ok(MerchantAssignmentReport {
+            assignments,
+            results: Some(results),
+        })
+    }
+
+    /// Merge duplicate merchants into one canonical merchant
+    ///
+    /// Every transaction referencing a merchant in `from` is moved to `into`
+    /// with [`update_transaction`](Self::update_transaction). The duplicates
+    /// are deleted with [`delete_merchant`](Self::delete_merchant) only once
+    /// every transaction has moved, so no transaction loses its merchant.
+    ///
+    /// The merge is resumable: if any move fails, the duplicates are kept,
+    /// and running the merge again only moves what still references them.
+    /// With `dry_run` set, nothing is sent and the report only contains the
+    /// plan. Candidate groups can be found with
+    /// [`MerchantIndex::suggest_merges`].
+    ///
+    /// # Arguments
+    /// * `into` - The merchant to keep (required)
+    /// * `from` - The duplicate merchants to merge away (required)
+    /// * `dry_run` - Only report what would change (default: false)
+    /// * `concurrency` - Maximum number of requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new requests after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited requests (default: 3)
+    ///
+    /// # Returns
+    /// The plan, plus the results of each move and delete unless this was a
+    /// dry run. [`MerchantMergeReport::moved`] lists the counts moved per
+    /// merchant.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `from` is empty or contains
+    /// `into`, or if `concurrency` is zero.
+    /// Returns `ApiError::NotFound` if any of the merchants does not exist.
+    /// Returns any error raised while listing merchants or transactions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::normalise::DEFAULT_MERGE_SIMILARITY;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let index = client.get_merchant_index().call().await?;
+    ///
+    /// for suggestion in index.suggest_merges(DEFAULT_MERGE_SIMILARITY) {
+    ///     let report = client.merge_merchants()
+    ///         .into(suggestion.into.id)
+    ///         .from(&suggestion.from_ids())
+    ///         .call()
+    ///         .await?;
+    ///     for count in report.moved() {
+    ///         println!("{}: moved {} of {}", count.merchant_id, count.moved, count.planned);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn merge_merchants(
+        &self,
+        into: MerchantId,
+        from: &[MerchantId],
+        #[builder(default = false)] dry_run: bool,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<MerchantMergeReport> {
+        let mut duplicates = Vec::with_capacity(from.len());
+        for id in from {
+            if !duplicates.contains(id) {
+                duplicates.push(*id);
+            }
+        }
+        if duplicates.is_empty() {
+            return Err(ApiError::InvalidParameter(
+                "no merchants to merge".to_string(),
+            ));
+        }
+        if duplicates.contains(&into) {
+            return Err(ApiError::InvalidParameter(
+                "cannot merge a merchant into itself".to_string(),
+            ));
+        }
+        let existing: HashSet<MerchantId> = self
+            .get_all_merchants()
+            .await?
+            .into_iter()
+            .map(|merchant| merchant.id)
+            .collect();
+        if let Some(missing) = std::iter::once(&into)
+            .chain(&duplicates)
+            .find(|id| !existing.contains(id))
+        {
+            return Err(ApiError::NotFound {
+                message: format!("merchant {missing} does not exist"),
+            });
+        }
+
+        let filter = TransactionFilter::builder()
+            .merchant_ids(duplicates.clone())
+            .build();
+        let transactions = self
+            .get_all_transactions(&filter)
+            .await?
+            .into_iter()
+            .filter_map(|transaction| {
+                let merchant = transaction.merchant.as_ref()?.id;
+                duplicates
+                    .contains(&merchant)
+                    .then_some((transaction.id, merchant))
+            })
+            .collect();
+        let plan = MerchantMergePlan {
+            into,
+            from: duplicates,
+            transactions,
+        };
+
+        if dry_run {
+            return Ok(MerchantMergeReport {
+                plan,
+                transactions: None,
+                deleted: None,
+            });
+        }
+
+        let config = BatchConfig::new(concurrency, stop_on_error, max_rate_limit_retries);
+        let transactions = run_batch(plan.transaction_ids(), config, |id| async move {
+            let update = UpdateTransactionData::builder().merchant_id(into).build();
+            self.submit_transaction_update(&id, update).await
+        })
+        .await?;
+
+        let deleted = if transactions.is_success() {
+            Some(
+                run_batch(plan.from.clone(), config, |id| async move {
+                    self.delete_merchant(&id).await
+                })
+                .await?,
+            )
+        } else {
+            None
+        };
+
+        Ok(MerchantMergeReport {
+            plan,
+            transactions: Some(transactions),
+            deleted,
+        })
+    }
+}
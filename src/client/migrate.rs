@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use bon::bon;
+
+use crate::backup::{MappingJournal, MigrationReport};
+use crate::error::BackupError;
+
+use super::SureClient;
+use super::batch::{BatchConfig, DEFAULT_CONCURRENCY, DEFAULT_RATE_LIMIT_RETRIES};
+
+#[bon]
+impl SureClient {
+    /// Copy this client's family into another instance
+    ///
+    /// Takes a [`backup`](Self::backup) of this instance and creates every
+    /// entity in `target` the way [`restore`](Self::restore) does, except that
+    /// `target` does not need to be empty. The mapping from source to target
+    /// IDs is loaded from `mapping_path` and saved back after every stage.
+    /// Between saves, every entity is appended to `<mapping_path>.journal` as
+    /// soon as it is created, so an interrupted migration resumes where it
+    /// stopped. Entities already in the mapping are never copied again,
+    /// which makes repeat runs safe: they only copy what was added to the
+    /// source since the last run. See [`crate::backup`] for what cannot be
+    /// copied.
+    ///
+    /// # Arguments
+    /// * `target` - Client for the instance to copy into (required)
+    /// * `mapping_path` - File the ID mapping is kept in; created if missing (required)
+    /// * `concurrency` - Maximum number of transaction requests in flight (default: 4)
+    /// * `max_rate_limit_retries` - Retries for rate-limited transaction requests (default: 3)
+    ///
+    /// # Returns
+    /// A report with the full mapping, how many entities this run copied or
+    /// skipped, and the entities that could not be copied.
+    ///
+    /// # Errors
+    /// Returns `BackupError::Api` if the source cannot be read or the target's
+    /// categories, merchants or tags cannot be listed.
+    /// Returns `BackupError::Io` or `BackupError::Json` if the mapping file or
+    /// its journal cannot be read or written. Everything copied before the
+    /// error is recorded in one of them, so the migration can simply be run
+    /// again.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::path::Path;
+    /// use sure_client_rs::{Auth, SureClient};
+    ///
+    /// # async fn example(staging: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let production = SureClient::new(
+    ///     reqwest::Client::new(),
+    ///     Auth::api_key("production_api_key"),
+    ///     "https://sure.example.com".parse()?,
+    /// );
+    ///
+    /// let report = staging.migrate()
+    ///     .target(&production)
+    ///     .mapping_path(Path::new("staging-to-production.json"))
+    ///     .call()
+    ///     .await?;
+    ///
+    /// println!("Copied {}, already there {}", report.copied, report.already_copied);
+    /// for failure in &report.failures {
+    ///     println!("{:?} {} not copied: {}", failure.entity, failure.source_id, failure.reason);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn migrate(
+        &self,
+        target: &Self,
+        mapping_path: &Path,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> Result<MigrationReport, BackupError> {
+        let (mut mapping, journal) = MappingJournal::resume(mapping_path)?;
+        let backup = self.backup().call().await?;
+        let config = BatchConfig::new(concurrency, false, max_rate_limit_retries);
+
+        let already_copied = mapping.count_in(&backup);
+        let mapped_before = mapping.len();
+        let mut failures = Vec::new();
+
+        target
+            .copy_categories(&backup, &mut mapping, Some(&journal), &mut failures)
+            .await?;
+        journal.checkpoint(&mapping, mapping_path)?;
+        target
+            .copy_merchants(&backup, &mut mapping, Some(&journal), &mut failures)
+            .await?;
+        journal.checkpoint(&mapping, mapping_path)?;
+        target
+            .copy_tags(&backup, &mut mapping, Some(&journal), &mut failures)
+            .await?;
+        journal.checkpoint(&mapping, mapping_path)?;
+        target
+            .copy_accounts(&backup, &mut mapping, Some(&journal), &mut failures)
+            .await;
+        journal.checkpoint(&mapping, mapping_path)?;
+        target
+            .copy_transactions(
+                &backup.transactions,
+                &mut mapping,
+                Some(&journal),
+                config,
+                &mut failures,
+            )
+            .await?;
+        journal.checkpoint(&mapping, mapping_path)?;
+        target
+            .copy_valuations(&backup, &mut mapping, Some(&journal), &mut failures)
+            .await;
+        journal.checkpoint(&mapping, mapping_path)?;
+        target
+            .copy_chats(&backup, &mut mapping, Some(&journal), &mut failures)
+            .await;
+        journal.finish(&mapping, mapping_path)?;
+
+        Ok(MigrationReport {
+            copied: mapping.len().saturating_sub(mapped_before),
+            already_copied,
+            mapping,
+            failures,
+        })
+    }
+}
@@ -1,10 +1,18 @@
 mod accounts;
+mod analysis;
 mod auth;
+mod backup;
+mod batch;
 mod categories;
 mod chats;
 mod core;
+mod export;
+mod import;
 mod merchants;
+mod migrate;
+mod rules;
 mod sync;
+mod tags;
 mod transactions;
 mod usage;
 mod valuations;
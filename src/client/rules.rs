@@ -0,0 +1,99 @@
+use bon::bon;
+
+use crate::error::ApiResult;
+use crate::models::transaction::TransactionFilter;
+use crate::rules::{RuleSet, RulesReport};
+
+use super::SureClient;
+use super::batch::{BatchConfig, DEFAULT_CONCURRENCY, DEFAULT_RATE_LIMIT_RETRIES, run_batch};
+
+#[bon]
+impl SureClient {
+    /// Apply categorisation rules to the transactions matching a filter
+    ///
+    /// Evaluates `rules` over every transaction matching `filter` (see
+    /// [`crate::rules`]) and sends one [`update_transaction`](Self::update_transaction)
+    /// request per transaction that changes. With `dry_run` set, nothing is
+    /// sent and the report only contains the plan: a field-by-field diff for
+    /// each transaction and a hit count for each rule.
+    ///
+    /// # Arguments
+    /// * `rules` - The rules to evaluate (required)
+    /// * `filter` - Selects the transactions to evaluate (required)
+    /// * `dry_run` - Only report what would change (default: false)
+    /// * `concurrency` - Maximum number of requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new requests after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited requests (default: 3)
+    ///
+    /// # Returns
+    /// The plan, plus per-transaction results unless this was a dry run.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `concurrency` is zero.
+    /// Returns any error raised while listing the matching transactions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::models::transaction::TransactionFilter;
+    /// use sure_client_rs::rules::RuleSet;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let rules = RuleSet::load("rules.toml")?;
+    /// let filter = TransactionFilter::default();
+    ///
+    /// let preview = client.apply_rules()
+    ///     .rules(&rules)
+    ///     .filter(&filter)
+    ///     .dry_run(true)
+    ///     .call()
+    ///     .await?;
+    /// for change in &preview.plan.changes {
+    ///     println!("{change}");
+    /// }
+    /// for hits in &preview.plan.hits {
+    ///     println!("{}: matched {}, changed {}", hits.rule, hits.matched, hits.changed);
+    /// }
+    ///
+    /// client.apply_rules().rules(&rules).filter(&filter).call().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn apply_rules(
+        &self,
+        rules: &RuleSet,
+        filter: &TransactionFilter,
+        #[builder(default = false)] dry_run: bool,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<RulesReport> {
+        let transactions = self.get_all_transactions(filter).await?;
+        let plan = rules.plan(&transactions);
+
+        if dry_run {
+            return Ok(RulesReport {
+                plan,
+                results: None,
+            });
+        }
+
+        let updates = plan
+            .changes
+            .iter()
+            .map(|change| (change.transaction_id, change.update.clone()))
+            .collect();
+        let config = BatchConfig::new(concurrency, stop_on_error, max_rate_limit_retries);
+        let results = run_batch(updates, config, |(id, update)| async move {
+            self.submit_transaction_update(&id, update).await
+        })
+        .await?;
+
+        Ok(RulesReport {
+            plan,
+            results: Some(results),
+        })
+    }
+}
@@ -0,0 +1,350 @@
+use bon::bon;
+use reqwest::Method;
+
+use crate::ApiError;
+use crate::error::ApiResult;
+use crate::models::tag::{
+    CreateTagData, CreateTagRequest, TagCollection, TagDetail, UpdateTagData, UpdateTagRequest,
+};
+use crate::models::{DeleteResponse, PaginatedResponse};
+use crate::normalise::normalise_name;
+use crate::style::{ColorPalette, HexColor};
+use crate::types::TagId;
+
+use super::SureClient;
+
+const MAX_PER_PAGE: u32 = 100;
+
+#[bon]
+impl SureClient {
+    /// List tags
+    ///
+    /// Retrieves a paginated list of tags.
+    ///
+    /// # Arguments
+    /// * `page` - Page number (default: 1)
+    /// * `per_page` - Items per page (default: 25, max: 100)
+    ///
+    /// # Returns
+    /// A paginated response containing tags and pagination metadata.
+    ///
+    /// # Errors
+    /// Returns `ApiError::Unauthorized` if the API key is invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let response = client.get_tags().call().await?;
+    ///
+    /// for tag in response.items.tags {
+    ///     println!("Tag: {} ({})", tag.name, tag.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn get_tags(
+        &self,
+        #[builder(default = 1)] page: u32,
+        #[builder(default = 25)] per_page: u32,
+    ) -> ApiResult<PaginatedResponse<TagCollection>> {
+        if per_page > MAX_PER_PAGE {
+            return Err(ApiError::InvalidParameter(format!(
+                "per_page cannot exceed {MAX_PER_PAGE}",
+            )));
+        }
+
+        let mut query_params = Vec::new();
+
+        query_params.push(("page", page.to_string()));
+        query_params.push(("per_page", per_page.to_string()));
+
+        self.execute_request(Method::GET, "/api/v1/tags", Some(&query_params), None)
+            .await
+    }
+
+    /// List every tag
+    ///
+    /// Walks every page of [`get_tags`](Self::get_tags) using the maximum
+    /// page size and collects the results.
+    ///
+    /// # Returns
+    /// All tags, in the order the API returns them.
+    ///
+    /// # Errors
+    /// Returns `ApiError::Unauthorized` if the API key is invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let tags = client.get_all_tags().await?;
+    /// println!("{} tags", tags.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub async fn get_all_tags(&self) -> ApiResult<Vec<TagDetail>> {
+        let mut tags = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let response = self
+                .get_tags()
+                .page(page)
+                .per_page(MAX_PER_PAGE)
+                .call()
+                .await?;
+            let is_empty = response.items.tags.is_empty();
+            tags.extend(response.items.tags);
+
+            if is_empty || page >= response.pagination.total_pages {
+                return Ok(tags);
+            }
+            page = page.saturating_add(1);
+        }
+    }
+
+    /// Get a specific tag by ID
+    ///
+    /// # Arguments
+    /// * `id` - The tag ID to retrieve
+    ///
+    /// # Returns
+    /// Detailed tag information.
+    ///
+    /// # Errors
+    /// Returns `ApiError::NotFound` if the tag doesn't exist.
+    /// Returns `ApiError::Unauthorized` if the API key is invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, TagId};
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let tag_id = TagId::new(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap());
+    /// let tag = client.get_tag(&tag_id).await?;
+    ///
+    /// println!("Tag: {}", tag.name);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_tag(&self, id: &TagId) -> ApiResult<TagDetail> {
+        self.execute_request(Method::GET, &format!("/api/v1/tags/{}", id), None, None)
+            .await
+    }
+}
+
+#[bon]
+impl SureClient {
+    /// Create a new tag
+    ///
+    /// # Arguments
+    /// * `name` - Tag name (required)
+    /// * `color` - Tag color (for example `#e99537`); Sure picks one when omitted
+    ///
+    /// # Returns
+    /// The newly created tag with full details.
+    ///
+    /// # Errors
+    /// Returns `ApiError::ValidationError` if the name is missing or already taken.
+    /// Returns `ApiError::Unauthorized` if the API key is invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let tag = client.create_tag()
+    ///     .name("Holiday".to_string())
+    ///     .color("#61c9ea".parse()?)
+    ///     .call()
+    ///     .await?;
+    ///
+    /// println!("Created tag: {}", tag.name);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn create_tag(&self, name: String, color: Option<HexColor>) -> ApiResult<TagDetail> {
+        let request = CreateTagRequest {
+            tag: CreateTagData { name, color },
+        };
+
+        self.execute_request(
+            Method::POST,
+            "/api/v1/tags",
+            None,
+            Some(serde_json::to_string(&request)?),
+        )
+        .await
+    }
+
+    /// Update a tag
+    ///
+    /// Renames or recolours an existing tag. Only fields provided will be
+    /// updated; transactions keep the tag either way.
+    ///
+    /// # Arguments
+    /// * `id` - The tag ID to update
+    /// * `name` - Updated tag name
+    /// * `color` - Updated tag color (for example `#e99537`)
+    ///
+    /// # Returns
+    /// The updated tag.
+    ///
+    /// # Errors
+    /// Returns `ApiError::NotFound` if the tag doesn't exist.
+    /// Returns `ApiError::ValidationError` if the provided values are invalid.
+    /// Returns `ApiError::Unauthorized` if the API key is invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, HexColor, TagId};
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let tag_id = TagId::new(Uuid::new_v4());
+    ///
+    /// let tag = client.update_tag()
+    ///     .id(&tag_id)
+    ///     .name("Travel".to_string())
+    ///     .color("#805dee".parse::<HexColor>()?)
+    ///     .call()
+    ///     .await?;
+    ///
+    /// println!("Updated tag: {}", tag.name);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn update_tag(
+        &self,
+        id: &TagId,
+        name: Option<String>,
+        color: Option<HexColor>,
+    ) -> ApiResult<TagDetail> {
+        let request = UpdateTagRequest {
+            tag: UpdateTagData { name, color },
+        };
+
+        self.execute_request(
+            Method::PATCH,
+            &format!("/api/v1/tags/{}", id),
+            None,
+            Some(serde_json::to_string(&request)?),
+        )
+        .await
+    }
+
+    /// Delete a tag
+    ///
+    /// Permanently deletes a tag and removes it from every transaction.
+    ///
+    /// # Arguments
+    /// * `id` - The tag ID to delete
+    ///
+    /// # Returns
+    /// A confirmation message.
+    ///
+    /// # Errors
+    /// Returns `ApiError::NotFound` if the tag doesn't exist.
+    /// Returns `ApiError::Unauthorized` if the API key is invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, TagId};
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let tag_id = TagId::new(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap());
+    /// let response = client.delete_tag(&tag_id).await?;
+    ///
+    /// println!("Deleted: {}", response.message);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_tag(&self, id: &TagId) -> ApiResult<DeleteResponse> {
+        self.execute_request(Method::DELETE, &format!("/api/v1/tags/{}", id), None, None)
+            .await
+    }
+
+    /// Find a tag by name, creating it if needed
+    ///
+    /// `name` is compared with the names in `tags` ignoring case and
+    /// punctuation. If none match, a tag is created with
+    /// [`create_tag`](Self::create_tag) and added to `tags`, so later calls
+    /// find it without another request.
+    ///
+    /// # Arguments
+    /// * `tags` - Cached tags, from [`get_all_tags`](Self::get_all_tags) (required)
+    /// * `name` - Tag name (required)
+    /// * `color` - Color for a created tag (default: the next
+    ///   [`ColorPalette`] colour not already used in `tags`)
+    ///
+    /// # Returns
+    /// The existing or newly created tag.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `name` is blank.
+    /// Returns any error raised while creating the tag.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut tags = client.get_all_tags().await?;
+    ///
+    /// let holiday = client.find_or_create_tag()
+    ///     .tags(&mut tags)
+    ///     .name("Holiday")
+    ///     .call()
+    ///     .await?;
+    /// println!("{} is {}", holiday.name, holiday.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn find_or_create_tag(
+        &self,
+        tags: &mut Vec<TagDetail>,
+        name: &str,
+        color: Option<HexColor>,
+    ) -> ApiResult<TagDetail> {
+        let key = normalise_name(name);
+        if key.is_empty() {
+            return Err(ApiError::InvalidParameter(format!(
+                "{name:?} is not a tag name"
+            )));
+        }
+        if let Some(tag) = tags.iter().find(|tag| normalise_name(&tag.name) == key) {
+            return Ok(tag.clone());
+        }
+
+        let color = color.unwrap_or_else(|| {
+            ColorPalette::new()
+                .excluding(tags.iter().filter_map(|tag| tag.color.parse().ok()))
+                .pick()
+        });
+        let tag = self
+            .create_tag()
+            .name(name.trim().to_string())
+            .color(color)
+            .call()
+            .await?;
+        tags.push(tag.clone());
+        Ok(tag)
+    }
+}
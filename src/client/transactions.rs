@@ -1,18 +1,20 @@
 use crate::ApiError;
 use crate::error::ApiResult;
+use crate::models::batch::{BatchReport, BulkAction, BulkPlan, BulkReport};
 use crate::models::transaction::{
     CreateTransactionData, CreateTransactionRequest, Transaction, TransactionCollection,
-    TransactionNature, TransactionType, UpdateTransactionData, UpdateTransactionRequest,
+    TransactionFilter, TransactionNature, TransactionType, UpdateTransactionData,
+    UpdateTransactionRequest,
 };
 use crate::models::{DeleteResponse, PaginatedResponse};
-use crate::types::{AccountId, CategoryId, MerchantId, TagId, TransactionId};
+use crate::types::{AccountId, CategoryId, MerchantId, Patch, TagId, TransactionId};
 use bon::bon;
 use chrono::{DateTime, Utc};
 use reqwest::Method;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
 
 use super::SureClient;
+use super::batch::{BatchConfig, DEFAULT_CONCURRENCY, DEFAULT_RATE_LIMIT_RETRIES, run_batch};
 
 const MAX_PER_PAGE: u32 = 100;
 
@@ -38,7 +40,7 @@ impl SureClient {
     /// * `min_amount` - Filter by minimum amount
     /// * `max_amount` - Filter by maximum amount
     /// * `transaction_type` - Filter by transaction type (income or expense)
-    /// * `search` - Search by name, notes, or merchant name
+    /// * `search` - Search by name, notes, or merchant name; ignored when blank
     ///
     /// # Returns
     /// A paginated response containing transactions and pagination metadata.
@@ -96,69 +98,69 @@ impl SureClient {
             )));
         }
 
-        let mut query_params = HashMap::new();
+        let mut query_params = Vec::new();
 
-        query_params.insert("page", page.to_string());
-        query_params.insert("per_page", per_page.to_string());
+        query_params.push(("page", page.to_string()));
+        query_params.push(("per_page", per_page.to_string()));
 
         if let Some(account_id) = account_id {
-            query_params.insert("account_id", account_id.to_string());
+            query_params.push(("account_id", account_id.to_string()));
         }
 
         if let Some(account_ids) = account_ids {
             for id in account_ids {
-                query_params.insert("account_ids[]", id.to_string());
+                query_params.push(("account_ids[]", id.to_string()));
             }
         }
 
         if let Some(category_id) = category_id {
-            query_params.insert("category_id", category_id.to_string());
+            query_params.push(("category_id", category_id.to_string()));
         }
 
         if let Some(category_ids) = category_ids {
             for id in category_ids {
-                query_params.insert("category_ids[]", id.to_string());
+                query_params.push(("category_ids[]", id.to_string()));
             }
         }
 
         if let Some(merchant_id) = merchant_id {
-            query_params.insert("merchant_id", merchant_id.to_string());
+            query_params.push(("merchant_id", merchant_id.to_string()));
         }
 
         if let Some(merchant_ids) = merchant_ids {
             for id in merchant_ids {
-                query_params.insert("merchant_ids[]", id.to_string());
+                query_params.push(("merchant_ids[]", id.to_string()));
             }
         }
 
         if let Some(tag_ids) = tag_ids {
             for id in tag_ids {
-                query_params.insert("tag_ids[]", id.to_string());
+                query_params.push(("tag_ids[]", id.to_string()));
             }
         }
 
         if let Some(start_date) = start_date {
-            query_params.insert("start_date", start_date.format("%Y-%m-%d").to_string());
+            query_params.push(("start_date", start_date.format("%Y-%m-%d").to_string()));
         }
 
         if let Some(end_date) = end_date {
-            query_params.insert("end_date", end_date.format("%Y-%m-%d").to_string());
+            query_params.push(("end_date", end_date.format("%Y-%m-%d").to_string()));
         }
 
         if let Some(min_amount) = min_amount {
-            query_params.insert("min_amount", min_amount.to_string());
+            query_params.push(("min_amount", min_amount.to_string()));
         }
 
         if let Some(max_amount) = max_amount {
-            query_params.insert("max_amount", max_amount.to_string());
+            query_params.push(("max_amount", max_amount.to_string()));
         }
 
         if let Some(transaction_type) = transaction_type {
-            query_params.insert("type", transaction_type.to_string());
+            query_params.push(("type", transaction_type.to_string()));
         }
 
-        if let Some(search) = search {
-            query_params.insert("search", search.to_string());
+        if let Some(search) = search.filter(|search| !search.trim().is_empty()) {
+            query_params.push(("search", search.to_string()));
         }
 
         self.execute_request(
@@ -170,6 +172,56 @@ impl SureClient {
         .await
     }
 
+    /// List every transaction matching a filter
+    ///
+    /// Walks all pages of [`get_transactions`](Self::get_transactions) using the
+    /// maximum page size and collects the results.
+    ///
+    /// # Arguments
+    /// * `filter` - The filters to apply
+    ///
+    /// # Returns
+    /// All matching transactions.
+    ///
+    /// # Errors
+    /// Returns `ApiError::Unauthorized` if the bearer token is invalid or expired.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::SureClient;
+    /// use sure_client_rs::models::transaction::TransactionFilter;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let filter = TransactionFilter::builder().search("coffee").build();
+    /// let transactions = client.get_all_transactions(&filter).await?;
+    ///
+    /// println!("Found {} coffee transactions", transactions.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub async fn get_all_transactions(
+        &self,
+        filter: &TransactionFilter,
+    ) -> ApiResult<Vec<Transaction>> {
+        let mut transactions = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let response = self
+                .get_transactions_page(filter, page, MAX_PER_PAGE)
+                .await?;
+            let is_empty = response.items.transactions.is_empty();
+            transactions.extend(response.items.transactions);
+
+            if is_empty || page >= response.pagination.total_pages {
+                return Ok(transactions);
+            }
+            page = page.saturating_add(1);
+        }
+    }
+
     /// Create a new transaction
     ///
     /// Creates a new transaction with the specified details.
@@ -230,30 +282,90 @@ impl SureClient {
         nature: Option<TransactionNature>,
         tag_ids: Option<Vec<TagId>>,
     ) -> ApiResult<Transaction> {
-        let request = CreateTransactionRequest {
-            transaction: CreateTransactionData {
-                account_id,
-                date,
-                amount,
-                name,
-                notes,
-                currency,
-                category_id,
-                merchant_id,
-                nature,
-                tag_ids,
-            },
-        };
-
-        self.execute_request(
-            Method::POST,
-            "/api/v1/transactions",
-            None,
-            Some(serde_json::to_string(&request)?),
-        )
+        self.submit_transaction(CreateTransactionData {
+            account_id,
+            date,
+            amount,
+            name,
+            notes,
+            currency,
+            category_id,
+            merchant_id,
+            nature,
+            tag_ids,
+        })
         .await
     }
 
+    /// Create many transactions
+    ///
+    /// Creates each transaction in `transactions`, keeping up to `concurrency`
+    /// requests in flight at once. The Sure API does not expose a bulk creation
+    /// endpoint, so every transaction is sent as an individual request. Requests
+    /// rejected with `ApiError::RateLimited` are retried with exponential backoff
+    /// up to `max_rate_limit_retries` times before being reported as failed.
+    ///
+    /// # Arguments
+    /// * `transactions` - The transactions to create (required)
+    /// * `concurrency` - Maximum number of requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new requests after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited requests (default: 3)
+    ///
+    /// # Returns
+    /// A report with one outcome per input transaction, in input order. Items not
+    /// sent because of `stop_on_error` are reported as skipped.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `concurrency` is zero. Failures of
+    /// individual requests are reported per item rather than as an error.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, AccountId};
+    /// use sure_client_rs::models::transaction::CreateTransactionData;
+    /// use chrono::{TimeZone, Utc};
+    /// use rust_decimal::Decimal;
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let account_id = AccountId::new(Uuid::new_v4());
+    /// let transactions = (1..=3)
+    ///     .map(|day| {
+    ///         CreateTransactionData::builder()
+    ///             .account_id(account_id)
+    ///             .date(Utc.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap())
+    ///             .amount(Decimal::new(450, 2))
+    ///             .name("Coffee".to_string())
+    ///             .build()
+    ///     })
+    ///     .collect();
+    ///
+    /// let report = client.create_transactions()
+    ///     .transactions(transactions)
+    ///     .concurrency(2)
+    ///     .call()
+    ///     .await?;
+    ///
+    /// for (index, error) in report.failed() {
+    ///     println!("Row {index} failed: {error}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn create_transactions(
+        &self,
+        transactions: Vec<CreateTransactionData>,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<BatchReport<Transaction>> {
+        let config = BatchConfig::new(concurrency, stop_on_error, max_rate_limit_retries);
+
+        run_batch(transactions, config, |data| self.submit_transaction(data)).await
+    }
+
     /// Get a specific transaction by ID
     ///
     /// Retrieves detailed information about a single transaction.
@@ -297,6 +409,8 @@ impl SureClient {
     /// Update a transaction
     ///
     /// Updates an existing transaction with new values. Only fields provided will be updated.
+    /// The nullable fields `notes`, `category_id`, `merchant_id` and `tag_ids` accept
+    /// either a value or a [`Patch`]; pass `Patch::Clear` to clear them.
     ///
     /// # Arguments
     /// * `id` - The transaction ID to update
@@ -321,7 +435,7 @@ impl SureClient {
     ///
     /// # Example
     /// ```no_run
-    /// use sure_client_rs::{SureClient, BearerToken, TransactionId};
+    /// use sure_client_rs::{SureClient, BearerToken, Patch, TransactionId};
     /// use rust_decimal::Decimal;
     /// use uuid::Uuid;
     ///
@@ -335,6 +449,14 @@ impl SureClient {
     ///     .call()
     ///     .await?;
     ///
+    /// // Remove the category and merchant from the transaction
+    /// let transaction = client.update_transaction()
+    ///     .id(&transaction_id)
+    ///     .category_id(Patch::Clear)
+    ///     .merchant_id(Patch::Clear)
+    ///     .call()
+    ///     .await?;
+    ///
     /// println!("Updated transaction: {}", transaction.id);
     /// # Ok(())
     /// # }
@@ -347,15 +469,16 @@ impl SureClient {
         date: Option<DateTime<Utc>>,
         amount: Option<Decimal>,
         name: Option<String>,
-        notes: Option<String>,
+        #[builder(default, into)] notes: Patch<String>,
         currency: Option<iso_currency::Currency>,
-        category_id: Option<CategoryId>,
-        merchant_id: Option<MerchantId>,
+        #[builder(default, into)] category_id: Patch<CategoryId>,
+        #[builder(default, into)] merchant_id: Patch<MerchantId>,
         nature: Option<TransactionNature>,
-        tag_ids: Option<Vec<TagId>>,
+        #[builder(default, into)] tag_ids: Patch<Vec<TagId>>,
     ) -> ApiResult<Transaction> {
-        let request = UpdateTransactionRequest {
-            transaction: UpdateTransactionData {
+        self.submit_transaction_update(
+            id,
+            UpdateTransactionData {
                 date,
                 amount,
                 name,
@@ -366,13 +489,6 @@ impl SureClient {
                 nature,
                 tag_ids,
             },
-        };
-
-        self.execute_request(
-            Method::PATCH,
-            &format!("/api/v1/transactions/{}", id),
-            None,
-            Some(serde_json::to_string(&request)?),
         )
         .await
     }
@@ -416,3 +532,264 @@ impl SureClient {
         .await
     }
 }
+
+#[bon]
+impl SureClient {
+    /// Update every transaction matching a filter
+    ///
+    /// Collects every transaction matching `filter` and applies the same
+    /// `changes` to each with [`update_transaction`](Self::update_transaction).
+    /// With `dry_run` set, nothing is sent and the report only contains the plan:
+    /// the matched transaction IDs and the intended changes.
+    ///
+    /// # Arguments
+    /// * `filter` - Selects the transactions to update (required)
+    /// * `changes` - The update applied to each matched transaction (required)
+    /// * `dry_run` - Only report what would change (default: false)
+    /// * `concurrency` - Maximum number of requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new requests after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited requests (default: 3)
+    ///
+    /// # Returns
+    /// The plan, plus per-transaction results unless this was a dry run.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `changes` is empty or `concurrency` is zero.
+    /// Returns any error raised while listing the matching transactions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, CategoryId, MerchantId};
+    /// use sure_client_rs::models::transaction::{TransactionFilter, UpdateTransactionData};
+    /// use chrono::{TimeZone, Utc};
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let merchant_id = MerchantId::new(Uuid::new_v4());
+    /// let category_id = CategoryId::new(Uuid::new_v4());
+    ///
+    /// // Everything from this merchant in 2024 goes into the category
+    /// let filter = TransactionFilter::builder()
+    ///     .merchant_id(merchant_id)
+    ///     .start_date(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+    ///     .end_date(Utc.with_ymd_and_hms(2024, 12, 31, 0, 0, 0).unwrap())
+    ///     .build();
+    /// let changes = UpdateTransactionData::builder().category_id(category_id).build();
+    ///
+    /// let preview = client.update_transactions_matching()
+    ///     .filter(&filter)
+    ///     .changes(changes.clone())
+    ///     .dry_run(true)
+    ///     .call()
+    ///     .await?;
+    /// println!("Would {}", preview.plan);
+    ///
+    /// let report = client.update_transactions_matching()
+    ///     .filter(&filter)
+    ///     .changes(changes)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn update_transactions_matching(
+        &self,
+        filter: &TransactionFilter,
+        changes: UpdateTransactionData,
+        #[builder(default = false)] dry_run: bool,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<BulkReport<Transaction>> {
+        if changes.is_empty() {
+            return Err(ApiError::InvalidParameter(
+                "changes must update at least one field".to_string(),
+            ));
+        }
+
+        let plan = BulkPlan {
+            transaction_ids: self.matching_transaction_ids(filter).await?,
+            action: BulkAction::Update(changes.clone()),
+        };
+
+        if dry_run {
+            return Ok(BulkReport {
+                plan,
+                results: None,
+            });
+        }
+
+        let config = BatchConfig::new(concurrency, stop_on_error, max_rate_limit_retries);
+        let results = run_batch(plan.transaction_ids.clone(), config, |id| {
+            let changes = changes.clone();
+            async move { self.submit_transaction_update(&id, changes).await }
+        })
+        .await?;
+
+        Ok(BulkReport {
+            plan,
+            results: Some(results),
+        })
+    }
+
+    /// Delete every transaction matching a filter
+    ///
+    /// Collects every transaction matching `filter` and deletes each with
+    /// [`delete_transaction`](Self::delete_transaction). With `dry_run` set,
+    /// nothing is sent and the report only contains the matched transaction IDs.
+    ///
+    /// # Arguments
+    /// * `filter` - Selects the transactions to delete (required)
+    /// * `delete_all` - Allow a filter that matches every transaction (default: false)
+    /// * `dry_run` - Only report what would be deleted (default: false)
+    /// * `concurrency` - Maximum number of requests in flight (default: 4)
+    /// * `stop_on_error` - Stop sending new requests after the first failure (default: false)
+    /// * `max_rate_limit_retries` - Retries for rate-limited requests (default: 3)
+    ///
+    /// # Returns
+    /// The plan, plus per-transaction results unless this was a dry run.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `concurrency` is zero, or if
+    /// `filter` is empty and `delete_all` is not set.
+    /// Returns any error raised while listing the matching transactions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, AccountId};
+    /// use sure_client_rs::models::transaction::TransactionFilter;
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let filter = TransactionFilter::builder()
+    ///     .account_id(AccountId::new(Uuid::new_v4()))
+    ///     .search("duplicate import")
+    ///     .build();
+    ///
+    /// let preview = client.delete_transactions_matching()
+    ///     .filter(&filter)
+    ///     .dry_run(true)
+    ///     .call()
+    ///     .await?;
+    /// println!("Would {}", preview.plan);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn delete_transactions_matching(
+        &self,
+        filter: &TransactionFilter,
+        #[builder(default = false)] delete_all: bool,
+        #[builder(default = false)] dry_run: bool,
+        #[builder(default = DEFAULT_CONCURRENCY)] concurrency: usize,
+        #[builder(default = false)] stop_on_error: bool,
+        #[builder(default = DEFAULT_RATE_LIMIT_RETRIES)] max_rate_limit_retries: u32,
+    ) -> ApiResult<BulkReport<DeleteResponse>> {
+        if filter.is_empty() && !delete_all {
+            return Err(ApiError::InvalidParameter(
+                "filter matches every transaction; set delete_all to delete them all".to_string(),
+            ));
+        }
+
+        let plan = BulkPlan {
+            transaction_ids: self.matching_transaction_ids(filter).await?,
+            action: BulkAction::Delete,
+        };
+
+        if dry_run {
+            return Ok(BulkReport {
+                plan,
+                results: None,
+            });
+        }
+
+        let config = BatchConfig::new(concurrency, stop_on_error, max_rate_limit_retries);
+        let results = run_batch(plan.transaction_ids.clone(), config, |id| async move {
+            self.delete_transaction(&id).await
+        })
+        .await?;
+
+        Ok(BulkReport {
+            plan,
+            results: Some(results),
+        })
+    }
+}
+
+impl SureClient {
+    /// Fetch a single page of transactions matching a filter
+    pub(crate) async fn get_transactions_page(
+        &self,
+        filter: &TransactionFilter,
+        page: u32,
+        per_page: u32,
+    ) -> ApiResult<PaginatedResponse<TransactionCollection>> {
+        self.get_transactions()
+            .page(page)
+            .per_page(per_page)
+            .maybe_account_id(filter.account_id.as_ref())
+            .maybe_account_ids(filter.account_ids.as_deref())
+            .maybe_category_id(filter.category_id.as_ref())
+            .maybe_category_ids(filter.category_ids.as_deref())
+            .maybe_merchant_id(filter.merchant_id.as_ref())
+            .maybe_merchant_ids(filter.merchant_ids.as_deref())
+            .maybe_tag_ids(filter.tag_ids.as_deref())
+            .maybe_start_date(filter.start_date.as_ref())
+            .maybe_end_date(filter.end_date.as_ref())
+            .maybe_min_amount(filter.min_amount)
+            .maybe_max_amount(filter.max_amount)
+            .maybe_transaction_type(filter.transaction_type)
+            .maybe_search(filter.search.as_deref())
+            .call()
+            .await
+    }
+
+    /// Collect the IDs of every transaction matching a filter
+    async fn matching_transaction_ids(
+        &self,
+        filter: &TransactionFilter,
+    ) -> ApiResult<Vec<TransactionId>> {
+        Ok(self
+            .get_all_transactions(filter)
+            .await?
+            .into_iter()
+            .map(|transaction| transaction.id)
+            .collect())
+    }
+
+    /// Send a single transaction update request
+    pub(crate) async fn submit_transaction_update(
+        &self,
+        id: &TransactionId,
+        data: UpdateTransactionData,
+    ) -> ApiResult<Transaction> {
+        let request = UpdateTransactionRequest { transaction: data };
+
+        self.execute_request(
+            Method::PATCH,
+            &format!("/api/v1/transactions/{}", id),
+            None,
+            Some(serde_json::to_string(&request)?),
+        )
+        .await
+    }
+
+    /// Send a single transaction creation request
+    pub(crate) async fn submit_transaction(
+        &self,
+        data: CreateTransactionData,
+    ) -> ApiResult<Transaction> {
+        let request = CreateTransactionRequest { transaction: data };
+
+        self.execute_request(
+            Method::POST,
+            "/api/v1/transactions",
+            None,
+            Some(serde_json::to_string(&request)?),
+        )
+        .await
+    }
+}
@@ -1,9 +1,11 @@
+use crate::ApiError;
 use crate::error::ApiResult;
 use crate::models::valuation::{
     CreateValuationData, CreateValuationRequest, UpdateValuationData, UpdateValuationRequest,
-    Valuation,
+    Valuation, ValuationCollection, ValuationKind,
 };
-use crate::types::{AccountId, ValuationId};
+use crate::models::{DeleteResponse, PaginatedResponse};
+use crate::types::{AccountId, Patch, ValuationId};
 use bon::bon;
 use chrono::NaiveDate;
 use reqwest::Method;
@@ -11,6 +13,8 @@ use rust_decimal::Decimal;
 
 use super::SureClient;
 
+const MAX_PER_PAGE: u32 = 100;
+
 #[bon]
 impl SureClient {
     /// Create a new valuation entry for an account.
@@ -79,6 +83,154 @@ impl SureClient {
         .await
     }
 
+    /// List valuations
+    ///
+    /// Retrieves a paginated list of valuation entries, newest first,
+    /// optionally narrowed to one account, a date range or one kind of entry.
+    ///
+    /// # Arguments
+    /// * `page` - Page number (default: 1)
+    /// * `per_page` - Items per page (default: 25, max: 100)
+    /// * `account_id` - Only valuations of this account
+    /// * `start_date` - Only valuations on or after this date
+    /// * `end_date` - Only valuations on or before this date
+    /// * `kind` - Only valuations of this kind
+    ///
+    /// # Returns
+    /// A paginated response containing valuations and pagination metadata.
+    ///
+    /// # Errors
+    /// Returns `ApiError::InvalidParameter` if `per_page` exceeds 100.
+    /// Returns `ApiError::Unauthorized` if the credentials are missing/invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, AccountId};
+    /// use sure_client_rs::models::valuation::ValuationKind;
+    /// use chrono::NaiveDate;
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let account_id = AccountId::new(Uuid::new_v4());
+    /// let response = client.get_valuations()
+    ///     .account_id(&account_id)
+    ///     .start_date(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap())
+    ///     .kind(ValuationKind::Reconciliation)
+    ///     .call()
+    ///     .await?;
+    ///
+    /// for valuation in response.items.valuations {
+    ///     println!("{}: {}", valuation.date, valuation.amount);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn get_valuations(
+        &self,
+        #[builder(default = 1)] page: u32,
+        #[builder(default = 25)] per_page: u32,
+        account_id: Option<&AccountId>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        kind: Option<ValuationKind>,
+    ) -> ApiResult<PaginatedResponse<ValuationCollection>> {
+        if per_page > MAX_PER_PAGE {
+            return Err(ApiError::InvalidParameter(format!(
+                "per_page cannot exceed {MAX_PER_PAGE}",
+            )));
+        }
+
+        let mut query_params = Vec::new();
+
+        query_params.push(("page", page.to_string()));
+        query_params.push(("per_page", per_page.to_string()));
+
+        if let Some(account_id) = account_id {
+            query_params.push(("account_id", account_id.to_string()));
+        }
+
+        if let Some(start_date) = start_date {
+            query_params.push(("start_date", start_date.format("%Y-%m-%d").to_string()));
+        }
+
+        if let Some(end_date) = end_date {
+            query_params.push(("end_date", end_date.format("%Y-%m-%d").to_string()));
+        }
+
+        if let Some(kind) = kind {
+            query_params.push(("kind", kind.to_string()));
+        }
+
+        self.execute_request(Method::GET, "/api/v1/valuations", Some(&query_params), None)
+            .await
+    }
+
+    /// List every valuation matching the filters
+    ///
+    /// Walks all pages of [`get_valuations`](Self::get_valuations) using the
+    /// maximum page size and collects the results.
+    ///
+    /// # Arguments
+    /// * `account_id` - Only valuations of this account
+    /// * `start_date` - Only valuations on or after this date
+    /// * `end_date` - Only valuations on or before this date
+    /// * `kind` - Only valuations of this kind
+    ///
+    /// # Returns
+    /// All matching valuations.
+    ///
+    /// # Errors
+    /// Returns `ApiError::Unauthorized` if the credentials are missing/invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, AccountId};
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let account_id = AccountId::new(Uuid::new_v4());
+    /// let history = client.get_all_valuations().account_id(&account_id).call().await?;
+    ///
+    /// println!("{} valuations", history.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[builder]
+    pub async fn get_all_valuations(
+        &self,
+        account_id: Option<&AccountId>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        kind: Option<ValuationKind>,
+    ) -> ApiResult<Vec<Valuation>> {
+        let mut valuations = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let response = self
+                .get_valuations()
+                .page(page)
+                .per_page(MAX_PER_PAGE)
+                .maybe_account_id(account_id)
+                .maybe_start_date(start_date)
+                .maybe_end_date(end_date)
+                .maybe_kind(kind)
+                .call()
+                .await?;
+            let is_empty = response.items.valuations.is_empty();
+            valuations.extend(response.items.valuations);
+
+            if is_empty || page >= response.pagination.total_pages {
+                return Ok(valuations);
+            }
+            page = page.saturating_add(1);
+        }
+    }
+
     /// Get a specific valuation by ID.
     ///
     /// # Arguments
@@ -97,7 +249,7 @@ impl SureClient {
     ///
     /// The Sure API requires both `amount` and `date` to be supplied together
     /// when changing the underlying reconciliation; `notes` may be updated
-    /// independently.
+    /// independently, and cleared with `Patch::Clear`.
     ///
     /// # Arguments
     /// * `id` - The valuation ID to update.
@@ -110,7 +262,7 @@ impl SureClient {
         id: &ValuationId,
         amount: Option<Decimal>,
         date: Option<NaiveDate>,
-        notes: Option<String>,
+        #[builder(default, into)] notes: Patch<String>,
     ) -> ApiResult<Valuation> {
         let request = UpdateValuationRequest {
             valuation: UpdateValuationData {
@@ -128,4 +280,43 @@ impl SureClient {
         )
         .await
     }
+
+    /// Delete a valuation entry.
+    ///
+    /// Removes the entry from the account's timeline; the account's balance
+    /// history is recalculated without it.
+    ///
+    /// # Arguments
+    /// * `id` - The valuation ID to delete.
+    ///
+    /// # Returns
+    /// A confirmation message.
+    ///
+    /// # Errors
+    /// Returns `ApiError::NotFound` if the valuation doesn't exist.
+    /// Returns `ApiError::Unauthorized` if the credentials are missing/invalid.
+    /// Returns `ApiError::Network` if the request fails due to network issues.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sure_client_rs::{SureClient, ValuationId};
+    /// use uuid::Uuid;
+    ///
+    /// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let valuation_id = ValuationId::new(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap());
+    /// let response = client.delete_valuation(&valuation_id).await?;
+    ///
+    /// println!("Deleted: {}", response.message);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_valuation(&self, id: &ValuationId) -> ApiResult<DeleteResponse> {
+        self.execute_request(
+            Method::DELETE,
+            &format!("/api/v1/valuations/{}", id),
+            None,
+            None,
+        )
+        .await
+    }
 }
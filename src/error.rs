@@ -97,3 +97,151 @@ pub enum ApiError {
 
 /// Result type alias for the Sure API client
 pub type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// Errors raised while reading import files or import profiles
+///
+/// Problems with individual rows are not errors: they are collected in the
+/// import result alongside the rows that parsed, so one bad line does not stop
+/// the rest of the file from being imported.
+#[derive(Debug, Error)]
+pub enum ImportError {
+    /// I/O error while reading or writing a file
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The CSV file could not be read
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// A column named in the profile is missing from the CSV header
+    #[error("Column `{0}` not found in CSV header")]
+    MissingColumn(String),
+
+    /// The OFX file is malformed or missing required elements
+    #[error("Invalid OFX: {0}")]
+    Ofx(String),
+
+    /// The QIF file has an invalid record
+    #[error("Invalid QIF at line {line}: {message}")]
+    Qif {
+        /// Line number of the invalid record or field (1-based)
+        line: usize,
+        /// What is wrong with it
+        message: String,
+    },
+
+    /// The import profile is invalid
+    #[error("Invalid profile: {0}")]
+    InvalidProfile(String),
+
+    /// The import profile could not be parsed as TOML
+    #[error("TOML deserialization error: {0}")]
+    TomlDeserialization(#[from] toml::de::Error),
+
+    /// The import profile could not be written as TOML
+    #[error("TOML serialization error: {0}")]
+    TomlSerialization(#[from] toml::ser::Error),
+
+    /// The import profile could not be parsed or written as JSON
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Errors raised while exporting data
+#[derive(Debug, Error)]
+pub enum ExportError {
+    /// Fetching data from the API failed
+    #[error(transparent)]
+    Api(#[from] ApiError),
+
+    /// I/O error while writing the export
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The CSV output could not be written
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// A row could not be serialized as JSON
+    #[error("JSON serialization error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The date format is not a valid `chrono` format
+    #[error("Invalid date format: {0}")]
+    InvalidDateFormat(String),
+}
+
+/// Errors raised while loading exchange rates
+#[derive(Debug, Error)]
+pub enum FxError {
+    /// I/O error while reading a rates file
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The CSV file could not be read
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// A row of the rates file is invalid
+    #[error("Invalid rate at line {line}: {message}")]
+    InvalidRate {
+        /// Line number of the invalid row (1-based)
+        line: u64,
+        /// What is wrong with it
+        message: String,
+    },
+}
+
+/// Errors raised while backing up or restoring a family
+#[derive(Debug, Error)]
+pub enum BackupError {
+    /// Fetching data from the API failed
+    #[error(transparent)]
+    Api(#[from] ApiError),
+
+    /// I/O error while reading or writing the archive
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The archive could not be parsed or written as JSON
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The archive was written by an incompatible version of the format
+    #[error("Unsupported backup version {found} (supported: {supported})")]
+    UnsupportedVersion {
+        /// Version recorded in the archive
+        found: u64,
+        /// Version this client reads and writes
+        supported: u64,
+    },
+
+    /// The target instance already has accounts, so restoring would mix data
+    #[error("Target instance is not empty: it has {0} accounts")]
+    TargetNotEmpty(u32),
+}
+
+/// Errors raised while loading transaction rules
+#[derive(Debug, Error)]
+pub enum RuleError {
+    /// I/O error while reading or writing a rules file
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The rules could not be parsed as TOML
+    #[error("TOML deserialization error: {0}")]
+    TomlDeserialization(#[from] toml::de::Error),
+
+    /// The rules could not be written as TOML
+    #[error("TOML serialization error: {0}")]
+    TomlSerialization(#[from] toml::ser::Error),
+
+    /// A rule is invalid, e.g. it has no actions or an empty amount range
+    #[error("Invalid rule {rule:?}: {message}")]
+    InvalidRule {
+        /// Name of the rule
+        rule: String,
+        /// What is wrong with it
+        message: String,
+    },
+}
@@ -0,0 +1,723 @@
+//! Plain-text accounting journals
+//!
+//! [`JournalWriter`] writes transactions as double-entry journals for
+//! [ledger](https://ledger-cli.org), [hledger](https://hledger.org) and
+//! [beancount](https://beancount.github.io).
+//!
+//! Sure accounts become `Assets:` or `Liabilities:` accounts, named after their
+//! kind and name (e.g. `Liabilities:CreditCard:Visa`). Categories become
+//! `Income:` or `Expenses:` accounts depending on the transaction's
+//! classification, named after their path when the writer is given the
+//! [`CategoryTree`] (e.g. `Expenses:Food:Groceries`), with `Uncategorized`
+//! standing in for a missing category. Transfers are written once, as a
+//! single entry with a posting in each account, however many of their sides
+//! are passed to the writer.
+//!
+//! An opening anchor becomes an entry moving the opening balance from
+//! `Equity:Opening-Balances`, and every other valuation becomes a balance
+//! assertion, so the journal checks itself against the balances recorded in
+//! Sure.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+use chrono::{Days, NaiveDate};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde::de::value::{Error as ValueError, StrDeserializer};
+
+use crate::error::ExportError;
+use crate::models::account::{Account, AccountClassification, AccountKind};
+use crate::models::category::Classification;
+use crate::models::category_tree::CategoryTree;
+use crate::models::transaction::{self, Transaction};
+use crate::models::valuation::{Valuation, ValuationKind};
+use crate::types::{AccountId, CategoryId, TransactionId};
+
+/// Date on which beancount `open` directives are written.
+///
+/// Beancount requires an account to be opened before its first posting; any
+/// date before the first transaction will do.
+pub const BEANCOUNT_OPEN_DATE: NaiveDate = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+
+/// Counterpart account for transfers whose other side is unknown
+pub const UNKNOWN_TRANSFER_ACCOUNT: &str = "Equity:Transfers";
+
+/// Counterpart account for opening balances
+pub const OPENING_BALANCE_ACCOUNT: &str = "Equity:Opening-Balances";
+
+/// Category name used for transactions without a category
+pub const UNCATEGORIZED: &str = "Uncategorized";
+
+const INDENT: &str = "  ";
+
+/// Journal dialect to write
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum JournalFormat {
+    /// ledger-cli
+    #[default]
+    Ledger,
+    /// hledger
+    Hledger,
+    /// beancount
+    Beancount,
+}
+
+/// Top-level journal account of a Sure account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JournalRoot {
+    /// `Assets`
+    Assets,
+    /// `Liabilities`
+    Liabilities,
+}
+
+impl JournalRoot {
+    /// Choose the root from a classification, falling back to the account kind
+    /// when the classification is missing or unrecognised
+    pub const fn of(
+        classification: Option<&AccountClassification>,
+        kind: Option<AccountKind>,
+    ) -> Self {
+        match classification {
+            Some(AccountClassification::Asset) => Self::Assets,
+            Some(AccountClassification::Liability) => Self::Liabilities,
+            Some(AccountClassification::Other(_)) | None => match kind {
+                Some(AccountKind::CreditCard | AccountKind::Loan | AccountKind::OtherLiability) => {
+                    Self::Liabilities
+                }
+                Some(
+                    AccountKind::Depository
+                    | AccountKind::Investment
+                    | AccountKind::Property
+                    | AccountKind::OtherAsset,
+                )
+                | None => Self::Assets,
+            },
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Assets => "Assets",
+            Self::Liabilities => "Liabilities",
+        }
+    }
+}
+
+/// Journal account name of a Sure account, e.g. `Assets:Depository:Everyday`
+pub fn account_name(account: &Account) -> String {
+    let root = JournalRoot::of(Some(&account.classification), Some(account.kind));
+    format!(
+        "{}:{}:{}",
+        root.as_str(),
+        account.kind,
+        account_component(&account.name)
+    )
+}
+
+/// Turn free text into a single journal account component.
+///
+/// Runs of characters other than letters and digits become `-`, and the first
+/// letter is capitalised, as beancount requires.
+fn account_component(name: &str) -> String {
+    let mut component = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            if component.is_empty() {
+                component.extend(c.to_uppercase());
+            } else {
+                component.push(c);
+            }
+        } else if !component.is_empty() && !component.ends_with('-') {
+            component.push('-');
+        } else {
+            // Leading or repeated separator
+        }
+    }
+    let trimmed = component.trim_end_matches('-');
+    if trimmed.is_empty() {
+        "Unnamed".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Turn a tag name into something every dialect accepts as a tag
+fn tag_component(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Collapse newlines, which would end an entry early
+fn single_line(text: &str) -> String {
+    text.split(['\r', '\n'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Quote a beancount string
+fn quoted(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        single_line(text).replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// Account kind of a transaction's account reference, which carries the kind
+/// as a snake_case string
+fn kind_of(account_type: &str) -> Option<AccountKind> {
+    AccountKind::deserialize(StrDeserializer::<ValueError>::new(account_type)).ok()
+}
+
+/// Writes transactions and valuations as a plain-text journal.
+///
+/// Entries are written in the order they are given. ledger checks balance
+/// assertions in file order, so write entries in date order when the journal
+/// contains assertions.
+pub struct JournalWriter<W: Write> {
+    writer: W,
+    format: JournalFormat,
+    accounts: HashMap<AccountId, String>,
+    categories: HashMap<CategoryId, String>,
+    opened: HashSet<String>,
+    transfers: HashSet<TransactionId>,
+    written: usize,
+}
+
+impl<W: Write> JournalWriter<W> {
+    /// Start a journal.
+    ///
+    /// `accounts` are used to name the accounts transactions refer to. A
+    /// transaction whose account is not in the list is named from the
+    /// classification and type on the transaction itself.
+    pub fn new(writer: W, format: JournalFormat, accounts: &[Account]) -> Self {
+        Self {
+            writer,
+            format,
+            accounts: accounts
+                .iter()
+                .map(|account| (account.id, account_name(account)))
+                .collect(),
+            categories: HashMap::new(),
+            opened: HashSet::new(),
+            transfers: HashSet::new(),
+            written: 0,
+        }
+    }
+
+    /// Name category accounts after their path in `tree`
+    ///
+    /// Without a tree, a subcategory is named after itself alone.
+    #[must_use]
+    pub fn with_categories(mut self, tree: &CategoryTree) -> Self {
+        self.categories = tree
+            .walk()
+            .into_iter()
+            .map(|node| {
+                let path: Vec<String> = tree
+                    .ancestry(&node.category.id)
+                    .iter()
+                    .map(|category| account_component(&category.name))
+                    .collect();
+                (node.category.id, path.join(":"))
+            })
+            .collect();
+        self
+    }
+
+    /// Write one transaction.
+    ///
+    /// A transfer is written as one entry with a posting in each account, so
+    /// the second side of a transfer is skipped.
+    ///
+    /// # Returns
+    /// `false` if the transaction was skipped because its transfer had already
+    /// been written.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the entry cannot be written.
+    pub fn write_transaction(&mut self, transaction: &Transaction) -> Result<bool, ExportError> {
+        let seen_transfer = transaction
+            .transfer
+            .as_ref()
+            .is_some_and(|transfer| !self.transfers.insert(transfer.id));
+        if seen_transfer {
+            return Ok(false);
+        }
+
+        let signed = transaction.signed_amount();
+        let account = self.reference_name(&transaction.account);
+        let counterpart = match &transaction.transfer {
+            Some(transfer) => transfer.other_account.as_ref().map_or_else(
+                || UNKNOWN_TRANSFER_ACCOUNT.to_string(),
+                |other| self.reference_name(other),
+            ),
+            None => self.category_account(transaction),
+        };
+        let currency = transaction.currency.code();
+        let date = transaction.date.date_naive();
+
+        self.open(&account)?;
+        self.open(&counterpart)?;
+        self.write_header(transaction, date)?;
+        writeln!(self.writer, "{INDENT}{account}  {signed} {currency}")?;
+        writeln!(
+            self.writer,
+            "{INDENT}{counterpart}  {} {currency}",
+            Decimal::ZERO.saturating_sub(signed)
+        )?;
+        writeln!(self.writer)?;
+
+        self.written = self.written.saturating_add(1);
+        Ok(true)
+    }
+
+    /// Write a valuation.
+    ///
+    /// An opening anchor becomes an entry moving the opening balance from
+    /// [`OPENING_BALANCE_ACCOUNT`]; reconciliations and current anchors
+    /// become balance assertions. Sure records liability balances as positive
+    /// amounts owed; they are negated so they agree with the postings. An
+    /// assertion holds at the end of the valuation's date, so beancount
+    /// `balance` directives, which are checked at the start of a day, are
+    /// dated the following day.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the entry cannot be written.
+    pub fn write_valuation(&mut self, valuation: &Valuation) -> Result<(), ExportError> {
+        let account = self.reference_name(&valuation.account);
+        let balance = if account.starts_with(JournalRoot::Liabilities.as_str()) {
+            Decimal::ZERO.saturating_sub(valuation.amount)
+        } else {
+            valuation.amount
+        };
+        let currency = valuation.currency.code();
+
+        self.open(&account)?;
+        if valuation.kind == ValuationKind::OpeningAnchor {
+            self.open(OPENING_BALANCE_ACCOUNT)?;
+            match self.format {
+                JournalFormat::Ledger | JournalFormat::Hledger => {
+                    writeln!(self.writer, "{} * Opening balance", valuation.date)?;
+                    self.write_comment("id", &valuation.id.to_string())?;
+                }
+                JournalFormat::Beancount => {
+                    writeln!(self.writer, "{} * \"Opening balance\"", valuation.date)?;
+                    writeln!(
+                        self.writer,
+                        "{INDENT}id: {}",
+                        quoted(&valuation.id.to_string())
+                    )?;
+                }
+            }
+            writeln!(self.writer, "{INDENT}{account}  {balance} {currency}")?;
+            writeln!(
+                self.writer,
+                "{INDENT}{OPENING_BALANCE_ACCOUNT}  {} {currency}",
+                Decimal::ZERO.saturating_sub(balance)
+            )?;
+            writeln!(self.writer)?;
+            self.written = self.written.saturating_add(1);
+            return Ok(());
+        }
+
+        match self.format {
+            JournalFormat::Ledger | JournalFormat::Hledger => {
+                writeln!(self.writer, "{} * Balance assertion", valuation.date)?;
+                self.write_comment("id", &valuation.id.to_string())?;
+                writeln!(
+                    self.writer,
+                    "{INDENT}{account}  0 {currency} = {balance} {currency}"
+                )?;
+            }
+            JournalFormat::Beancount => {
+                let date = valuation
+                    .date
+                    .checked_add_days(Days::new(1))
+                    .unwrap_or(valuation.date);
+                writeln!(
+                    self.writer,
+                    "{date} balance {account}  {balance} {currency}"
+                )?;
+            }
+        }
+        writeln!(self.writer)?;
+
+        self.written = self.written.saturating_add(1);
+        Ok(())
+    }
+
+    /// Flush buffered entries to the underlying writer
+    ///
+    /// # Errors
+    /// Returns an I/O error if the underlying writer fails.
+    pub fn flush(&mut self) -> Result<(), ExportError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Number of entries (transactions, opening balances and balance
+    /// assertions) written so far
+    pub const fn written(&self) -> usize {
+        self.written
+    }
+
+    fn reference_name(&self, account: &transaction::Account) -> String {
+        self.accounts.get(&account.id).cloned().unwrap_or_else(|| {
+            let kind = kind_of(&account.account_type);
+            let root = JournalRoot::of(account.classification.as_ref(), kind);
+            let kind = kind.map_or_else(
+                || account_component(&account.account_type),
+                |kind| kind.to_string(),
+            );
+            format!(
+                "{}:{kind}:{}",
+                root.as_str(),
+                account_component(&account.name)
+            )
+        })
+    }
+
+    /// Income or expense account of a transaction's category
+    fn category_account(&self, transaction: &Transaction) -> String {
+        let root = if transaction.classification == Classification::Income {
+            "Income"
+        } else {
+            "Expenses"
+        };
+        let category = transaction.category.as_ref().map_or_else(
+            || UNCATEGORIZED.to_string(),
+            |category| {
+                self.categories
+                    .get(&category.id)
+                    .cloned()
+                    .unwrap_or_else(|| account_component(&category.name))
+            },
+        );
+        format!("{root}:{category}")
+    }
+
+    /// Write a beancount `open` directive the first time an account is used
+    fn open(&mut self, account: &str) -> Result<(), ExportError> {
+        if self.format == JournalFormat::Beancount && self.opened.insert(account.to_string()) {
+            writeln!(self.writer, "{BEANCOUNT_OPEN_DATE} open {account}\n")?;
+        }
+        Ok(())
+    }
+
+    fn write_header(
+        &mut self,
+        transaction: &Transaction,
+        date: NaiveDate,
+    ) -> Result<(), ExportError> {
+        let name = single_line(&transaction.name);
+        let merchant = transaction
+            .merchant
+            .as_ref()
+            .map(|merchant| single_line(&merchant.name));
+        let tags: Vec<String> = transaction
+            .tags
+            .iter()
+            .map(|tag| tag_component(&tag.name))
+            .collect();
+        let notes = transaction.notes.as_deref().map(single_line);
+
+        match self.format {
+            JournalFormat::Ledger => {
+                writeln!(self.writer, "{date} * {name}")?;
+                self.write_comment("id", &transaction.id.to_string())?;
+                if let Some(merchant) = &merchant {
+                    self.write_comment("Payee", merchant)?;
+                }
+                if !tags.is_empty() {
+                    writeln!(self.writer, "{INDENT}; :{}:", tags.join(":"))?;
+                }
+            }
+            JournalFormat::Hledger => {
+                // hledger reads "payee | note" descriptions
+                match &merchant {
+                    Some(merchant) => writeln!(self.writer, "{date} * {merchant} | {name}")?,
+                    None => writeln!(self.writer, "{date} * {name}")?,
+                }
+                self.write_comment("id", &transaction.id.to_string())?;
+                if !tags.is_empty() {
+                    let tags: Vec<String> = tags.iter().map(|tag| format!("{tag}:")).collect();
+                    writeln!(self.writer, "{INDENT}; {}", tags.join(", "))?;
+                }
+            }
+            JournalFormat::Beancount => {
+                write!(self.writer, "{date} *")?;
+                if let Some(merchant) = &merchant {
+                    write!(self.writer, " {}", quoted(merchant))?;
+                }
+                write!(self.writer, " {}", quoted(&name))?;
+                for tag in &tags {
+                    write!(self.writer, " #{tag}")?;
+                }
+                writeln!(self.writer)?;
+                writeln!(
+                    self.writer,
+                    "{INDENT}id: {}",
+                    quoted(&transaction.id.to_string())
+                )?;
+                if let Some(notes) = &notes {
+                    writeln!(self.writer, "{INDENT}notes: {}", quoted(notes))?;
+                }
+                return Ok(());
+            }
+        }
+
+        if let Some(notes) = &notes {
+            writeln!(self.writer, "{INDENT}; {notes}")?;
+        }
+        Ok(())
+    }
+
+    /// Write a `key: value` comment (ledger metadata, hledger tag)
+    fn write_comment(&mut self, key: &str, value: &str) -> Result<(), ExportError> {
+        match self.format {
+            JournalFormat::Ledger => writeln!(self.writer, "{INDENT}; {key}: {value}")?,
+            JournalFormat::Hledger | JournalFormat::Beancount => {
+                writeln!(self.writer, "{INDENT}; {key}:{value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::category::CategoryDetail;
+    use crate::test_fixtures;
+
+    fn account(id: u128, name: &str, classification: &str, kind: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": uuid::Uuid::from_u128(id),
+            "name": name,
+            "classification": classification,
+            "account_type": kind
+        })
+    }
+
+    fn transaction(
+        id: u128,
+        signed_amount_cents: i64,
+        mut extra: serde_json::Value,
+    ) -> Transaction {
+        if let Some(extra) = extra.as_object_mut() {
+            extra
+                .entry("account")
+                .or_insert_with(|| account(10, "Everyday", "asset", "depository"));
+        }
+        test_fixtures::transaction_with(
+            id,
+            "2024-03-01",
+            "Coffee, \"large\"",
+            signed_amount_cents,
+            extra,
+        )
+    }
+
+    fn journal<F: FnOnce(&mut JournalWriter<&mut Vec<u8>>)>(
+        format: JournalFormat,
+        write: F,
+    ) -> String {
+        let mut output = Vec::new();
+        let mut writer = JournalWriter::new(&mut output, format, &[]);
+        write(&mut writer);
+        drop(writer);
+        String::from_utf8(output).expect("UTF-8 output")
+    }
+
+    #[test]
+    fn writes_ledger_entries_and_transfers_once() {
+        let coffee = transaction(
+            1,
+            -450,
+            serde_json::json!({
+                "category": { "id": uuid::Uuid::from_u128(3), "name": "Dining out", "color": "#e99537", "icon": "utensils" },
+                "tags": [{ "id": uuid::Uuid::from_u128(4), "name": "work trip", "color": "#4da568" }]
+            }),
+        );
+        let transfer = serde_json::json!({
+            "transfer": {
+                "id": uuid::Uuid::from_u128(5),
+                "amount": "NZ$100.00",
+                "currency": "NZD",
+                "other_account": account(11, "Visa", "liability", "credit_card")
+            }
+        });
+        let payment = transaction(2, -10000, transfer.clone());
+        let other_side = transaction(6, 10000, transfer);
+
+        let output = journal(JournalFormat::Ledger, |writer| {
+            assert!(writer.write_transaction(&coffee).expect("writes"));
+            assert!(writer.write_transaction(&payment).expect("writes"));
+            assert!(!writer.write_transaction(&other_side).expect("writes"));
+            assert_eq!(writer.written(), 2);
+        });
+
+        assert_eq!(
+            output,
+            "2024-03-01 * Coffee, \"large\"\n\
+             \x20 ; id: 00000000-0000-0000-0000-000000000001\n\
+             \x20 ; :work-trip:\n\
+             \x20 Assets:Depository:Everyday  -4.50 NZD\n\
+             \x20 Expenses:Dining-out  4.50 NZD\n\
+             \n\
+             2024-03-01 * Coffee, \"large\"\n\
+             \x20 ; id: 00000000-0000-0000-0000-000000000002\n\
+             \x20 Assets:Depository:Everyday  -100.00 NZD\n\
+             \x20 Liabilities:CreditCard:Visa  100.00 NZD\n\
+             \n"
+        );
+    }
+
+    #[test]
+    fn writes_beancount_with_open_directives_and_balances() {
+        let coffee = transaction(
+            1,
+            -450,
+            serde_json::json!({ "merchant": { "id": uuid::Uuid::from_u128(7), "name": "Coffee Co" } }),
+        );
+        let valuation: Valuation = serde_json::from_value(serde_json::json!({
+            "id": uuid::Uuid::from_u128(8),
+            "date": "2024-03-31",
+            "amount": "NZ$1,234.56",
+            "currency": "NZD",
+            "kind": "reconciliation",
+            "account": account(11, "Visa", "liability", "credit_card"),
+            "created_at": "2024-03-31T00:00:00Z",
+            "updated_at": "2024-03-31T00:00:00Z"
+        }))
+        .expect("valid valuation fixture");
+
+        let output = journal(JournalFormat::Beancount, |writer| {
+            writer.write_transaction(&coffee).expect("writes");
+            writer.write_valuation(&valuation).expect("writes");
+        });
+
+        assert_eq!(
+            output,
+            "1970-01-01 open Assets:Depository:Everyday\n\n\
+             1970-01-01 open Expenses:Uncategorized\n\n\
+             2024-03-01 * \"Coffee Co\" \"Coffee, \\\"large\\\"\"\n\
+             \x20 id: \"00000000-0000-0000-0000-000000000001\"\n\
+             \x20 Assets:Depository:Everyday  -4.50 NZD\n\
+             \x20 Expenses:Uncategorized  4.50 NZD\n\
+             \n\
+             1970-01-01 open Liabilities:CreditCard:Visa\n\n\
+             2024-04-01 balance Liabilities:CreditCard:Visa  -1234.56 NZD\n\
+             \n"
+        );
+    }
+
+    #[test]
+    fn writes_opening_balances_and_category_paths() {
+        let category = |id: u128, name: &str, parent: Option<(u128, &str)>| {
+            serde_json::from_value::<CategoryDetail>(serde_json::json!({
+                "id": uuid::Uuid::from_u128(id),
+                "name": name,
+                "color": "#4da568",
+                "icon": "shopping-cart",
+                "parent": parent.map(|(id, name)| serde_json::json!({
+                    "id": uuid::Uuid::from_u128(id),
+                    "name": name
+                })),
+                "subcategories_count": 0,
+                "created_at": "2024-03-01T00:00:00Z",
+                "updated_at": "2024-03-01T00:00:00Z"
+            }))
+            .expect("valid category fixture")
+        };
+        let tree = CategoryTree::new([
+            category(20, "Food", None),
+            category(21, "Groceries", Some((20, "Food"))),
+        ]);
+        let valuation = |id: u128, date: &str, amount: &str, kind: &str| {
+            serde_json::from_value::<Valuation>(serde_json::json!({
+                "id": uuid::Uuid::from_u128(id),
+                "date": date,
+                "amount": amount,
+                "currency": "NZD",
+                "kind": kind,
+                "account": account(10, "Everyday", "asset", "depository"),
+                "created_at": "2024-03-01T00:00:00Z",
+                "updated_at": "2024-03-01T00:00:00Z"
+            }))
+            .expect("valid valuation fixture")
+        };
+        let groceries = transaction(
+            1,
+            -4500,
+            serde_json::json!({
+                "category": { "id": uuid::Uuid::from_u128(21), "name": "Groceries", "color": "#4da568", "icon": "shopping-cart" }
+            }),
+        );
+
+        let mut output = Vec::new();
+        let mut writer =
+            JournalWriter::new(&mut output, JournalFormat::Hledger, &[]).with_categories(&tree);
+        writer
+            .write_valuation(&valuation(8, "2024-02-29", "NZ$1,000.00", "opening_anchor"))
+            .expect("writes");
+        writer.write_transaction(&groceries).expect("writes");
+        writer
+            .write_valuation(&valuation(9, "2024-03-31", "NZ$955.00", "reconciliation"))
+            .expect("writes");
+        assert_eq!(writer.written(), 3);
+        drop(writer);
+
+        assert_eq!(
+            String::from_utf8(output).expect("UTF-8 output"),
+            "2024-02-29 * Opening balance\n\
+             \x20 ; id:00000000-0000-0000-0000-000000000008\n\
+             \x20 Assets:Depository:Everyday  1000.00 NZD\n\
+             \x20 Equity:Opening-Balances  -1000.00 NZD\n\
+             \n\
+             2024-03-01 * Coffee, \"large\"\n\
+             \x20 ; id:00000000-0000-0000-0000-000000000001\n\
+             \x20 Assets:Depository:Everyday  -45.00 NZD\n\
+             \x20 Expenses:Food:Groceries  45.00 NZD\n\
+             \n\
+             2024-03-31 * Balance assertion\n\
+             \x20 ; id:00000000-0000-0000-0000-000000000009\n\
+             \x20 Assets:Depository:Everyday  0 NZD = 955.00 NZD\n\
+             \n"
+        );
+    }
+
+    #[test]
+    fn names_accounts_from_classification_then_kind() {
+        assert_eq!(
+            JournalRoot::of(
+                Some(&AccountClassification::Liability),
+                Some(AccountKind::Depository)
+            ),
+            JournalRoot::Liabilities
+        );
+        assert_eq!(
+            JournalRoot::of(None, Some(AccountKind::Loan)),
+            JournalRoot::Liabilities
+        );
+        assert_eq!(
+            JournalRoot::of(
+                Some(&AccountClassification::Other("equity".to_string())),
+                None
+            ),
+            JournalRoot::Assets
+        );
+        assert_eq!(account_component("  joint savings (2) "), "Joint-savings-2");
+        assert_eq!(account_component("!!"), "Unnamed");
+    }
+}
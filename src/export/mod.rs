@@ -0,0 +1,346 @@
+//! Exporting transactions to files
+//!
+//! [`TransactionWriter`] writes transactions as CSV or JSON Lines, one flat
+//! [`ExportRow`] per transaction, with the nested account, category, merchant
+//! and tags flattened into columns. Columns always appear in the order of
+//! [`ExportRow::COLUMNS`], so exports from different runs line up.
+//!
+//! [`SureClient::export_transactions`](crate::SureClient::export_transactions)
+//! streams every transaction matching a filter through a writer page by page,
+//! so large exports are never held in memory.
+//!
+//! Double-entry journals for ledger, hledger and beancount are written by
+//! [`journal`].
+
+pub mod journal;
+
+use std::io::Write;
+
+use chrono::format::{Item, StrftimeItems};
+use serde::Serialize;
+
+use crate::error::ExportError;
+use crate::models::transaction::Transaction;
+
+/// Date format used when none is given
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Separator between tag names in the `tags` column
+pub const TAG_SEPARATOR: &str = "; ";
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row
+    #[default]
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+/// How amounts are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AmountStyle {
+    /// Positive for income, negative for expenses
+    #[default]
+    Signed,
+    /// Always positive; the `classification` column gives the direction
+    Unsigned,
+}
+
+/// Options controlling how each transaction is written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// File format
+    pub format: ExportFormat,
+    /// How amounts are written
+    pub amount_style: AmountStyle,
+    /// `chrono` format of the `date` column
+    pub date_format: String,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::default(),
+            amount_style: AmountStyle::default(),
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+        }
+    }
+}
+
+/// A transaction flattened into export columns.
+///
+/// Amounts are written as decimal strings in major units (e.g. `-4.50`) so no
+/// precision is lost, and optional values are empty (CSV) or `null` (JSON
+/// Lines) when absent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportRow {
+    /// Transaction ID
+    pub id: String,
+    /// Transaction date, in the export's date format
+    pub date: String,
+    /// Transaction name
+    pub name: String,
+    /// Amount, in the export's amount style
+    pub amount: String,
+    /// ISO 4217 currency code
+    pub currency: String,
+    /// `income` or `expense`
+    pub classification: String,
+    /// Account ID
+    pub account_id: String,
+    /// Account name
+    pub account_name: String,
+    /// Account type, e.g. `depository`
+    pub account_type: String,
+    /// Category ID
+    pub category_id: Option<String>,
+    /// Category name
+    pub category_name: Option<String>,
+    /// Merchant ID
+    pub merchant_id: Option<String>,
+    /// Merchant name
+    pub merchant_name: Option<String>,
+    /// Tag names, joined with [`TAG_SEPARATOR`]
+    pub tags: String,
+    /// Notes
+    pub notes: Option<String>,
+    /// ID of the transfer this transaction belongs to
+    pub transfer_id: Option<String>,
+}
+
+impl ExportRow {
+    /// Column names, in the order they are written
+    pub const COLUMNS: [&str; 16] = [
+        "id",
+        "date",
+        "name",
+        "amount",
+        "currency",
+        "classification",
+        "account_id",
+        "account_name",
+        "account_type",
+        "category_id",
+        "category_name",
+        "merchant_id",
+        "merchant_name",
+        "tags",
+        "notes",
+        "transfer_id",
+    ];
+
+    /// Flatten a transaction.
+    ///
+    /// `date_format` must be a valid `chrono` format; [`TransactionWriter::new`]
+    /// checks this before any row is built.
+    pub fn from_transaction(
+        transaction: &Transaction,
+        amount_style: AmountStyle,
+        date_format: &str,
+    ) -> Self {
+        let signed = transaction.signed_amount();
+        let amount = match amount_style {
+            AmountStyle::Signed => signed,
+            AmountStyle::Unsigned => signed.abs(),
+        };
+
+        Self {
+            id: transaction.id.to_string(),
+            date: transaction.date.format(date_format).to_string(),
+            name: transaction.name.clone(),
+            amount: amount.to_string(),
+            currency: transaction.currency.code().to_string(),
+            classification: transaction.classification.to_string(),
+            account_id: transaction.account.id.to_string(),
+            account_name: transaction.account.name.clone(),
+            account_type: transaction.account.account_type.clone(),
+            category_id: transaction.category.as_ref().map(|c| c.id.to_string()),
+            category_name: transaction.category.as_ref().map(|c| c.name.clone()),
+            merchant_id: transaction.merchant.as_ref().map(|m| m.id.to_string()),
+            merchant_name: transaction.merchant.as_ref().map(|m| m.name.clone()),
+            tags: transaction
+                .tags
+                .iter()
+                .map(|tag| tag.name.as_str())
+                .collect::<Vec<_>>()
+                .join(TAG_SEPARATOR),
+            notes: transaction.notes.clone(),
+            transfer_id: transaction.transfer.as_ref().map(|t| t.id.to_string()),
+        }
+    }
+}
+
+enum Sink<W: Write> {
+    Csv(Box<csv::Writer<W>>),
+    JsonLines(W),
+}
+
+/// Writes transactions to CSV or JSON Lines as they arrive
+pub struct TransactionWriter<W: Write> {
+    sink: Sink<W>,
+    amount_style: AmountStyle,
+    date_format: String,
+    written: usize,
+}
+
+impl<W: Write> TransactionWriter<W> {
+    /// Start an export. For CSV, the header row is written immediately, so an
+    /// export with no transactions still has its columns.
+    ///
+    /// # Errors
+    /// Returns `ExportError::InvalidDateFormat` if `options.date_format` is not
+    /// a valid `chrono` format, or an I/O error if the header cannot be written.
+    pub fn new(writer: W, options: ExportOptions) -> Result<Self, ExportError> {
+        if StrftimeItems::new(&options.date_format).any(|item| matches!(item, Item::Error)) {
+            return Err(ExportError::InvalidDateFormat(options.date_format));
+        }
+
+        let sink = match options.format {
+            ExportFormat::Csv => {
+                let mut csv = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(writer);
+                csv.write_record(ExportRow::COLUMNS)?;
+                Sink::Csv(Box::new(csv))
+            }
+            ExportFormat::JsonLines => Sink::JsonLines(writer),
+        };
+
+        Ok(Self {
+            sink,
+            amount_style: options.amount_style,
+            date_format: options.date_format,
+            written: 0,
+        })
+    }
+
+    /// Write one transaction
+    ///
+    /// # Errors
+    /// Returns an error if the row cannot be serialised or written.
+    pub fn write(&mut self, transaction: &Transaction) -> Result<(), ExportError> {
+        let row = ExportRow::from_transaction(transaction, self.amount_style, &self.date_format);
+        match &mut self.sink {
+            Sink::Csv(csv) => csv.serialize(&row)?,
+            Sink::JsonLines(writer) => {
+                serde_json::to_writer(&mut *writer, &row)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        self.written = self.written.saturating_add(1);
+        Ok(())
+    }
+
+    /// Flush buffered rows to the underlying writer
+    ///
+    /// # Errors
+    /// Returns an I/O error if the underlying writer fails.
+    pub fn flush(&mut self) -> Result<(), ExportError> {
+        match &mut self.sink {
+            Sink::Csv(csv) => csv.flush()?,
+            Sink::JsonLines(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+
+    /// Number of transactions written so far
+    pub const fn written(&self) -> usize {
+        self.written
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    fn transaction() -> Transaction {
+        test_fixtures::transaction_with(
+            1,
+            "2024-03-01",
+            "Coffee, large",
+            -450,
+            serde_json::json!({
+                "account": {
+                    "id": "00000000-0000-0000-0000-000000000002",
+                    "name": "Everyday",
+                    "account_type": "depository"
+                },
+                "category": {
+                    "id": "00000000-0000-0000-0000-000000000003",
+                    "name": "Dining",
+                    "color": "#e99537",
+                    "icon": "utensils"
+                },
+                "tags": [
+                    { "id": "00000000-0000-0000-0000-000000000004", "name": "work", "color": "#4da568" },
+                    { "id": "00000000-0000-0000-0000-000000000005", "name": "travel", "color": "#6471eb" }
+                ]
+            }),
+        )
+    }
+
+    fn export(options: ExportOptions) -> String {
+        let mut output = Vec::new();
+        let mut writer = TransactionWriter::new(&mut output, options).expect("valid options");
+        writer.write(&transaction()).expect("writes");
+        writer.flush().expect("flushes");
+        drop(writer);
+        String::from_utf8(output).expect("UTF-8 output")
+    }
+
+    #[test]
+    fn writes_csv_with_stable_columns() {
+        let output = export(ExportOptions {
+            amount_style: AmountStyle::Unsigned,
+            date_format: "%d/%m/%Y".to_string(),
+            ..ExportOptions::default()
+        });
+        let mut lines = output.lines();
+
+        assert_eq!(lines.next(), Some(ExportRow::COLUMNS.join(",").as_str()));
+        assert_eq!(
+            lines.next(),
+            Some(
+                "00000000-0000-0000-0000-000000000001,01/03/2024,\"Coffee, large\",4.50,NZD,expense,\
+                 00000000-0000-0000-0000-000000000002,Everyday,depository,\
+                 00000000-0000-0000-0000-000000000003,Dining,,,work; travel,,"
+            )
+        );
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let output = export(ExportOptions {
+            format: ExportFormat::JsonLines,
+            ..ExportOptions::default()
+        });
+        let row: serde_json::Value =
+            serde_json::from_str(output.trim_end()).expect("one JSON object");
+
+        let field = |name: &str| row.get(name).cloned();
+
+        assert_eq!(field("amount"), Some("-4.50".into()));
+        assert_eq!(field("date"), Some("2024-03-01".into()));
+        assert_eq!(field("merchant_name"), Some(serde_json::Value::Null));
+        assert_eq!(
+            row.as_object().map(serde_json::Map::len),
+            Some(ExportRow::COLUMNS.len())
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_date_format() {
+        let options = ExportOptions {
+            date_format: "%Q".to_string(),
+            ..ExportOptions::default()
+        };
+        assert!(matches!(
+            TransactionWriter::new(Vec::new(), options),
+            Err(ExportError::InvalidDateFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,298 @@
+//! Exchange rates for converting amounts between currencies
+//!
+//! Anything that converts money, such as
+//! [`net_worth`](crate::analysis::net_worth::net_worth), takes an
+//! [`FxRateProvider`], so every conversion uses the same rates:
+//!
+//! - [`StaticRates`] holds a table of rates set in code.
+//! - [`CsvRates`] loads the table from a CSV file with `from`, `to` and `rate`
+//!   columns and an optional `date` column:
+//!
+//! ```csv
+//! from,to,rate,date
+//! AUD,NZD,1.0842,2024-06-28
+//! USD,NZD,1.6389,2024-06-28
+//! ```
+//!
+//! A rate is how many units of `to` one unit of `from` buys. Both providers
+//! also use a pair's inverse, and a cross rate through a third currency when
+//! neither direction is known, so NZD to AUD above needs no extra row.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use iso_currency::Currency;
+use rust_decimal::Decimal;
+
+use crate::error::FxError;
+
+/// A source of exchange rates
+pub trait FxRateProvider: Send + Sync {
+    /// How many units of `to` one unit of `from` buys, or `None` if the rate
+    /// is unknown
+    fn rate(&self, from: Currency, to: Currency) -> Option<Decimal>;
+
+    /// Convert `amount` from one currency to another
+    ///
+    /// Returns `None` if the rate is unknown or the result overflows.
+    fn convert(&self, amount: Decimal, from: Currency, to: Currency) -> Option<Decimal> {
+        if from == to {
+            return Some(amount);
+        }
+        amount.checked_mul(self.rate(from, to)?)
+    }
+}
+
+/// A fixed table of exchange rates
+///
+/// # Example
+/// ```
+/// use iso_currency::Currency;
+/// use rust_decimal::Decimal;
+/// use sure_client_rs::fx::{FxRateProvider, StaticRates};
+///
+/// let rates = StaticRates::new()
+///     .with_rate(Currency::USD, Currency::NZD, Decimal::new(16389, 4))
+///     .with_rate(Currency::AUD, Currency::NZD, Decimal::new(10842, 4));
+///
+/// assert_eq!(
+///     rates.convert(Decimal::from(100), Currency::USD, Currency::NZD),
+///     Some(Decimal::new(16389, 2)),
+/// );
+/// assert!(rates.rate(Currency::NZD, Currency::AUD).is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StaticRates {
+    rates: BTreeMap<(Currency, Currency), Decimal>,
+}
+
+impl StaticRates {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rate, replacing any earlier rate for the same pair
+    #[must_use]
+    pub fn with_rate(mut self, from: Currency, to: Currency, rate: Decimal) -> Self {
+        self.insert(from, to, rate);
+        self
+    }
+
+    /// Add a rate, returning the rate it replaced
+    ///
+    /// Rates that are zero or negative are stored but never used.
+    pub fn insert(&mut self, from: Currency, to: Currency, rate: Decimal) -> Option<Decimal> {
+        self.rates.insert((from, to), rate)
+    }
+
+    /// Number of rates in the table
+    pub fn len(&self) -> usize {
+        self.rates.len()
+    }
+
+    /// Returns `true` if the table has no rates
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// The rate for a pair from the table, either as given or inverted
+    fn known_rate(&self, from: Currency, to: Currency) -> Option<Decimal> {
+        let usable = |rate: &Decimal| rate.is_sign_positive() && !rate.is_zero();
+        self.rates
+            .get(&(from, to))
+            .copied()
+            .filter(usable)
+            .or_else(|| {
+                self.rates
+                    .get(&(to, from))
+                    .filter(|rate| usable(rate))
+                    .and_then(|rate| Decimal::ONE.checked_div(*rate))
+            })
+    }
+}
+
+impl FxRateProvider for StaticRates {
+    fn rate(&self, from: Currency, to: Currency) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+        self.known_rate(from, to).or_else(|| {
+            self.rates
+                .keys()
+                .flat_map(|(a, b)| [*a, *b])
+                .filter(|via| *via != from && *via != to)
+                .find_map(|via| {
+                    self.known_rate(from, via)?
+                        .checked_mul(self.known_rate(via, to)?)
+                })
+        })
+    }
+}
+
+/// Exchange rates loaded from a CSV file
+///
+/// The file needs a header row naming `from`, `to` and `rate` columns, in any
+/// order; currencies are ISO 4217 codes. With an optional `date` column
+/// (`YYYY-MM-DD`), a file of historical rates can be loaded as is: the most
+/// recent row for each pair is used. Otherwise a later row for a pair
+/// replaces an earlier one.
+///
+/// # Example
+/// ```
+/// use iso_currency::Currency;
+/// use rust_decimal::Decimal;
+/// use sure_client_rs::fx::{CsvRates, FxRateProvider};
+///
+/// let csv = "from,to,rate,date\n\
+///            USD,NZD,1.60,2024-05-31\n\
+///            USD,NZD,1.6389,2024-06-28\n";
+/// let rates = CsvRates::from_reader(csv.as_bytes())?;
+///
+/// assert_eq!(rates.rate(Currency::USD, Currency::NZD), Some(Decimal::new(16389, 4)));
+/// # Ok::<(), sure_client_rs::FxError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvRates {
+    rates: StaticRates,
+    as_of: Option<NaiveDate>,
+}
+
+#[derive(serde::Deserialize)]
+struct RateRow {
+    from: String,
+    to: String,
+    rate: String,
+    #[serde(default)]
+    date: Option<String>,
+}
+
+impl CsvRates {
+    /// Read rates from CSV
+    ///
+    /// # Errors
+    /// Returns `FxError::Csv` if the CSV is malformed or lacks a required
+    /// column, `FxError::InvalidRate` if a currency, rate or date cannot be
+    /// parsed or a rate is not positive, or `FxError::Io` if reading fails.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, FxError> {
+        let mut csv = ::csv::ReaderBuilder::new()
+            .trim(::csv::Trim::All)
+            .from_reader(reader);
+        let headers = csv.headers()?.clone();
+
+        let mut dates: BTreeMap<(Currency, Currency), Option<NaiveDate>> = BTreeMap::new();
+        let mut loaded = Self::default();
+        for record in csv.records() {
+            let record = record?;
+            let line = record.position().map_or(0, ::csv::Position::line);
+            let invalid = |message: String| FxError::InvalidRate { line, message };
+
+            let row: RateRow = record.deserialize(Some(&headers))?;
+            let currency = |code: &str| {
+                Currency::from_code(&code.to_ascii_uppercase())
+                    .ok_or_else(|| invalid(format!("unknown currency {code:?}")))
+            };
+            let from = currency(&row.from)?;
+            let to = currency(&row.to)?;
+            let rate = row
+                .rate
+                .parse::<Decimal>()
+                .ok()
+                .filter(|rate| rate.is_sign_positive() && !rate.is_zero())
+                .ok_or_else(|| invalid(format!("{:?} is not a positive rate", row.rate)))?;
+            let date = row
+                .date
+                .filter(|date| !date.is_empty())
+                .map(|date| {
+                    date.parse::<NaiveDate>()
+                        .map_err(|_| invalid(format!("{date:?} is not a YYYY-MM-DD date")))
+                })
+                .transpose()?;
+
+            let newest = dates.get(&(from, to)).is_none_or(|seen| date >= *seen);
+            if newest {
+                dates.insert((from, to), date);
+                loaded.rates.insert(from, to, rate);
+                loaded.as_of = loaded.as_of.max(date);
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    /// Load rates from a CSV file
+    ///
+    /// # Errors
+    /// Returns `FxError::Io` if the file cannot be opened, or any error from
+    /// [`from_reader`](Self::from_reader).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, FxError> {
+        Self::from_reader(std::fs::File::open(path)?)
+    }
+
+    /// The rates that were loaded
+    pub const fn rates(&self) -> &StaticRates {
+        &self.rates
+    }
+
+    /// The most recent date of the rates used, if the file has dates
+    pub const fn as_of(&self) -> Option<NaiveDate> {
+        self.as_of
+    }
+}
+
+impl FxRateProvider for CsvRates {
+    fn rate(&self, from: Currency, to: Currency) -> Option<Decimal> {
+        self.rates.rate(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn static_rates_invert_and_cross_through_a_third_currency() {
+        let rates = StaticRates::new()
+            .with_rate(Currency::USD, Currency::NZD, Decimal::new(16, 1))
+            .with_rate(Currency::AUD, Currency::NZD, Decimal::new(11, 1));
+
+        assert_eq!(
+            rates.rate(Currency::NZD, Currency::USD),
+            Some(Decimal::new(625, 3))
+        );
+        assert_eq!(
+            rates
+                .convert(Decimal::from(10), Currency::USD, Currency::AUD)
+                .map(|amount| amount.round_dp(2)),
+            Some(Decimal::new(1455, 2))
+        );
+        assert_eq!(rates.rate(Currency::EUR, Currency::NZD), None);
+        assert_eq!(
+            rates.convert(Decimal::from(5), Currency::EUR, Currency::EUR),
+            Some(Decimal::from(5))
+        );
+    }
+
+    #[test]
+    fn csv_rates_keep_the_latest_row_and_report_bad_lines() {
+        let csv = "date,from,to,rate\n\
+                   2024-06-28,usd,NZD,1.64\n\
+                   2024-05-31,USD,NZD,1.60\n\
+                   ,AUD,NZD,1.08\n";
+        let rates = CsvRates::from_reader(csv.as_bytes()).expect("valid rates");
+        assert_eq!(
+            rates.rate(Currency::USD, Currency::NZD),
+            Some(Decimal::new(164, 2))
+        );
+        assert_eq!(rates.rates().len(), 2);
+        assert_eq!(rates.as_of(), NaiveDate::from_ymd_opt(2024, 6, 28));
+
+        let csv = "from,to,rate\nUSD,NZD,1.64\nUSD,XYZ,2\n";
+        match CsvRates::from_reader(csv.as_bytes()) {
+            Err(FxError::InvalidRate { line, .. }) => assert_eq!(line, 3),
+            other => panic!("expected an invalid rate, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,732 @@
+//! CSV import driven by column mapping profiles
+//!
+//! Every bank lays out its CSV export differently. A [`CsvProfile`] describes
+//! one layout: which columns hold the date, amount, name, notes and currency,
+//! how dates are written, and how the amount's sign encodes direction.
+//! Profiles can be saved as TOML or JSON and reused for every export from the
+//! same bank.
+//!
+//! # Example
+//! ```no_run
+//! use sure_client_rs::{SureClient, AccountId};
+//! use sure_client_rs::import::csv::CsvProfile;
+//! use uuid::Uuid;
+//!
+//! # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let profile = CsvProfile::load("profiles/kiwibank.toml")?;
+//! let file = std::io::BufReader::new(std::fs::File::open("export.csv")?);
+//! let parsed = profile.read_transactions(file, AccountId::new(Uuid::new_v4()))?;
+//!
+//! for error in &parsed.errors {
+//!     eprintln!("skipped {error}");
+//! }
+//!
+//! let report = client.import_transactions()
+//!     .transactions(parsed.transactions())
+//!     .call()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::BufRead;
+use std::path::Path;
+
+use bon::Builder;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::error::ImportError;
+use crate::models::transaction::{CreateTransactionData, TransactionNature};
+use crate::serde::parse_decimal;
+use crate::types::AccountId;
+
+/// Date format used when a profile does not specify one
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// How a single signed amount column encodes direction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignConvention {
+    /// Negative amounts are money out, as in most bank account exports
+    #[default]
+    NegativeIsExpense,
+    /// Positive amounts are money out, as in many credit card exports
+    PositiveIsExpense,
+}
+
+/// The character between whole units and the fraction in amount cells
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecimalSeparator {
+    /// `1,234.56`; commas are thousands separators
+    #[default]
+    Dot,
+    /// `1.234,56`; dots are thousands separators
+    Comma,
+}
+
+impl DecimalSeparator {
+    /// The separator character
+    pub const fn as_char(self) -> char {
+        match self {
+            Self::Dot => '.',
+            Self::Comma => ',',
+        }
+    }
+}
+
+/// Where a profile finds the transaction amount
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AmountColumns {
+    /// A single column holding a signed amount
+    Signed {
+        /// Column holding the amount
+        column: String,
+        /// How the sign of the amount encodes direction
+        #[serde(default)]
+        sign: SignConvention,
+    },
+    /// Separate columns for money out and money in. Each row should fill at
+    /// most one of them; empty cells and zeroes are ignored.
+    DebitCredit {
+        /// Column holding money out
+        debit: String,
+        /// Column holding money in
+        credit: String,
+    },
+}
+
+/// Column mapping for one bank's CSV export.
+///
+/// Columns are referred to by their header name.
+///
+/// A profile for a Kiwibank export, as TOML:
+///
+/// ```toml
+/// date_column = "Date"
+/// date_format = "%d-%m-%Y"
+/// name_column = "Memo/Description"
+/// currency = "NZD"
+///
+/// [amount]
+/// kind = "debit_credit"
+/// debit = "Amount (debit)"
+/// credit = "Amount (credit)"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Builder)]
+#[serde(deny_unknown_fields)]
+pub struct CsvProfile {
+    /// Field delimiter (default: `,`)
+    #[serde(default = "default_delimiter")]
+    #[builder(default = default_delimiter())]
+    pub delimiter: char,
+    /// Number of lines before the header row, for exports that start with a
+    /// preamble such as the account number (default: 0)
+    #[serde(default)]
+    #[builder(default)]
+    pub skip_lines: usize,
+    /// Column holding the transaction date
+    #[builder(into)]
+    pub date_column: String,
+    /// `chrono` format of the date column, e.g. `%d/%m/%Y` (default: `%Y-%m-%d`)
+    #[serde(default = "default_date_format")]
+    #[builder(default = default_date_format(), into)]
+    pub date_format: String,
+    /// Where the amount is found
+    pub amount: AmountColumns,
+    /// Decimal separator of the amount columns (default: `dot`)
+    #[serde(default)]
+    #[builder(default)]
+    pub decimal_separator: DecimalSeparator,
+    /// Column holding the transaction name
+    #[builder(into)]
+    pub name_column: String,
+    /// Column holding additional notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(into)]
+    pub notes_column: Option<String>,
+    /// Column holding the ISO 4217 currency code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(into)]
+    pub currency_column: Option<String>,
+    /// Currency used when there is no currency column, or the cell is empty.
+    /// Without either, transactions use the family currency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<iso_currency::Currency>,
+}
+
+const fn default_delimiter() -> char {
+    ','
+}
+
+fn default_date_format() -> String {
+    DEFAULT_DATE_FORMAT.to_string()
+}
+
+/// A CSV row that parsed into a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvRow {
+    /// Line number of the row in the file (1-based)
+    pub line: u64,
+    /// The transaction to create
+    pub transaction: CreateTransactionData,
+}
+
+/// Why a CSV row was rejected
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CsvRowErrorKind {
+    /// The row could not be read, e.g. it is not valid UTF-8
+    #[error("malformed row: {0}")]
+    Malformed(String),
+    /// A required cell is missing or empty
+    #[error("missing value for column `{0}`")]
+    MissingValue(String),
+    /// The date does not match the profile's date format
+    #[error("invalid date `{value}` (expected format `{format}`)")]
+    InvalidDate {
+        /// The cell contents
+        value: String,
+        /// The profile's date format
+        format: String,
+    },
+    /// The amount is not a number
+    #[error("invalid amount `{0}`")]
+    InvalidAmount(String),
+    /// The amount is zero
+    #[error("amount is zero")]
+    ZeroAmount,
+    /// Both the debit and the credit column hold an amount
+    #[error("both debit `{debit}` and credit `{credit}` are set")]
+    DebitAndCredit {
+        /// The debit cell
+        debit: String,
+        /// The credit cell
+        credit: String,
+    },
+    /// The currency is not an ISO 4217 code
+    #[error("unknown currency `{0}`")]
+    InvalidCurrency(String),
+}
+
+/// A CSV row that failed parsing or validation
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("line {line}: {kind}")]
+pub struct CsvRowError {
+    /// Line number of the row in the file (1-based)
+    pub line: u64,
+    /// Why the row was rejected
+    pub kind: CsvRowErrorKind,
+}
+
+/// The result of reading a CSV file with a profile
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvImport {
+    /// Rows that parsed, in file order
+    pub rows: Vec<CsvRow>,
+    /// Rows that were rejected, in file order
+    pub errors: Vec<CsvRowError>,
+}
+
+impl CsvImport {
+    /// The parsed transactions, in file order.
+    ///
+    /// The `i`-th transaction comes from `rows[i]`, so results reported per
+    /// transaction can be traced back to a line of the file.
+    pub fn transactions(&self) -> Vec<CreateTransactionData> {
+        self.rows
+            .iter()
+            .map(|row| row.transaction.clone())
+            .collect()
+    }
+}
+
+/// Header positions of the columns a profile uses
+struct ColumnIndices<'a> {
+    date: usize,
+    amount: AmountIndices<'a>,
+    name: usize,
+    notes: Option<usize>,
+    currency: Option<usize>,
+}
+
+enum AmountIndices<'a> {
+    Signed {
+        index: usize,
+        column: &'a str,
+        sign: SignConvention,
+    },
+    DebitCredit {
+        debit: usize,
+        credit: usize,
+        debit_column: &'a str,
+    },
+}
+
+impl CsvProfile {
+    /// Parse a profile from TOML
+    ///
+    /// # Errors
+    /// Returns `ImportError::TomlDeserialization` if the TOML is not a valid profile.
+    pub fn from_toml(toml: &str) -> Result<Self, ImportError> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Write the profile as TOML
+    ///
+    /// # Errors
+    /// Returns `ImportError::TomlSerialization` if the profile cannot be written.
+    pub fn to_toml(&self) -> Result<String, ImportError> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Parse a profile from JSON
+    ///
+    /// # Errors
+    /// Returns `ImportError::Json` if the JSON is not a valid profile.
+    pub fn from_json(json: &str) -> Result<Self, ImportError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Write the profile as JSON
+    ///
+    /// # Errors
+    /// Returns `ImportError::Json` if the profile cannot be written.
+    pub fn to_json(&self) -> Result<String, ImportError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Load a profile from a `.toml` or `.json` file
+    ///
+    /// # Errors
+    /// Returns `ImportError::InvalidProfile` if the file extension is not
+    /// `toml` or `json`, `ImportError::Io` if the file cannot be read, or a
+    /// parse error if its contents are not a valid profile.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ImportError> {
+        let path = path.as_ref();
+        let format = ProfileFormat::of(path)?;
+        let contents = std::fs::read_to_string(path)?;
+        match format {
+            ProfileFormat::Toml => Self::from_toml(&contents),
+            ProfileFormat::Json => Self::from_json(&contents),
+        }
+    }
+
+    /// Save the profile to a `.toml` or `.json` file
+    ///
+    /// # Errors
+    /// Returns `ImportError::InvalidProfile` if the file extension is not
+    /// `toml` or `json`, or `ImportError::Io` if the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ImportError> {
+        let path = path.as_ref();
+        let contents = match ProfileFormat::of(path)? {
+            ProfileFormat::Toml => self.to_toml()?,
+            ProfileFormat::Json => self.to_json()?,
+        };
+        Ok(std::fs::write(path, contents)?)
+    }
+
+    /// Read transactions for `account_id` from a CSV export.
+    ///
+    /// Rows that fail parsing or validation are collected in
+    /// [`CsvImport::errors`] with their line number; the remaining rows are
+    /// still returned.
+    ///
+    /// # Errors
+    /// Returns `ImportError::InvalidProfile` if the delimiter is not an ASCII
+    /// character, `ImportError::MissingColumn` if a column named by the profile
+    /// is not in the header, or `ImportError::Io`/`ImportError::Csv` if the
+    /// header cannot be read.
+    pub fn read_transactions<R: BufRead>(
+        &self,
+        mut reader: R,
+        account_id: AccountId,
+    ) -> Result<CsvImport, ImportError> {
+        let delimiter = u8::try_from(self.delimiter)
+            .ok()
+            .filter(u8::is_ascii)
+            .ok_or_else(|| {
+                ImportError::InvalidProfile(format!(
+                    "delimiter `{}` is not an ASCII character",
+                    self.delimiter
+                ))
+            })?;
+
+        let mut discarded = String::new();
+        for _ in 0..self.skip_lines {
+            discarded.clear();
+            reader.read_line(&mut discarded)?;
+        }
+        let line_offset = u64::try_from(self.skip_lines).unwrap_or(u64::MAX);
+
+        let mut csv = ::csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(::csv::Trim::All)
+            .from_reader(reader);
+        let columns = self.column_indices(csv.headers()?)?;
+
+        let mut import = CsvImport::default();
+        for record in csv.records() {
+            let (line, parsed) = match record {
+                Ok(record) => {
+                    let line = record.position().map_or(0, ::csv::Position::line);
+                    (line, self.parse_record(&record, &columns, account_id))
+                }
+                Err(error) => {
+                    let line = error.position().map_or(0, ::csv::Position::line);
+                    (line, Err(CsvRowErrorKind::Malformed(error.to_string())))
+                }
+            };
+            let line = line.saturating_add(line_offset);
+
+            match parsed {
+                Ok(transaction) => import.rows.push(CsvRow { line, transaction }),
+                Err(kind) => import.errors.push(CsvRowError { line, kind }),
+            }
+        }
+
+        Ok(import)
+    }
+
+    fn column_indices(
+        &self,
+        headers: &::csv::StringRecord,
+    ) -> Result<ColumnIndices<'_>, ImportError> {
+        let find = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or_else(|| ImportError::MissingColumn(name.to_string()))
+        };
+
+        Ok(ColumnIndices {
+            date: find(&self.date_column)?,
+            amount: match &self.amount {
+                AmountColumns::Signed { column, sign } => AmountIndices::Signed {
+                    index: find(column)?,
+                    column,
+                    sign: *sign,
+                },
+                AmountColumns::DebitCredit { debit, credit } => AmountIndices::DebitCredit {
+                    debit: find(debit)?,
+                    credit: find(credit)?,
+                    debit_column: debit,
+                },
+            },
+            name: find(&self.name_column)?,
+            notes: self.notes_column.as_deref().map(find).transpose()?,
+            currency: self.currency_column.as_deref().map(find).transpose()?,
+        })
+    }
+
+    fn parse_record(
+        &self,
+        record: &::csv::StringRecord,
+        columns: &ColumnIndices<'_>,
+        account_id: AccountId,
+    ) -> Result<CreateTransactionData, CsvRowErrorKind> {
+        let cell = |index: usize| record.get(index).filter(|value| !value.is_empty());
+        let required = |index: usize, column: &str| {
+            cell(index).ok_or_else(|| CsvRowErrorKind::MissingValue(column.to_string()))
+        };
+
+        let date = parse_date(
+            required(columns.date, &self.date_column)?,
+            &self.date_format,
+        )?;
+        let name = required(columns.name, &self.name_column)?;
+
+        let (amount, nature) = match &columns.amount {
+            AmountIndices::Signed {
+                index,
+                column,
+                sign,
+            } => {
+                let amount = self.parse_amount(required(*index, column)?)?;
+                let is_expense = match sign {
+                    SignConvention::NegativeIsExpense => amount.is_sign_negative(),
+                    SignConvention::PositiveIsExpense => amount.is_sign_positive(),
+                };
+                let nature = if is_expense {
+                    TransactionNature::Expense
+                } else {
+                    TransactionNature::Income
+                };
+                (amount.abs(), nature)
+            }
+            AmountIndices::DebitCredit {
+                debit,
+                credit,
+                debit_column,
+            } => {
+                let debit_cell = cell(*debit);
+                let credit_cell = cell(*credit);
+                if debit_cell.is_none() && credit_cell.is_none() {
+                    return Err(CsvRowErrorKind::MissingValue(debit_column.to_string()));
+                }
+                let debit_amount = debit_cell.map(|cell| self.parse_amount(cell)).transpose()?;
+                let credit_amount = credit_cell
+                    .map(|cell| self.parse_amount(cell))
+                    .transpose()?;
+                match (
+                    debit_amount.filter(|amount| !amount.is_zero()),
+                    credit_amount.filter(|amount| !amount.is_zero()),
+                ) {
+                    (Some(_), Some(_)) => {
+                        return Err(CsvRowErrorKind::DebitAndCredit {
+                            debit: debit_cell.unwrap_or_default().to_string(),
+                            credit: credit_cell.unwrap_or_default().to_string(),
+                        });
+                    }
+                    (Some(amount), None) => (amount.abs(), TransactionNature::Expense),
+                    (None, Some(amount)) => (amount.abs(), TransactionNature::Income),
+                    (None, None) => (Decimal::ZERO, TransactionNature::Expense),
+                }
+            }
+        };
+        if amount.is_zero() {
+            return Err(CsvRowErrorKind::ZeroAmount);
+        }
+
+        let currency = match columns.currency.and_then(cell) {
+            Some(code) => Some(
+                iso_currency::Currency::from_code(&code.to_uppercase())
+                    .ok_or_else(|| CsvRowErrorKind::InvalidCurrency(code.to_string()))?,
+            ),
+            None => self.currency,
+        };
+
+        Ok(CreateTransactionData {
+            account_id,
+            date,
+            amount,
+            name: name.to_string(),
+            notes: columns.notes.and_then(cell).map(str::to_string),
+            currency,
+            category_id: None,
+            merchant_id: None,
+            nature: Some(nature),
+            tag_ids: None,
+        })
+    }
+
+    fn parse_amount(&self, value: &str) -> Result<Decimal, CsvRowErrorKind> {
+        parse_decimal(value, self.decimal_separator.as_char())
+            .map_err(|_| CsvRowErrorKind::InvalidAmount(value.to_string()))
+    }
+}
+
+/// File formats a profile can be stored in
+enum ProfileFormat {
+    Toml,
+    Json,
+}
+
+impl ProfileFormat {
+    fn of(path: &Path) -> Result<Self, ImportError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Ok(Self::Toml),
+            Some("json") => Ok(Self::Json),
+            _ => Err(ImportError::InvalidProfile(format!(
+                "`{}` must have a .toml or .json extension",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Parse a date cell, accepting formats with or without a time component
+fn parse_date(value: &str, format: &str) -> Result<DateTime<Utc>, CsvRowErrorKind> {
+    NaiveDateTime::parse_from_str(value, format)
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, format).map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map(|datetime| datetime.and_utc())
+        .map_err(|_| CsvRowErrorKind::InvalidDate {
+            value: value.to_string(),
+            format: format.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn account() -> AccountId {
+        AccountId::new(Uuid::nil())
+    }
+
+    #[test]
+    fn reads_debit_credit_export_and_reports_bad_rows() {
+        let profile = CsvProfile::builder()
+            .date_column("Date")
+            .date_format("%d-%m-%Y")
+            .name_column("Memo/Description")
+            .amount(AmountColumns::DebitCredit {
+                debit: "Amount (debit)".to_string(),
+                credit: "Amount (credit)".to_string(),
+            })
+            .currency(iso_currency::Currency::NZD)
+            .build();
+        let csv = "\
+Date,Memo/Description,Amount (credit),Amount (debit)
+01-03-2024,COFFEE CO,,4.50
+02-03-2024,SALARY,\"2,500.00\",
+2024-03-03,BAD DATE,,1.00
+04-03-2024,,,1.00
+05-03-2024,BOTH,1.00,2.00
+";
+
+        let import = profile
+            .read_transactions(csv.as_bytes(), account())
+            .expect("valid header");
+
+        let parsed: Vec<_> = import
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.line,
+                    row.transaction.name.as_str(),
+                    row.transaction.signed_amount(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            parsed,
+            [
+                (2, "COFFEE CO", Decimal::new(-450, 2)),
+                (3, "SALARY", Decimal::new(250_000, 2)),
+            ]
+        );
+
+        let errors: Vec<_> = import.errors.iter().map(|error| error.line).collect();
+        assert_eq!(errors, [4, 5, 6]);
+        assert!(matches!(
+            import.errors.first().map(|error| &error.kind),
+            Some(CsvRowErrorKind::InvalidDate { .. })
+        ));
+    }
+
+    #[test]
+    fn reads_signed_export_with_preamble() {
+        let profile = CsvProfile::builder()
+            .skip_lines(2)
+            .delimiter(';')
+            .date_column("Posted")
+            .date_format("%Y/%m/%d")
+            .name_column("Payee")
+            .notes_column("Memo")
+            .currency_column("Currency")
+            .amount(AmountColumns::Signed {
+                column: "Amount".to_string(),
+                sign: SignConvention::PositiveIsExpense,
+            })
+            .build();
+        let csv = "\
+Account 12-3456-7890123-00
+Created 2024/03/31
+Posted;Payee;Memo;Amount;Currency
+2024/03/01;Bookshop;gift;30.00;nzd
+2024/03/02;Refund;;-12.00;
+";
+
+        let import = profile
+            .read_transactions(csv.as_bytes(), account())
+            .expect("valid header");
+
+        assert!(import.errors.is_empty(), "{:?}", import.errors);
+        let first = import.rows.first().expect("first row");
+        assert_eq!(first.line, 4);
+        assert_eq!(first.transaction.signed_amount(), Decimal::new(-3000, 2));
+        assert_eq!(first.transaction.notes.as_deref(), Some("gift"));
+        assert_eq!(
+            first.transaction.currency,
+            Some(iso_currency::Currency::NZD)
+        );
+        let second = import.rows.get(1).expect("second row");
+        assert_eq!(second.transaction.signed_amount(), Decimal::new(1200, 2));
+        assert_eq!(second.transaction.currency, None);
+    }
+
+    #[test]
+    fn reads_amounts_with_the_profile_decimal_separator() {
+        let csv = "\
+Date,Payee,Amount
+2024-03-01,Interest,0.125
+2024-03-02,Fee,-1.234
+2024-03-03,Transfer,\"-1.234,50\"
+";
+        let amounts = |decimal_separator| {
+            let profile = CsvProfile::builder()
+                .date_column("Date")
+                .name_column("Payee")
+                .amount(AmountColumns::Signed {
+                    column: "Amount".to_string(),
+                    sign: SignConvention::NegativeIsExpense,
+                })
+                .decimal_separator(decimal_separator)
+                .build();
+            let import = profile
+                .read_transactions(csv.as_bytes(), account())
+                .expect("valid header");
+            assert!(import.errors.is_empty(), "{:?}", import.errors);
+            import
+                .rows
+                .iter()
+                .map(|row| row.transaction.signed_amount())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            amounts(DecimalSeparator::Dot),
+            [
+                Decimal::new(125, 3),
+                Decimal::new(-1234, 3),
+                Decimal::new(-12345, 4)
+            ]
+        );
+        assert_eq!(
+            amounts(DecimalSeparator::Comma),
+            [
+                Decimal::from(125),
+                Decimal::from(-1234),
+                Decimal::new(-123_450, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_round_trips_through_toml_and_json() {
+        let toml = r#"
+date_column = "Date"
+date_format = "%d/%m/%Y"
+name_column = "Payee"
+currency = "NZD"
+
+[amount]
+kind = "signed"
+column = "Amount"
+"#;
+        let profile = CsvProfile::from_toml(toml).expect("valid profile");
+        assert_eq!(profile.delimiter, ',');
+        assert_eq!(profile.decimal_separator, DecimalSeparator::Dot);
+        assert_eq!(
+            profile.amount,
+            AmountColumns::Signed {
+                column: "Amount".to_string(),
+                sign: SignConvention::NegativeIsExpense,
+            }
+        );
+
+        let from_toml = CsvProfile::from_toml(&profile.to_toml().expect("serialises"));
+        let from_json = CsvProfile::from_json(&profile.to_json().expect("serialises"));
+        assert_eq!(from_toml.expect("round trips"), profile);
+        assert_eq!(from_json.expect("round trips"), profile);
+    }
+}
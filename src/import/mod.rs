@@ -0,0 +1,235 @@
+//! Helpers for importing transactions from external sources
+//!
+//! Re-running an import should not create duplicates. Each candidate row is
+//! reduced to a [`TransactionFingerprint`] and compared against the
+//! transactions already stored for the same accounts and date window; see
+//! [`SureClient::import_transactions`](crate::SureClient::import_transactions).
+//!
+//! Parsers for specific file formats live in submodules: [`csv`], [`ofx`] and [`qif`].
+
+pub mod csv;
+pub mod ofx;
+pub mod qif;
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::models::batch::BatchOutcome;
+use crate::models::transaction::{CreateTransactionData, Transaction};
+use crate::normalise::normalise_name;
+use crate::types::{AccountId, TransactionId};
+
+/// The attributes that identify a transaction across imports.
+///
+/// Two transactions with the same account, date, signed amount and normalised
+/// name are considered the same transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransactionFingerprint {
+    /// Account the transaction belongs to
+    pub account_id: AccountId,
+    /// Transaction date
+    pub date: NaiveDate,
+    /// Signed amount (positive for income, negative for expenses), normalised
+    /// so that `1.50` and `1.5` compare equal
+    pub signed_amount: Decimal,
+    /// Name normalised for case, punctuation and whitespace
+    pub name: String,
+}
+
+impl TransactionFingerprint {
+    /// Fingerprint a stored transaction
+    pub fn of_transaction(transaction: &Transaction) -> Self {
+        Self {
+            account_id: transaction.account.id,
+            date: transaction.date.date_naive(),
+            signed_amount: transaction.signed_amount().normalize(),
+            name: normalise_name(&transaction.name),
+        }
+    }
+
+    /// Fingerprint a candidate transaction that has not been created yet
+    pub fn of_candidate(candidate: &CreateTransactionData) -> Self {
+        Self {
+            account_id: candidate.account_id,
+            date: candidate.date.date_naive(),
+            signed_amount: candidate.signed_amount().normalize(),
+            name: normalise_name(&candidate.name),
+        }
+    }
+
+    /// The fingerprint without the name, used to detect conflicts
+    const fn amount_key(&self) -> (AccountId, NaiveDate, Decimal) {
+        (self.account_id, self.date, self.signed_amount)
+    }
+}
+
+/// What to do with a candidate row that matches an existing transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MatchAction {
+    /// Leave the existing transaction untouched
+    #[default]
+    Skip,
+    /// Copy the row's notes, category, merchant and tags onto the existing transaction
+    Update,
+}
+
+/// How a candidate row relates to the transactions already stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportMatch {
+    /// No stored transaction has the same account, date and amount
+    New,
+    /// A stored transaction has the same fingerprint
+    Matched(TransactionId),
+    /// Stored transactions share the account, date and amount but have a
+    /// different name. Conflicting rows are never written.
+    Conflict(Vec<TransactionId>),
+}
+
+/// The result of importing a single row
+#[derive(Debug)]
+pub struct ImportRow {
+    /// How the row relates to the stored transactions
+    pub status: ImportMatch,
+    /// Result of the request sent for this row: a creation for new rows, an
+    /// update for matched rows with [`MatchAction::Update`]. `None` when nothing
+    /// was sent, e.g. for skipped matches, conflicts and dry runs.
+    pub outcome: Option<BatchOutcome<Transaction>>,
+}
+
+/// Report for an import, with one entry per candidate row in input order
+#[derive(Debug)]
+pub struct ImportReport {
+    /// Result of each row, in input order
+    pub rows: Vec<ImportRow>,
+}
+
+impl ImportReport {
+    /// Indices of rows that did not match any stored transaction
+    pub fn new_rows(&self) -> Vec<usize> {
+        self.indices_where(|status| matches!(status, ImportMatch::New))
+    }
+
+    /// Indices of rows that matched a stored transaction
+    pub fn matched_rows(&self) -> Vec<usize> {
+        self.indices_where(|status| matches!(status, ImportMatch::Matched(_)))
+    }
+
+    /// Indices of rows that conflict with a stored transaction
+    pub fn conflicting_rows(&self) -> Vec<usize> {
+        self.indices_where(|status| matches!(status, ImportMatch::Conflict(_)))
+    }
+
+    /// Indices of rows whose request failed
+    pub fn failed_rows(&self) -> Vec<usize> {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.outcome.as_ref().is_some_and(BatchOutcome::is_failed))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn indices_where<P: Fn(&ImportMatch) -> bool>(&self, predicate: P) -> Vec<usize> {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| predicate(&row.status))
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+/// Classify candidate rows against stored transactions.
+///
+/// Each stored transaction can be matched by at most one row, so importing two
+/// identical rows (e.g. two coffees on the same day) against one stored
+/// transaction yields one match and one new row.
+pub fn classify_candidates(
+    candidates: &[CreateTransactionData],
+    existing: &[Transaction],
+) -> Vec<ImportMatch> {
+    let mut pool: HashMap<(AccountId, NaiveDate, Decimal), Vec<(TransactionId, String)>> =
+        HashMap::new();
+    for transaction in existing {
+        let fingerprint = TransactionFingerprint::of_transaction(transaction);
+        pool.entry(fingerprint.amount_key())
+            .or_default()
+            .push((transaction.id, fingerprint.name));
+    }
+
+    candidates
+        .iter()
+        .map(|candidate| {
+            let fingerprint = TransactionFingerprint::of_candidate(candidate);
+            let Some(stored) = pool.get_mut(&fingerprint.amount_key()) else {
+                return ImportMatch::New;
+            };
+
+            if let Some(position) = stored
+                .iter()
+                .position(|(_, name)| *name == fingerprint.name)
+            {
+                let (id, _) = stored.swap_remove(position);
+                ImportMatch::Matched(id)
+            } else if stored.is_empty() {
+                ImportMatch::New
+            } else {
+                ImportMatch::Conflict(stored.iter().map(|(id, _)| *id).collect())
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::transaction::TransactionNature;
+    use crate::test_fixtures::transaction;
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    fn candidate(name: &str, amount: Decimal) -> CreateTransactionData {
+        CreateTransactionData::builder()
+            .account_id(AccountId::new(Uuid::nil()))
+            .date(Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap())
+            .amount(amount)
+            .name(name.to_string())
+            .nature(TransactionNature::Expense)
+            .build()
+    }
+
+    #[test]
+    fn classifies_new_matched_and_conflicting_rows() {
+        let existing = [
+            transaction(1, "2024-03-01", "Coffee Co", -450),
+            transaction(2, "2024-03-01", "Bakery", -1200),
+        ];
+        let candidates = [
+            candidate("COFFEE CO.", Decimal::new(450, 2)),
+            candidate("Coffee Co", Decimal::new(450, 2)),
+            candidate("Sandwich Shop", Decimal::new(1200, 2)),
+            candidate("Bookshop", Decimal::new(3000, 2)),
+        ];
+
+        let statuses = classify_candidates(&candidates, &existing);
+
+        assert_eq!(
+            statuses,
+            [
+                ImportMatch::Matched(TransactionId::new(Uuid::from_u128(1))),
+                ImportMatch::New,
+                ImportMatch::Conflict(vec![TransactionId::new(Uuid::from_u128(2))]),
+                ImportMatch::New,
+            ]
+        );
+    }
+
+    #[test]
+    fn fingerprint_ignores_amount_scale() {
+        let a = TransactionFingerprint::of_candidate(&candidate("Coffee", Decimal::new(450, 2)));
+        let b = TransactionFingerprint::of_candidate(&candidate("coffee", Decimal::new(45, 1)));
+        assert_eq!(a, b);
+    }
+}
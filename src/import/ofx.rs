@@ -0,0 +1,496 @@
+//! OFX/QFX statement import
+//!
+//! Reads OFX 1.x (SGML) and 2.x (XML) statements. QFX files are OFX with extra
+//! Quicken-specific elements, which are ignored. Each `STMTTRN` entry becomes
+//! an [`OfxTransaction`], and the statement's `LEDGERBAL` is kept so it can be
+//! recorded as a reconciliation valuation.
+//!
+//! The bank's `FITID` is a stable identifier for each entry. Sure has no field
+//! for external IDs, so imported transactions carry it in their notes as a
+//! `FITID:<id>` line (see [`fit_id_marker`]), which is how
+//! [`SureClient::import_ofx`](crate::SureClient::import_ofx) recognises entries
+//! it has already imported.
+//!
+//! # Example
+//! ```no_run
+//! use sure_client_rs::{SureClient, AccountId};
+//! use sure_client_rs::import::ofx::parse_ofx;
+//! use uuid::Uuid;
+//!
+//! # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let statements = parse_ofx(&std::fs::read_to_string("statement.ofx")?)?;
+//! let account_id = AccountId::new(Uuid::new_v4());
+//!
+//! for statement in &statements {
+//!     let report = client.import_ofx()
+//!         .statement(statement)
+//!         .account_id(account_id)
+//!         .create_valuation(true)
+//!         .call()
+//!         .await?;
+//!     println!("{} new transactions", report.new_rows().len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::error::{ApiError, ImportError};
+use crate::models::batch::BatchOutcome;
+use crate::models::transaction::{CreateTransactionData, Transaction, TransactionNature};
+use crate::models::valuation::Valuation;
+use crate::serde::parse_decimal;
+use crate::types::{AccountId, TransactionId};
+
+/// Prefix of the notes line that records an entry's `FITID`
+const FIT_ID_PREFIX: &str = "FITID:";
+
+/// A single `STMTTRN` entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfxTransaction {
+    /// The bank's stable identifier for the entry (`FITID`)
+    pub fit_id: String,
+    /// Transaction type (`TRNTYPE`), e.g. `DEBIT`, `CREDIT` or `POS`
+    pub transaction_type: Option<String>,
+    /// Date the entry was posted (`DTPOSTED`)
+    pub date_posted: NaiveDate,
+    /// Signed amount (`TRNAMT`): negative for money out, positive for money in
+    pub amount: Decimal,
+    /// Payee (`NAME` or `PAYEE`), falling back to the memo or transaction type
+    pub name: String,
+    /// Memo (`MEMO`)
+    pub memo: Option<String>,
+}
+
+/// A statement balance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfxBalance {
+    /// The balance (`BALAMT`)
+    pub amount: Decimal,
+    /// Date the balance applies to (`DTASOF`)
+    pub as_of: NaiveDate,
+}
+
+/// A bank or credit card statement (`STMTRS` or `CCSTMTRS`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OfxStatement {
+    /// The bank's account number (`ACCTID`)
+    pub account_number: Option<String>,
+    /// Statement currency (`CURDEF`)
+    pub currency: Option<iso_currency::Currency>,
+    /// Entries in the statement, in file order
+    pub transactions: Vec<OfxTransaction>,
+    /// Ledger balance at the end of the statement (`LEDGERBAL`)
+    pub ledger_balance: Option<OfxBalance>,
+}
+
+impl OfxStatement {
+    /// The statement's entries as transaction creation inputs for `account_id`.
+    ///
+    /// The sign of `TRNAMT` decides the [`TransactionNature`], and each
+    /// transaction's notes end with the entry's [`fit_id_marker`].
+    pub fn transactions_for(&self, account_id: AccountId) -> Vec<CreateTransactionData> {
+        self.transactions
+            .iter()
+            .map(|entry| {
+                let marker = fit_id_marker(&entry.fit_id);
+                let notes = match &entry.memo {
+                    Some(memo) if *memo != entry.name => format!("{memo}\n{marker}"),
+                    Some(_) | None => marker,
+                };
+                let nature = if entry.amount.is_sign_negative() {
+                    TransactionNature::Expense
+                } else {
+                    TransactionNature::Income
+                };
+
+                CreateTransactionData {
+                    account_id,
+                    date: entry.date_posted.and_time(chrono::NaiveTime::MIN).and_utc(),
+                    amount: entry.amount.abs(),
+                    name: entry.name.clone(),
+                    notes: Some(notes),
+                    currency: self.currency,
+                    category_id: None,
+                    merchant_id: None,
+                    nature: Some(nature),
+                    tag_ids: None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The notes line recording an entry's `FITID`
+pub fn fit_id_marker(fit_id: &str) -> String {
+    format!("{FIT_ID_PREFIX}{fit_id}")
+}
+
+/// The `FITID` recorded in a transaction's notes, if any
+pub fn fit_id_from_notes(notes: &str) -> Option<&str> {
+    notes
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(FIT_ID_PREFIX))
+}
+
+/// How an OFX entry relates to the transactions already stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OfxMatch {
+    /// No stored transaction carries the entry's `FITID`
+    New,
+    /// A stored transaction carries the entry's `FITID`
+    Imported(TransactionId),
+    /// An earlier entry in the same statement has the same `FITID`
+    Repeated,
+}
+
+/// The result of importing a single OFX entry
+#[derive(Debug)]
+pub struct OfxImportRow {
+    /// The entry's `FITID`
+    pub fit_id: String,
+    /// How the entry relates to the stored transactions
+    pub status: OfxMatch,
+    /// Result of creating the transaction. `None` when nothing was sent, i.e.
+    /// for entries that were already imported and for dry runs.
+    pub outcome: Option<BatchOutcome<Transaction>>,
+}
+
+/// What happened to the statement's ledger balance
+#[derive(Debug)]
+pub enum OfxValuation {
+    /// A valuation was created
+    Created(Valuation),
+    /// The account already has a valuation on the balance date, so none was
+    /// created
+    Existing(Valuation),
+    /// Some entries failed to import, so the balance would not reconcile and
+    /// no valuation was created
+    Skipped,
+    /// Looking up or creating the valuation failed
+    Failed(ApiError),
+}
+
+/// Report for an OFX import
+#[derive(Debug)]
+pub struct OfxImportReport {
+    /// Result of each entry, in statement order
+    pub rows: Vec<OfxImportRow>,
+    /// What happened to the ledger balance; `None` for a dry run, or when no
+    /// valuation was requested or the statement has no balance
+    pub valuation: Option<OfxValuation>,
+}
+
+impl OfxImportReport {
+    /// Indices of entries that were not imported before
+    pub fn new_rows(&self) -> Vec<usize> {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.status == OfxMatch::New)
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+/// Parse every statement in an OFX or QFX file.
+///
+/// # Errors
+/// Returns `ImportError::Ofx` if the file is not well-formed OFX, or an entry
+/// is missing its `FITID`, `DTPOSTED` or `TRNAMT`.
+pub fn parse_ofx(input: &str) -> Result<Vec<OfxStatement>, ImportError> {
+    let mut statements = Vec::new();
+    let mut statement: Option<OfxStatement> = None;
+    // Leaf values of the aggregate being read
+    let mut transaction: Option<HashMap<&str, String>> = None;
+    let mut balance: Option<HashMap<&str, String>> = None;
+
+    let tokens = tokenize(input)?;
+    let mut tokens = tokens.iter().peekable();
+    while let Some(token) = tokens.next() {
+        match *token {
+            Token::Open(tag) => {
+                match tag {
+                    "STMTRS" | "CCSTMTRS" => statement = Some(OfxStatement::default()),
+                    "STMTTRN" => transaction = Some(HashMap::new()),
+                    "LEDGERBAL" => balance = Some(HashMap::new()),
+                    _ => {}
+                }
+
+                // A leaf element: its value is the text that follows the tag.
+                // In SGML the closing tag is optional, so it is not relied on.
+                let Some(Token::Text(text)) = tokens.peek() else {
+                    continue;
+                };
+                let value = decode_entities(text);
+                tokens.next();
+
+                if let Some(fields) = transaction.as_mut() {
+                    // `NAME` may appear inside a `PAYEE` aggregate; either is the payee
+                    fields.entry(tag).or_insert(value);
+                } else if let Some(fields) = balance.as_mut() {
+                    fields.insert(tag, value);
+                } else if let Some(statement) = statement.as_mut() {
+                    match tag {
+                        "ACCTID" => statement.account_number = Some(value),
+                        "CURDEF" => statement.currency = iso_currency::Currency::from_code(&value),
+                        _ => {}
+                    }
+                } else {
+                    // Elements outside a statement, e.g. the sign-on response, are not needed
+                }
+            }
+            Token::Close(tag) => match tag {
+                "STMTTRN" => {
+                    if let (Some(fields), Some(statement)) =
+                        (transaction.take(), statement.as_mut())
+                    {
+                        statement.transactions.push(build_transaction(&fields)?);
+                    }
+                }
+                "LEDGERBAL" => {
+                    if let (Some(fields), Some(statement)) = (balance.take(), statement.as_mut()) {
+                        statement.ledger_balance = Some(OfxBalance {
+                            amount: parse_amount(required(&fields, "BALAMT")?)?,
+                            as_of: parse_date(required(&fields, "DTASOF")?)?,
+                        });
+                    }
+                }
+                "STMTRS" | "CCSTMTRS" => statements.extend(statement.take()),
+                _ => {}
+            },
+            Token::Text(_) => {}
+        }
+    }
+
+    if statements.is_empty() {
+        return Err(ImportError::Ofx("no statements found".to_string()));
+    }
+    Ok(statements)
+}
+
+fn build_transaction(fields: &HashMap<&str, String>) -> Result<OfxTransaction, ImportError> {
+    let fit_id = required(fields, "FITID")?.to_string();
+    let transaction_type = fields.get("TRNTYPE").cloned();
+    let memo = fields.get("MEMO").cloned();
+    let name = fields
+        .get("NAME")
+        .or(memo.as_ref())
+        .or(transaction_type.as_ref())
+        .cloned()
+        .unwrap_or_else(|| fit_id.clone());
+
+    Ok(OfxTransaction {
+        date_posted: parse_date(required(fields, "DTPOSTED")?)?,
+        amount: parse_amount(required(fields, "TRNAMT")?)?,
+        fit_id,
+        transaction_type,
+        name,
+        memo,
+    })
+}
+
+fn required<'a>(fields: &'a HashMap<&str, String>, tag: &str) -> Result<&'a str, ImportError> {
+    fields
+        .get(tag)
+        .map(String::as_str)
+        .ok_or_else(|| ImportError::Ofx(format!("missing <{tag}>")))
+}
+
+/// Parse an OFX date (`YYYYMMDD`, optionally followed by a time and time zone)
+fn parse_date(value: &str) -> Result<NaiveDate, ImportError> {
+    value
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| ImportError::Ofx(format!("invalid date `{value}`")))
+}
+
+/// Parse an OFX amount
+///
+/// OFX amounts have no thousands separators, and the spec lets the decimal
+/// point be a comma, so a lone `,` is read as the decimal point rather than
+/// guessed at.
+fn parse_amount(value: &str) -> Result<Decimal, ImportError> {
+    let separator = match (value.contains('.'), value.contains(',')) {
+        (_, false) => Some('.'),
+        (false, true) => Some(','),
+        (true, true) => None,
+    };
+    separator
+        .and_then(|separator| parse_decimal(value, separator).ok())
+        .ok_or_else(|| ImportError::Ofx(format!("invalid amount `{value}`")))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+/// Split OFX into tags and text, skipping XML declarations, processing
+/// instructions and whitespace between tags. The SGML header becomes a leading
+/// text token, which the parser ignores.
+fn tokenize(input: &str) -> Result<Vec<Token<'_>>, ImportError> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+
+    while let Some((text, after)) = rest.split_once('<') {
+        let text = text.trim();
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+
+        let (tag, after_tag) = after
+            .split_once('>')
+            .ok_or_else(|| ImportError::Ofx("unterminated tag".to_string()))?;
+        let tag = tag.trim();
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim()));
+        } else if tag.starts_with('?') || tag.starts_with('!') || tag.ends_with('/') {
+            // Declarations, processing instructions and empty elements carry no values
+        } else {
+            tokens.push(Token::Open(tag));
+        }
+        rest = after_tag;
+    }
+
+    Ok(tokens)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SGML: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+
+<OFX>
+<BANKMSGSRSV1><STMTTRNRS><STMTRS>
+<CURDEF>NZD
+<BANKACCTFROM><BANKID>12<ACCTID>12-3456-7890123-00<ACCTTYPE>CHECKING</BANKACCTFROM>
+<BANKTRANLIST>
+<DTSTART>20240301<DTEND>20240331
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20240301120000.000[+13:NZDT]
+<TRNAMT>-4.50
+<FITID>2024030101
+<NAME>COFFEE CO
+<MEMO>Card 1234
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20240315
+<TRNAMT>2500.00
+<FITID>2024031501
+<NAME>SALARY &amp; WAGES
+</STMTTRN>
+</BANKTRANLIST>
+<LEDGERBAL><BALAMT>1234.56<DTASOF>20240331</LEDGERBAL>
+<AVAILBAL><BALAMT>1000.00<DTASOF>20240331</AVAILBAL>
+</STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>
+";
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="220"?>
+<OFX>
+  <CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS>
+    <CURDEF>USD</CURDEF>
+    <CCACCTFROM><ACCTID>4111</ACCTID></CCACCTFROM>
+    <BANKTRANLIST>
+      <STMTTRN>
+        <TRNTYPE>DEBIT</TRNTYPE>
+        <DTPOSTED>20240302</DTPOSTED>
+        <TRNAMT>-12.00</TRNAMT>
+        <FITID>abc-1</FITID>
+        <PAYEE><NAME>Bookshop</NAME></PAYEE>
+      </STMTTRN>
+    </BANKTRANLIST>
+  </CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1>
+</OFX>
+"#;
+
+    #[test]
+    fn parses_sgml_statement() {
+        let statements = parse_ofx(SGML).expect("valid OFX");
+        let [statement] = statements.as_slice() else {
+            panic!("expected one statement, got {statements:?}");
+        };
+
+        assert_eq!(statement.currency, Some(iso_currency::Currency::NZD));
+        assert_eq!(
+            statement.account_number.as_deref(),
+            Some("12-3456-7890123-00")
+        );
+        assert_eq!(
+            statement.ledger_balance,
+            Some(OfxBalance {
+                amount: Decimal::new(123_456, 2),
+                as_of: NaiveDate::from_ymd_opt(2024, 3, 31).expect("valid date"),
+            })
+        );
+
+        let summary: Vec<_> = statement
+            .transactions
+            .iter()
+            .map(|t| (t.fit_id.as_str(), t.name.as_str(), t.amount))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("2024030101", "COFFEE CO", Decimal::new(-450, 2)),
+                ("2024031501", "SALARY & WAGES", Decimal::new(250_000, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_xml_statement_and_marks_fit_ids() {
+        let statements = parse_ofx(XML).expect("valid OFX");
+        let statement = statements.first().expect("one statement");
+        let account_id = AccountId::new(uuid::Uuid::nil());
+
+        let candidates = statement.transactions_for(account_id);
+        let candidate = candidates.first().expect("one transaction");
+
+        assert_eq!(candidate.name, "Bookshop");
+        assert_eq!(candidate.signed_amount(), Decimal::new(-1200, 2));
+        assert_eq!(candidate.currency, Some(iso_currency::Currency::USD));
+        assert_eq!(
+            candidate.notes.as_deref().and_then(fit_id_from_notes),
+            Some("abc-1")
+        );
+        assert_eq!(statement.ledger_balance, None);
+    }
+
+    #[test]
+    fn reads_a_dot_or_a_comma_as_the_decimal_point() {
+        assert_eq!(parse_amount("-1.234").ok(), Some(Decimal::new(-1234, 3)));
+        assert_eq!(parse_amount("-1,234").ok(), Some(Decimal::new(-1234, 3)));
+        assert_eq!(parse_amount("2500,5").ok(), Some(Decimal::new(25005, 1)));
+        assert_eq!(parse_amount("+12").ok(), Some(Decimal::from(12)));
+        assert!(matches!(parse_amount("1,234.56"), Err(ImportError::Ofx(_))));
+    }
+
+    #[test]
+    fn rejects_entries_without_fit_id() {
+        let input = "<OFX><STMTRS><STMTTRN><DTPOSTED>20240301<TRNAMT>1.00</STMTTRN></STMTRS></OFX>";
+        assert!(matches!(parse_ofx(input), Err(ImportError::Ofx(_))));
+    }
+}
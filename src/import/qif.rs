@@ -0,0 +1,644 @@
+//! QIF import
+//!
+//! Reads the `!Type:Bank`, `!Type:CCard` and `!Type:Cash` sections of a QIF
+//! file, as exported by legacy desktop finance apps. Other sections, such as
+//! investment accounts and category lists, are skipped.
+//!
+//! QIF categories are names (`L` lines, with `Parent:Child` for
+//! subcategories), which
+//! [`SureClient::import_qif`](crate::SureClient::import_qif) resolves against
+//! the family's categories. A transaction with split lines becomes one
+//! transaction per split, so each part keeps its own category.
+//!
+//! # Example
+//! ```no_run
+//! use sure_client_rs::{SureClient, AccountId};
+//! use sure_client_rs::import::qif::{QifDateOrder, parse_qif};
+//! use uuid::Uuid;
+//!
+//! # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let sections = parse_qif(&std::fs::read_to_string("legacy.qif")?, QifDateOrder::DayFirst)?;
+//!
+//! for section in &sections {
+//!     let report = client.import_qif()
+//!         .section(section)
+//!         .account_id(AccountId::new(Uuid::new_v4()))
+//!         .currency(iso_currency::Currency::NZD)
+//!         .create_missing_categories(true)
+//!         .call()
+//!         .await?;
+//!     println!("{} new transactions", report.import.new_rows().len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::error::ImportError;
+use crate::import::ImportReport;
+use crate::models::category::CategoryDetail;
+use crate::models::transaction::{CreateTransactionData, TransactionNature};
+use crate::serde::parse_decimal;
+use crate::types::AccountId;
+
+/// Order of the day and month in QIF dates such as `03/04/2024`.
+///
+/// The format does not say, so it depends on the locale of the exporting app.
+/// Dates written year first (`2024-04-03`) are read the same either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum QifDateOrder {
+    /// `MM/DD/YYYY`, as written by US versions of Quicken and Money
+    #[default]
+    MonthFirst,
+    /// `DD/MM/YYYY`, as written by most other locales
+    DayFirst,
+}
+
+/// The kind of account a QIF section belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QifAccountType {
+    /// `!Type:Bank`
+    Bank,
+    /// `!Type:CCard`
+    CreditCard,
+    /// `!Type:Cash`
+    Cash,
+}
+
+/// One split line (`S`, `E` and `$`) of a QIF transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QifSplit {
+    /// Category name (`S`), if any
+    pub category: Option<String>,
+    /// Account the split transfers to, from a bracketed category such as `[Savings]`
+    pub transfer_account: Option<String>,
+    /// Memo (`E`)
+    pub memo: Option<String>,
+    /// Signed amount (`$`)
+    pub amount: Decimal,
+}
+
+/// A QIF transaction record, ended by a `^` line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QifTransaction {
+    /// Line number of the record's first line (1-based)
+    pub line: usize,
+    /// Date (`D`)
+    pub date: NaiveDate,
+    /// Signed amount (`T`): negative for money out, positive for money in
+    pub amount: Decimal,
+    /// Payee (`P`)
+    pub payee: Option<String>,
+    /// Memo (`M`)
+    pub memo: Option<String>,
+    /// Category name (`L`), if any
+    pub category: Option<String>,
+    /// Account the transaction transfers to, from a bracketed category such as `[Savings]`
+    pub transfer_account: Option<String>,
+    /// Split lines, in file order
+    pub splits: Vec<QifSplit>,
+}
+
+/// A `!Type:` section of a QIF file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QifSection {
+    /// The kind of account
+    pub account_type: QifAccountType,
+    /// Account name from the preceding `!Account` block, if any
+    pub account_name: Option<String>,
+    /// Transactions in the section, in file order
+    pub transactions: Vec<QifTransaction>,
+}
+
+/// A transaction creation input produced from a QIF record or split
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QifEntry {
+    /// Line number of the record the entry comes from (1-based)
+    pub line: usize,
+    /// The transaction to create. `category_id` is left unset, since QIF only
+    /// names the category.
+    pub transaction: CreateTransactionData,
+    /// Category name to resolve, if any
+    pub category: Option<String>,
+}
+
+impl QifSection {
+    /// The section's transactions as creation inputs for `account_id`.
+    ///
+    /// A transaction with splits produces one entry per split, each with the
+    /// split's amount, category and memo (falling back to the transaction's
+    /// memo). Transfers between QIF accounts are imported as plain
+    /// transactions without a category.
+    pub fn entries_for(
+        &self,
+        account_id: AccountId,
+        currency: Option<iso_currency::Currency>,
+    ) -> Vec<QifEntry> {
+        let entry = |record: &QifTransaction,
+                     amount: Decimal,
+                     memo: Option<&String>,
+                     category: Option<&String>| {
+            let nature = if amount.is_sign_negative() {
+                TransactionNature::Expense
+            } else {
+                TransactionNature::Income
+            };
+            let name = record
+                .payee
+                .as_ref()
+                .or(memo)
+                .or(category)
+                .cloned()
+                .unwrap_or_else(|| "QIF import".to_string());
+
+            QifEntry {
+                line: record.line,
+                transaction: CreateTransactionData {
+                    account_id,
+                    date: record.date.and_time(chrono::NaiveTime::MIN).and_utc(),
+                    amount: amount.abs(),
+                    name,
+                    notes: memo.cloned(),
+                    currency,
+                    category_id: None,
+                    merchant_id: None,
+                    nature: Some(nature),
+                    tag_ids: None,
+                },
+                category: category.cloned(),
+            }
+        };
+
+        self.transactions
+            .iter()
+            .flat_map(|record| {
+                if record.splits.is_empty() {
+                    vec![entry(
+                        record,
+                        record.amount,
+                        record.memo.as_ref(),
+                        record.category.as_ref(),
+                    )]
+                } else {
+                    record
+                        .splits
+                        .iter()
+                        .map(|split| {
+                            entry(
+                                record,
+                                split.amount,
+                                split.memo.as_ref().or(record.memo.as_ref()),
+                                split.category.as_ref(),
+                            )
+                        })
+                        .collect()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Report for a QIF import
+#[derive(Debug)]
+pub struct QifImportReport {
+    /// Line number of the QIF record behind each import row; `lines[i]`
+    /// belongs to `import.rows[i]`
+    pub lines: Vec<usize>,
+    /// Result of importing each entry
+    pub import: ImportReport,
+    /// Categories created because no existing category had the name
+    pub created_categories: Vec<CategoryDetail>,
+    /// Category names that did not resolve; their entries were imported
+    /// without a category. In a dry run, this also lists categories that
+    /// would have been created.
+    pub unresolved_categories: Vec<String>,
+}
+
+/// Parse the bank, credit card and cash sections of a QIF file.
+///
+/// # Errors
+/// Returns `ImportError::Qif` with the offending line if a record has no
+/// date, has an unreadable date or amount, or a split has no amount.
+pub fn parse_qif(input: &str, date_order: QifDateOrder) -> Result<Vec<QifSection>, ImportError> {
+    let mut sections: Vec<QifSection> = Vec::new();
+    let mut mode = Mode::Skip;
+    let mut account_name: Option<String> = None;
+    let mut record = RecordBuilder::default();
+
+    for (index, raw) in input.lines().enumerate() {
+        let line = index.saturating_add(1);
+        let raw = raw.trim_end();
+        if raw.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = raw.strip_prefix('!') {
+            let header = header.trim();
+            if header.eq_ignore_ascii_case("Account") {
+                mode = Mode::Account;
+                continue;
+            }
+            if starts_with_ignore_case(header, "Option:")
+                || starts_with_ignore_case(header, "Clear:")
+            {
+                continue;
+            }
+
+            if let Some(transaction) = record.finish(date_order)? {
+                push_transaction(&mut sections, transaction);
+            }
+            mode = match header.to_ascii_lowercase().as_str() {
+                "type:bank" => Mode::Section(QifAccountType::Bank),
+                "type:ccard" => Mode::Section(QifAccountType::CreditCard),
+                "type:cash" => Mode::Section(QifAccountType::Cash),
+                _ => Mode::Skip,
+            };
+            if let Mode::Section(account_type) = mode {
+                sections.push(QifSection {
+                    account_type,
+                    account_name: account_name.take(),
+                    transactions: Vec::new(),
+                });
+            }
+            continue;
+        }
+
+        let mut chars = raw.chars();
+        let code = chars.next().unwrap_or('^');
+        let value = chars.as_str().trim();
+
+        match mode {
+            Mode::Account => {
+                if code == 'N' {
+                    account_name = Some(value.to_string());
+                }
+            }
+            Mode::Section(_) => {
+                if code == '^' {
+                    if let Some(transaction) = record.finish(date_order)? {
+                        push_transaction(&mut sections, transaction);
+                    }
+                } else {
+                    record.push(line, code, value)?;
+                }
+            }
+            Mode::Skip => {}
+        }
+    }
+
+    if let Some(transaction) = record.finish(date_order)? {
+        push_transaction(&mut sections, transaction);
+    }
+    Ok(sections)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    /// Inside an `!Account` block
+    Account,
+    /// Inside a supported `!Type:` section
+    Section(QifAccountType),
+    /// Inside a section that is not imported
+    Skip,
+}
+
+fn push_transaction(sections: &mut [QifSection], transaction: QifTransaction) {
+    if let Some(section) = sections.last_mut() {
+        section.transactions.push(transaction);
+    }
+}
+
+fn starts_with_ignore_case(value: &str, prefix: &str) -> bool {
+    value
+        .get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// Fields of the record being read
+#[derive(Debug, Default)]
+struct RecordBuilder {
+    line: Option<usize>,
+    date: Option<String>,
+    amount: Option<Decimal>,
+    payee: Option<String>,
+    memo: Option<String>,
+    category: Option<String>,
+    splits: Vec<SplitBuilder>,
+}
+
+#[derive(Debug, Default)]
+struct SplitBuilder {
+    line: usize,
+    category: Option<String>,
+    memo: Option<String>,
+    amount: Option<Decimal>,
+}
+
+impl RecordBuilder {
+    fn push(&mut self, line: usize, code: char, value: &str) -> Result<(), ImportError> {
+        self.line.get_or_insert(line);
+        let text = (!value.is_empty()).then(|| value.to_string());
+
+        match code {
+            'D' => self.date = text,
+            // `U` repeats `T` with more precision in newer exports
+            'T' | 'U' => self.amount = Some(parse_amount(line, value)?),
+            'P' => self.payee = text,
+            'M' => self.memo = text,
+            'L' => self.category = text,
+            'S' => self.splits.push(SplitBuilder {
+                line,
+                category: text,
+                ..SplitBuilder::default()
+            }),
+            'E' => self.current_split(line).memo = text,
+            '$' => self.current_split(line).amount = Some(parse_amount(line, value)?),
+            // Check number, cleared status, address and other fields are not imported
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn current_split(&mut self, line: usize) -> &mut SplitBuilder {
+        if self.splits.is_empty() {
+            self.splits.push(SplitBuilder {
+                line,
+                ..SplitBuilder::default()
+            });
+        }
+        self.splits
+            .last_mut()
+            .expect("a split was pushed if there were none")
+    }
+
+    /// Build the record read so far, if any, and reset for the next one
+    fn finish(&mut self, date_order: QifDateOrder) -> Result<Option<QifTransaction>, ImportError> {
+        let builder = std::mem::take(self);
+        let Some(line) = builder.line else {
+            return Ok(None);
+        };
+
+        let date = builder
+            .date
+            .ok_or_else(|| qif_error(line, "record has no date"))?;
+        let date = parse_date(&date, date_order)
+            .ok_or_else(|| qif_error(line, &format!("invalid date `{date}`")))?;
+
+        let splits = builder
+            .splits
+            .into_iter()
+            .map(|split| {
+                let (category, transfer_account) = split_category(split.category);
+                Ok(QifSplit {
+                    amount: split
+                        .amount
+                        .ok_or_else(|| qif_error(split.line, "split has no amount"))?,
+                    category,
+                    transfer_account,
+                    memo: split.memo,
+                })
+            })
+            .collect::<Result<Vec<_>, ImportError>>()?;
+
+        let amount = match builder.amount {
+            Some(amount) => amount,
+            None => splits
+                .iter()
+                .try_fold(Decimal::ZERO, |total, split| {
+                    total.checked_add(split.amount)
+                })
+                .ok_or_else(|| qif_error(line, "split amounts overflow"))?,
+        };
+        let (category, transfer_account) = split_category(builder.category);
+
+        Ok(Some(QifTransaction {
+            line,
+            date,
+            amount,
+            payee: builder.payee,
+            memo: builder.memo,
+            category,
+            transfer_account,
+            splits,
+        }))
+    }
+}
+
+fn qif_error(line: usize, message: &str) -> ImportError {
+    ImportError::Qif {
+        line,
+        message: message.to_string(),
+    }
+}
+
+/// Separate a bracketed transfer account (`[Savings]`) from a category name.
+///
+/// Returns `(category, transfer_account)`.
+fn split_category(category: Option<String>) -> (Option<String>, Option<String>) {
+    match category {
+        Some(name) => match name
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            Some(account) => (None, Some(account.to_string())),
+            None => (Some(name), None),
+        },
+        None => (None, None),
+    }
+}
+
+/// Parse a QIF amount. Quicken always writes a `.` decimal separator and
+/// `,` thousands grouping, so `1.234` is never read as a thousand.
+fn parse_amount(line: usize, value: &str) -> Result<Decimal, ImportError> {
+    parse_decimal(value, '.').map_err(|_| qif_error(line, &format!("invalid amount `{value}`")))
+}
+
+/// Parse a QIF date such as `3/31/2024`, `03/31'24`, `31.03.2024` or `2024-03-31`.
+///
+/// Quicken writes years from 2000 onwards as `'YY`, sometimes padded with a
+/// space (`' 4`). Other two-digit years follow the usual 1970–2069 window.
+fn parse_date(value: &str, order: QifDateOrder) -> Option<NaiveDate> {
+    let value = value.replace(' ', "0");
+    let apostrophe = value.contains('\'');
+    let parts: Vec<&str> = value
+        .split(['/', '-', '.', '\''])
+        .filter(|part| !part.is_empty())
+        .collect();
+    let [first, second, third] = parts.as_slice() else {
+        return None;
+    };
+
+    let (year, month, day) = if first.len() == 4 {
+        (*first, *second, *third)
+    } else {
+        match order {
+            QifDateOrder::MonthFirst => (*third, *first, *second),
+            QifDateOrder::DayFirst => (*third, *second, *first),
+        }
+    };
+
+    let short_year: i32 = year.parse().ok()?;
+    let year = if year.len() > 2 {
+        short_year
+    } else if apostrophe || short_year < 70 {
+        short_year.checked_add(2000)?
+    } else {
+        short_year.checked_add(1900)?
+    };
+
+    NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QIF: &str = "\
+!Account
+NEveryday
+TBank
+^
+!Type:Bank
+D03/01/2024
+T-45.00
+PSupermarket
+MWeekly shop
+SFood:Groceries
+EGroceries
+$-40.00
+SHousehold
+$-5.00
+^
+D15/03'24
+T2,500.00
+PEmployer
+LSalary
+^
+D20/03/2024
+T-100.00
+PTransfer
+L[Savings]
+^
+!Type:Invst
+D01/04/2024
+NBuy
+^
+!Type:CCard
+D2024-03-05
+T-12.50
+PBookshop
+";
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("valid date")
+    }
+
+    #[test]
+    fn parses_supported_sections_and_splits() {
+        let sections = parse_qif(QIF, QifDateOrder::DayFirst).expect("valid QIF");
+        let [bank, card] = sections.as_slice() else {
+            panic!("expected bank and card sections, got {sections:?}");
+        };
+
+        assert_eq!(bank.account_type, QifAccountType::Bank);
+        assert_eq!(bank.account_name.as_deref(), Some("Everyday"));
+        assert_eq!(bank.transactions.len(), 3);
+
+        let shop = bank.transactions.first().expect("first record");
+        assert_eq!(shop.line, 6);
+        assert_eq!(shop.date, date(2024, 1, 3));
+        assert_eq!(shop.splits.len(), 2);
+
+        let salary = bank.transactions.get(1).expect("second record");
+        assert_eq!(salary.date, date(2024, 3, 15));
+        assert_eq!(salary.amount, Decimal::new(2500, 0));
+
+        let transfer = bank.transactions.get(2).expect("third record");
+        assert_eq!(transfer.category, None);
+        assert_eq!(transfer.transfer_account.as_deref(), Some("Savings"));
+
+        assert_eq!(card.account_type, QifAccountType::CreditCard);
+        let bookshop = card.transactions.first().expect("unterminated record");
+        assert_eq!(bookshop.date, date(2024, 3, 5));
+    }
+
+    #[test]
+    fn splits_become_separate_entries() {
+        let sections = parse_qif(QIF, QifDateOrder::DayFirst).expect("valid QIF");
+        let bank = sections.first().expect("bank section");
+        let entries = bank.entries_for(AccountId::new(uuid::Uuid::nil()), None);
+
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.category.as_deref(),
+                    entry.transaction.notes.as_deref(),
+                    entry.transaction.signed_amount(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    Some("Food:Groceries"),
+                    Some("Groceries"),
+                    Decimal::new(-40, 0)
+                ),
+                (Some("Household"), Some("Weekly shop"), Decimal::new(-5, 0)),
+                (Some("Salary"), None, Decimal::new(2500, 0)),
+                (None, None, Decimal::new(-100, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_date_variants() {
+        let month_first = QifDateOrder::MonthFirst;
+        assert_eq!(
+            parse_date("3/31/2024", month_first),
+            Some(date(2024, 3, 31))
+        );
+        assert_eq!(parse_date("3/31' 4", month_first), Some(date(2004, 3, 31)));
+        assert_eq!(
+            parse_date("12/25/99", month_first),
+            Some(date(1999, 12, 25))
+        );
+        assert_eq!(
+            parse_date("31.03.2024", QifDateOrder::DayFirst),
+            Some(date(2024, 3, 31))
+        );
+        assert_eq!(parse_date("31/31/2024", month_first), None);
+    }
+
+    #[test]
+    fn reads_amounts_with_a_dot_decimal_separator() {
+        assert_eq!(parse_amount(1, "-1.234").ok(), Some(Decimal::new(-1234, 3)));
+        assert_eq!(parse_amount(1, "0.125").ok(), Some(Decimal::new(125, 3)));
+        assert_eq!(
+            parse_amount(1, "1,234.5").ok(),
+            Some(Decimal::new(12345, 1))
+        );
+        assert_eq!(parse_amount(1, "1,000").ok(), Some(Decimal::from(1000)));
+
+        let input = "!Type:Bank\nD01/01/2024\nT-1.23\nU-1.234\n^\n";
+        let sections = parse_qif(input, QifDateOrder::MonthFirst).expect("valid QIF");
+        let record = sections
+            .first()
+            .and_then(|section| section.transactions.first())
+            .expect("one record");
+        assert_eq!(record.amount, Decimal::new(-1234, 3));
+    }
+
+    #[test]
+    fn reports_line_of_invalid_record() {
+        let input = "!Type:Bank\nD01/01/2024\nT1.00\n^\nTnot a number\n^\n";
+        assert!(matches!(
+            parse_qif(input, QifDateOrder::MonthFirst),
+            Err(ImportError::Qif { line: 5, .. })
+        ));
+    }
+}
@@ -198,15 +198,27 @@
 //! ```
 
 // Module declarations
+pub mod analysis;
+pub mod backup;
 mod client;
 mod error;
+pub mod export;
+pub mod fx;
+pub mod import;
 pub mod models;
+pub mod normalise;
+pub mod rules;
 pub(crate) mod serde;
+pub mod style;
+#[cfg(test)]
+mod test_fixtures;
 mod types;
 
 // Public re-exports
 pub use client::SureClient;
-pub use error::{ApiError, ApiResult};
+pub use error::{ApiError, ApiResult, BackupError, ExportError, FxError, ImportError, RuleError};
+pub use style::{HexColor, Icon};
 pub use types::{
-    AccountId, ApiKey, Auth, BearerToken, CategoryId, MerchantId, TagId, TransactionId, ValuationId,
+    AccountId, ApiKey, Auth, BearerToken, CategoryId, MerchantId, Patch, TagId, TransactionId,
+    ValuationId,
 };
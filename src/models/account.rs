@@ -1,6 +1,6 @@
 use crate::{
     serde::{deserialize_flexible_decimal, deserialize_flexible_decimal_opt},
-    types::AccountId,
+    types::{AccountId, Patch},
 };
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
@@ -9,7 +9,7 @@ use serde_json::Value as JsonValue;
 use url::Url;
 
 /// The kind of an account.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub enum AccountKind {
     /// A depository account, such as a checking or savings account.
@@ -87,6 +87,168 @@ impl TryFrom<&str> for AccountKind {
     }
 }
 
+/// Whether an account holds assets or liabilities.
+///
+/// Values this client does not recognise deserialise as
+/// [`AccountClassification::Other`] rather than failing the response, and
+/// serialise back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AccountClassification {
+    /// An asset, such as a bank account or property
+    Asset,
+    /// A liability, such as a credit card or loan
+    Liability,
+    /// A classification this client does not recognise, as the API wrote it
+    Other(String),
+}
+
+impl AccountClassification {
+    /// The classification as the API writes it
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Asset => "asset",
+            Self::Liability => "liability",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl std::fmt::Display for AccountClassification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for AccountClassification {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountClassification {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(value.parse().unwrap_or(Self::Other(value)))
+    }
+}
+
+/// Error returned when parsing an `AccountClassification` from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAccountClassificationError(String);
+
+impl std::fmt::Display for ParseAccountClassificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid account classification: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseAccountClassificationError {}
+
+impl std::str::FromStr for AccountClassification {
+    type Err = ParseAccountClassificationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asset" => Ok(Self::Asset),
+            "liability" => Ok(Self::Liability),
+            _ => Err(ParseAccountClassificationError(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<&str> for AccountClassification {
+    type Error = ParseAccountClassificationError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Lifecycle status of an account.
+///
+/// Values this client does not recognise deserialise as
+/// [`AccountStatus::Other`] rather than failing the response, and serialise
+/// back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AccountStatus {
+    /// The account is in use
+    Active,
+    /// The account is being set up and is not yet shown
+    Draft,
+    /// The account is hidden and excluded from totals
+    Disabled,
+    /// The account is scheduled for deletion
+    PendingDeletion,
+    /// A status this client does not recognise, as the API wrote it
+    Other(String),
+}
+
+impl AccountStatus {
+    /// The status as the API writes it
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Active => "active",
+            Self::Draft => "draft",
+            Self::Disabled => "disabled",
+            Self::PendingDeletion => "pending_deletion",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl std::fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for AccountStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(value.parse().unwrap_or(Self::Other(value)))
+    }
+}
+
+/// Error returned when parsing an `AccountStatus` from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAccountStatusError(String);
+
+impl std::fmt::Display for ParseAccountStatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid account status: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseAccountStatusError {}
+
+impl std::str::FromStr for AccountStatus {
+    type Err = ParseAccountStatusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" => Ok(Self::Active),
+            "draft" => Ok(Self::Draft),
+            "disabled" => Ok(Self::Disabled),
+            "pending_deletion" => Ok(Self::PendingDeletion),
+            _ => Err(ParseAccountStatusError(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<&str> for AccountStatus {
+    type Error = ParseAccountStatusError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
 /// Account information.
 ///
 /// Sure renders both list and detail responses through the same `_account`
@@ -127,19 +289,19 @@ pub struct Account {
     pub cash_balance_cents: Option<i64>,
     /// Currency code (e.g. "USD")
     pub currency: iso_currency::Currency,
-    /// Account classification (e.g. "asset", "liability")
-    pub classification: String,
+    /// Whether the account is an asset or a liability
+    pub classification: AccountClassification,
     /// Account kind
     #[serde(rename = "account_type")]
     pub kind: AccountKind,
     /// Account subtype (e.g. "checking", "savings")
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub subtype: Option<String>,
-    /// Account status (e.g. "active", "disabled", "draft"). Only emitted by
-    /// post-rework Sure deployments; pre-rework instances expose the legacy
-    /// [`Account::is_active`] boolean instead.
+    /// Account status. Only emitted by post-rework Sure deployments;
+    /// pre-rework instances expose the legacy [`Account::is_active`] boolean
+    /// instead.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub status: Option<String>,
+    pub status: Option<AccountStatus>,
     /// Name of the financial institution
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub institution_name: Option<String>,
@@ -230,14 +392,14 @@ pub(crate) struct UpdateAccountData {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub balance: Option<Decimal>,
     /// Name of the financial institution
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub institution_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    pub institution_name: Patch<String>,
     /// Domain of the financial institution
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub institution_domain: Option<Url>,
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    pub institution_domain: Patch<Url>,
     /// Additional notes
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    pub notes: Patch<String>,
     /// Type-specific attributes (optional, must match the account kind if provided)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub accountable_attributes: Option<AccountableAttributes>,
@@ -602,7 +764,8 @@ mod tests {
             Decimal::from_str("770000").expect("valid decimal literal")
         );
         assert_eq!(acct.balance_cents, Some(77_000_000));
-        assert_eq!(acct.status.as_deref(), Some("active"));
+        assert_eq!(acct.classification, AccountClassification::Asset);
+        assert_eq!(acct.status, Some(AccountStatus::Active));
         assert_eq!(acct.notes, None);
         assert_eq!(acct.is_active, None);
     }
@@ -634,8 +797,38 @@ mod tests {
         );
         assert_eq!(acct.balance_cents, None);
         assert_eq!(acct.cash_balance, None);
+        assert_eq!(acct.classification, AccountClassification::Liability);
         assert_eq!(acct.status, None);
         assert_eq!(acct.is_active, Some(true));
         assert_eq!(acct.notes, None);
     }
+
+    #[test]
+    fn unrecognised_classification_and_status_keep_their_value() {
+        let status: AccountStatus = serde_json::from_str(r#""frozen""#).expect("lenient status");
+        let classification: AccountClassification =
+            serde_json::from_str(r#""equity""#).expect("lenient classification");
+
+        assert_eq!(status, AccountStatus::Other("frozen".to_string()));
+        assert_eq!(
+            classification,
+            AccountClassification::Other("equity".to_string())
+        );
+        assert_eq!(
+            serde_json::to_string(&status).ok().as_deref(),
+            Some(r#""frozen""#)
+        );
+        assert_eq!(
+            serde_json::to_string(&classification).ok().as_deref(),
+            Some(r#""equity""#)
+        );
+        assert_eq!(
+            "frozen".parse::<AccountStatus>(),
+            Err(ParseAccountStatusError("frozen".to_string()))
+        );
+        assert_eq!(
+            serde_json::from_str::<AccountStatus>(r#""pending_deletion""#).ok(),
+            Some(AccountStatus::PendingDeletion)
+        );
+    }
 }
@@ -0,0 +1,183 @@
+use std::fmt;
+
+use crate::error::ApiError;
+use crate::models::transaction::UpdateTransactionData;
+use crate::types::TransactionId;
+
+/// Outcome of a single item in a batch operation
+#[derive(Debug)]
+pub enum BatchOutcome<T> {
+    /// The request for this item succeeded
+    Succeeded(T),
+    /// The request for this item failed
+    Failed(ApiError),
+    /// The item was never sent because the batch stopped on an earlier failure
+    Skipped,
+}
+
+impl<T> BatchOutcome<T> {
+    /// Returns `true` if the item succeeded
+    pub const fn is_succeeded(&self) -> bool {
+        matches!(self, Self::Succeeded(_))
+    }
+
+    /// Returns `true` if the item failed
+    pub const fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+
+    /// Returns `true` if the item was skipped
+    pub const fn is_skipped(&self) -> bool {
+        matches!(self, Self::Skipped)
+    }
+}
+
+/// Per-item report for a batch operation.
+///
+/// `results` is in the same order as the batch input, so `results[i]` is the
+/// outcome of the `i`-th input item.
+#[derive(Debug)]
+pub struct BatchReport<T> {
+    /// Outcome of each item, in input order
+    pub results: Vec<BatchOutcome<T>>,
+}
+
+impl<T> BatchReport<T> {
+    /// Returns `true` if every item succeeded
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(BatchOutcome::is_succeeded)
+    }
+
+    /// Iterates over the successful items along with their input index
+    pub fn succeeded(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, outcome)| match outcome {
+                BatchOutcome::Succeeded(value) => Some((index, value)),
+                BatchOutcome::Failed(_) | BatchOutcome::Skipped => None,
+            })
+    }
+
+    /// Iterates over the failed items along with their input index
+    pub fn failed(&self) -> impl Iterator<Item = (usize, &ApiError)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, outcome)| match outcome {
+                BatchOutcome::Failed(error) => Some((index, error)),
+                BatchOutcome::Succeeded(_) | BatchOutcome::Skipped => None,
+            })
+    }
+
+    /// Number of items that were skipped
+    pub fn skipped_count(&self) -> usize {
+        self.results.iter().filter(|o| o.is_skipped()).count()
+    }
+}
+
+/// The action a bulk operation applies to every matching transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkAction {
+    /// Apply the same update to every matching transaction
+    Update(UpdateTransactionData),
+    /// Delete every matching transaction
+    Delete,
+}
+
+/// The set of transactions a bulk operation affects, and what it does to them.
+///
+/// A dry run returns the plan without sending any write requests. Its
+/// [`Display`](fmt::Display) implementation gives a one-line summary of the
+/// intended changes, e.g. `update 12 transactions: set category_id to "…"; clear notes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkPlan {
+    /// IDs of the transactions matched by the filter
+    pub transaction_ids: Vec<TransactionId>,
+    /// The action applied to each matched transaction
+    pub action: BulkAction,
+}
+
+impl fmt::Display for BulkPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.transaction_ids.len();
+        let noun = if count == 1 {
+            "transaction"
+        } else {
+            "transactions"
+        };
+
+        match &self.action {
+            BulkAction::Delete => write!(f, "delete {count} {noun}"),
+            BulkAction::Update(changes) => {
+                write!(f, "update {count} {noun}: ")?;
+
+                // Reuse the request serialisation so every field is described
+                // exactly as it will be sent: `null` clears, a value sets.
+                let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(changes) else {
+                    return f.write_str("(changes could not be described)");
+                };
+                let descriptions: Vec<String> = fields
+                    .iter()
+                    .map(|(field, value)| {
+                        if value.is_null() {
+                            format!("clear {field}")
+                        } else {
+                            format!("set {field} to {value}")
+                        }
+                    })
+                    .collect();
+                f.write_str(&descriptions.join("; "))
+            }
+        }
+    }
+}
+
+/// Report for a bulk operation over every transaction matching a filter
+#[derive(Debug)]
+pub struct BulkReport<T> {
+    /// The matched transactions and the action applied to them
+    pub plan: BulkPlan,
+    /// Per-transaction results, in the same order as `plan.transaction_ids`.
+    /// `None` for a dry run.
+    pub results: Option<BatchReport<T>>,
+}
+
+impl<T> BulkReport<T> {
+    /// Returns `true` if this was a dry run and nothing was sent
+    pub const fn is_dry_run(&self) -> bool {
+        self.results.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CategoryId, Patch};
+    use uuid::Uuid;
+
+    #[test]
+    fn bulk_plan_summarises_changes() {
+        let category_id = CategoryId::new(Uuid::nil());
+        let plan = BulkPlan {
+            transaction_ids: vec![TransactionId::new(Uuid::nil()); 3],
+            action: BulkAction::Update(
+                UpdateTransactionData::builder()
+                    .category_id(category_id)
+                    .notes(Patch::Clear)
+                    .build(),
+            ),
+        };
+
+        assert_eq!(
+            plan.to_string(),
+            format!("update 3 transactions: set category_id to \"{category_id}\"; clear notes")
+        );
+
+        let delete = BulkPlan {
+            transaction_ids: vec![TransactionId::new(Uuid::nil())],
+            action: BulkAction::Delete,
+        };
+        assert_eq!(delete.to_string(), "delete 1 transaction");
+    }
+}
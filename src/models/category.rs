@@ -1,27 +1,66 @@
-use crate::types::CategoryId;
+use crate::models::batch::BatchReport;
+use crate::models::transaction::{Transaction, TransactionNature};
+use crate::models::{DeleteResponse, plural};
+use crate::style::{HexColor, Icon};
+use crate::types::{CategoryId, Patch, TransactionId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-/// Classification of a category
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+/// Classification of a category or transaction
+///
+/// Values this client does not recognise deserialise as
+/// [`Classification::Other`] rather than failing the response, and serialise
+/// back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Classification {
     /// Income category
     Income,
     /// Expense category
     Expense,
+    /// A classification this client does not recognise, as the API wrote it
+    Other(String),
 }
 
-impl fmt::Display for Classification {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Classification {
+    /// The transaction nature with the same direction, if known
+    pub const fn nature(&self) -> Option<TransactionNature> {
+        match self {
+            Self::Income => Some(TransactionNature::Income),
+            Self::Expense => Some(TransactionNature::Expense),
+            Self::Other(_) => None,
+        }
+    }
+
+    /// The classification as the API writes it
+    pub fn as_str(&self) -> &str {
         match self {
-            Self::Income => write!(f, "income"),
-            Self::Expense => write!(f, "expense"),
+            Self::Income => "income",
+            Self::Expense => "expense",
+            Self::Other(value) => value,
         }
     }
 }
 
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Classification {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Classification {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(value.parse().unwrap_or(Self::Other(value)))
+    }
+}
+
 /// Error returned when parsing a `Classification` from a string fails.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParseClassificationError(String);
@@ -115,7 +154,7 @@ pub struct CategoryDetail {
     /// partial, so it is accepted here as an optional field for compatibility
     /// and is `None` when the server does not provide it.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub classification: Option<String>,
+    pub classification: Option<Classification>,
 }
 
 /// Collection of categories with pagination
@@ -140,11 +179,11 @@ pub(crate) struct CreateCategoryRequest {
 pub(crate) struct CreateCategoryData {
     /// Category name
     pub name: String,
-    /// Color for UI display (hex code)
-    pub color: String,
+    /// Color for UI display
+    pub color: HexColor,
     /// Lucide icon name
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub lucide_icon: Option<String>,
+    pub lucide_icon: Option<Icon>,
     /// Parent category ID for subcategories
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub parent_id: Option<CategoryId>,
@@ -165,15 +204,81 @@ pub(crate) struct UpdateCategoryData {
     /// Category name
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
-    /// Color for UI display (hex code)
+    /// Color for UI display
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub color: Option<String>,
+    pub color: Option<HexColor>,
     /// Lucide icon name
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub lucide_icon: Option<String>,
+    pub lucide_icon: Option<Icon>,
     /// Parent category ID for subcategories
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub parent_id: Option<CategoryId>,
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    pub parent_id: Patch<CategoryId>,
+}
+
+/// What [`SureClient::merge_categories`](crate::SureClient::merge_categories)
+/// does to the category being merged away
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryMergePlan {
+    /// The category being merged away and deleted
+    pub from: CategoryId,
+    /// The category that receives its transactions
+    pub into: CategoryId,
+    /// Transactions still in `from`, which are moved into `into`
+    pub transaction_ids: Vec<TransactionId>,
+    /// Subcategories of `from`
+    pub subcategory_ids: Vec<CategoryId>,
+    /// Whether subcategories move under `into`; otherwise they become root
+    /// categories
+    pub move_subcategories: bool,
+}
+
+impl fmt::Display for CategoryMergePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} into {}",
+            plural(self.transaction_ids.len(), "transaction", "transactions"),
+            self.from,
+            self.into
+        )?;
+        if !self.subcategory_ids.is_empty() {
+            let subcategories = plural(self.subcategory_ids.len(), "subcategory", "subcategories");
+            if self.move_subcategories {
+                write!(f, ", move {subcategories} under {}", self.into)?;
+            } else {
+                write!(f, ", make {subcategories} root categories")?;
+            }
+        }
+        write!(f, ", then delete {}", self.from)
+    }
+}
+
+/// Report returned by [`SureClient::merge_categories`](crate::SureClient::merge_categories)
+#[derive(Debug)]
+pub struct CategoryMergeReport {
+    /// What the merge does
+    pub plan: CategoryMergePlan,
+    /// Per-transaction results, in the same order as `plan.transaction_ids`;
+    /// `None` for a dry run
+    pub transactions: Option<BatchReport<Transaction>>,
+    /// Per-subcategory results, in the same order as `plan.subcategory_ids`;
+    /// `None` for a dry run
+    pub subcategories: Option<BatchReport<CategoryDetail>>,
+    /// Response to deleting `from`; `None` for a dry run, or when anything
+    /// failed and `from` was kept so the merge can be run again
+    pub deleted: Option<DeleteResponse>,
+}
+
+impl CategoryMergeReport {
+    /// Returns `true` if this report is from a dry run
+    pub const fn is_dry_run(&self) -> bool {
+        self.transactions.is_none()
+    }
+
+    /// Returns `true` if everything moved and `from` was deleted
+    pub const fn is_complete(&self) -> bool {
+        self.deleted.is_some()
+    }
 }
 
 #[cfg(test)]
@@ -216,6 +321,43 @@ mod tests {
             "updated_at": "2026-05-04T10:10:12Z"
         }"##;
         let detail: CategoryDetail = serde_json::from_str(json).expect("deserialise legacy shape");
-        assert_eq!(detail.classification.as_deref(), Some("expense"));
+        assert_eq!(detail.classification, Some(Classification::Expense));
+    }
+
+    #[test]
+    fn merge_plan_summarises_changes() {
+        let from = CategoryId::new(uuid::Uuid::from_u128(1));
+        let into = CategoryId::new(uuid::Uuid::from_u128(2));
+        let mut plan = CategoryMergePlan {
+            from,
+            into,
+            transaction_ids: vec![TransactionId::new(uuid::Uuid::nil()); 3],
+            subcategory_ids: vec![CategoryId::new(uuid::Uuid::from_u128(3))],
+            move_subcategories: true,
+        };
+
+        assert_eq!(
+            plan.to_string(),
+            format!(
+                "move 3 transactions from {from} into {into}, move 1 subcategory under {into}, then delete {from}"
+            )
+        );
+
+        plan.subcategory_ids
+            .push(CategoryId::new(uuid::Uuid::from_u128(4)));
+        plan.move_subcategories = false;
+        assert_eq!(
+            plan.to_string(),
+            format!(
+                "move 3 transactions from {from} into {into}, make 2 subcategories root categories, then delete {from}"
+            )
+        );
+
+        plan.subcategory_ids.clear();
+        plan.transaction_ids.truncate(1);
+        assert_eq!(
+            plan.to_string(),
+            format!("move 1 transaction from {from} into {into}, then delete {from}")
+        );
     }
 }
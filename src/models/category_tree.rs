@@ -0,0 +1,315 @@
+//! Category hierarchy assembled from the flat category list
+//!
+//! [`SureClient::get_categories`](crate::SureClient::get_categories) returns
+//! categories one page at a time, each pointing at its parent. A
+//! [`CategoryTree`] links them up so the hierarchy can be walked and
+//! categories can be found by path, such as `"Food > Groceries"`.
+
+use std::collections::HashMap;
+
+use crate::models::category::CategoryDetail;
+use crate::normalise::normalise_name;
+use crate::types::CategoryId;
+
+/// Separator between levels of a category path
+pub const PATH_SEPARATOR: char = '>';
+
+/// Split a category path such as `"Food > Groceries"` into its names
+///
+/// Names are trimmed and empty levels are dropped.
+pub fn parse_category_path(path: &str) -> Vec<&str> {
+    path.split(PATH_SEPARATOR)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// A category reached by [`CategoryTree::walk`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryNode<'a> {
+    /// The category
+    pub category: &'a CategoryDetail,
+    /// Number of ancestors; root categories are at depth 0
+    pub depth: usize,
+}
+
+/// The family's categories, linked into a hierarchy
+///
+/// Siblings are kept in name order. A category whose parent is not in the
+/// tree is treated as a root.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryTree {
+    categories: HashMap<CategoryId, CategoryDetail>,
+    children: HashMap<CategoryId, Vec<CategoryId>>,
+}
+
+impl CategoryTree {
+    /// Build a tree from a flat list of categories
+    pub fn new<I>(categories: I) -> Self
+    where
+        I: IntoIterator<Item = CategoryDetail>,
+    {
+        let mut tree = Self::default();
+        for category in categories {
+            tree.insert(category);
+        }
+        tree
+    }
+
+    /// Add a category, or replace the one with the same ID
+    pub fn insert(&mut self, category: CategoryDetail) {
+        let id = category.id;
+        if let Some(previous) = self.categories.remove(&id) {
+            self.unlink(&previous);
+        }
+        if let Some(parent) = &category.parent {
+            self.children.entry(parent.id).or_default().push(id);
+        }
+        self.categories.insert(id, category);
+        if let Some(parent) = self.get(&id).and_then(|c| c.parent.as_ref()).map(|p| p.id) {
+            self.sort_children(parent);
+        }
+    }
+
+    /// Remove a category, returning it
+    ///
+    /// Its subcategories stay in the tree and become roots.
+    pub fn remove(&mut self, id: &CategoryId) -> Option<CategoryDetail> {
+        let category = self.categories.remove(id)?;
+        self.unlink(&category);
+        Some(category)
+    }
+
+    /// The category with the given ID
+    pub fn get(&self, id: &CategoryId) -> Option<&CategoryDetail> {
+        self.categories.get(id)
+    }
+
+    /// Root categories, in name order
+    pub fn roots(&self) -> Vec<&CategoryDetail> {
+        let mut roots: Vec<_> = self
+            .categories
+            .values()
+            .filter(|category| self.parent_of(category).is_none())
+            .collect();
+        roots.sort_by(|a, b| a.name.cmp(&b.name));
+        roots
+    }
+
+    /// Direct subcategories of a category, in name order
+    pub fn children(&self, id: &CategoryId) -> Vec<&CategoryDetail> {
+        self.children
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|child| self.categories.get(child))
+            .collect()
+    }
+
+    /// The parent of a category, if it has one in the tree
+    pub fn parent(&self, id: &CategoryId) -> Option<&CategoryDetail> {
+        self.parent_of(self.get(id)?)
+    }
+
+    /// A category and its ancestors, root first
+    pub fn ancestry(&self, id: &CategoryId) -> Vec<&CategoryDetail> {
+        let mut ancestry = Vec::new();
+        let mut current = self.get(id);
+        while let Some(category) = current {
+            // Guard against a malformed parent cycle
+            if ancestry.len() > self.categories.len() {
+                break;
+            }
+            ancestry.push(category);
+            current = self.parent_of(category);
+        }
+        ancestry.reverse();
+        ancestry
+    }
+
+    /// The path of a category, such as `"Food > Groceries"`
+    pub fn path(&self, id: &CategoryId) -> Option<String> {
+        let ancestry = self.ancestry(id);
+        (!ancestry.is_empty()).then(|| {
+            ancestry
+                .iter()
+                .map(|category| category.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" > ")
+        })
+    }
+
+    /// Every category, depth first: each root followed by its subcategories
+    pub fn walk(&self) -> Vec<CategoryNode<'_>> {
+        let mut nodes = Vec::with_capacity(self.categories.len());
+        let mut stack: Vec<_> = self
+            .roots()
+            .into_iter()
+            .rev()
+            .map(|category| CategoryNode { category, depth: 0 })
+            .collect();
+        while let Some(node) = stack.pop() {
+            if nodes.len() >= self.categories.len() {
+                break;
+            }
+            stack.extend(
+                self.children(&node.category.id)
+                    .into_iter()
+                    .rev()
+                    .map(|category| CategoryNode {
+                        category,
+                        depth: node.depth.saturating_add(1),
+                    }),
+            );
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    /// The child of `parent` (or the root, for `None`) with the given name
+    ///
+    /// Names are compared ignoring case and punctuation.
+    pub fn find_child(&self, parent: Option<&CategoryId>, name: &str) -> Option<&CategoryDetail> {
+        let name = normalise_name(name);
+        let siblings = match parent {
+            Some(parent) => self.children(parent),
+            None => self.roots(),
+        };
+        siblings
+            .into_iter()
+            .find(|category| normalise_name(&category.name) == name)
+    }
+
+    /// The category at a path such as `"Food > Groceries"`
+    ///
+    /// The first name must be a root category and each following name a
+    /// subcategory of the one before.
+    pub fn find_path(&self, path: &str) -> Option<CategoryId> {
+        self.find_names(&parse_category_path(path))
+    }
+
+    /// The category at a path already split into names
+    pub fn find_names(&self, names: &[&str]) -> Option<CategoryId> {
+        let mut current: Option<CategoryId> = None;
+        for name in names {
+            current = Some(self.find_child(current.as_ref(), name)?.id);
+        }
+        current
+    }
+
+    /// Any category with the given name, preferring a root category
+    pub fn find_by_name(&self, name: &str) -> Option<&CategoryDetail> {
+        self.find_child(None, name).or_else(|| {
+            let name = normalise_name(name);
+            self.walk()
+                .into_iter()
+                .map(|node| node.category)
+                .find(|category| normalise_name(&category.name) == name)
+        })
+    }
+
+    /// Number of categories in the tree
+    pub fn len(&self) -> usize {
+        self.categories.len()
+    }
+
+    /// Returns `true` if the tree holds no categories
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty()
+    }
+
+    /// The parent of a category, if it is in the tree
+    fn parent_of(&self, category: &CategoryDetail) -> Option<&CategoryDetail> {
+        self.categories.get(&category.parent.as_ref()?.id)
+    }
+
+    /// Drop a category from its parent's child list
+    fn unlink(&mut self, category: &CategoryDetail) {
+        if let Some(siblings) = category
+            .parent
+            .as_ref()
+            .and_then(|parent| self.children.get_mut(&parent.id))
+        {
+            siblings.retain(|id| *id != category.id);
+        }
+    }
+
+    fn sort_children(&mut self, parent: CategoryId) {
+        let Some(mut children) = self.children.remove(&parent) else {
+            return;
+        };
+        children.sort_by(|a, b| {
+            let name = |id| self.categories.get(id).map(|c| c.name.as_str());
+            name(a).cmp(&name(b))
+        });
+        self.children.insert(parent, children);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn category(id: u128, name: &str, parent: Option<(u128, &str)>) -> CategoryDetail {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::from_u128(id),
+            "name": name,
+            "color": "#6471eb",
+            "icon": "tag",
+            "parent": parent.map(|(id, name)| serde_json::json!({
+                "id": Uuid::from_u128(id),
+                "name": name
+            })),
+            "subcategories_count": 0,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        }))
+        .expect("valid category fixture")
+    }
+
+    fn tree() -> CategoryTree {
+        CategoryTree::new([
+            category(3, "Restaurants", Some((1, "Food"))),
+            category(1, "Food", None),
+            category(2, "Groceries", Some((1, "Food"))),
+            category(4, "Transport", None),
+            category(5, "Groceries", None),
+        ])
+    }
+
+    #[test]
+    fn walks_depth_first_in_name_order() {
+        let tree = tree();
+        let walked: Vec<_> = tree
+            .walk()
+            .into_iter()
+            .map(|node| (node.category.name.as_str(), node.depth))
+            .collect();
+
+        assert_eq!(
+            walked,
+            [
+                ("Food", 0),
+                ("Groceries", 1),
+                ("Restaurants", 1),
+                ("Groceries", 0),
+                ("Transport", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_paths() {
+        let tree = tree();
+        let id = |id| CategoryId::new(Uuid::from_u128(id));
+
+        assert_eq!(tree.find_path("Food > Groceries"), Some(id(2)));
+        assert_eq!(tree.find_path("  food>GROCERIES "), Some(id(2)));
+        assert_eq!(tree.find_path("Groceries"), Some(id(5)));
+        assert_eq!(tree.find_path("Food > Fuel"), None);
+        assert_eq!(tree.find_path(""), None);
+        assert_eq!(tree.path(&id(3)), Some("Food > Restaurants".to_string()));
+        assert_eq!(tree.parent(&id(3)).map(|c| c.id), Some(id(1)));
+    }
+}
@@ -1,6 +1,11 @@
-use crate::types::MerchantId;
+use crate::models::batch::BatchReport;
+use crate::models::transaction::Transaction;
+use crate::models::{DeleteResponse, plural};
+use crate::style::HexColor;
+use crate::types::{MerchantId, Patch, TransactionId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Basic merchant information (used in transactions)
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -51,9 +56,9 @@ pub(crate) struct CreateMerchantRequest {
 pub(crate) struct CreateMerchantData {
     /// Merchant name
     pub name: String,
-    /// Merchant color (hex code)
+    /// Merchant color
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub color: Option<String>,
+    pub color: Option<HexColor>,
 }
 
 /// Request to update an existing merchant
@@ -71,7 +76,161 @@ pub(crate) struct UpdateMerchantData {
     /// Merchant name
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
-    /// Merchant color (hex code)
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub color: Option<String>,
+    /// Merchant color
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    pub color: Patch<HexColor>,
+}
+
+/// A merchant to be attached to a transaction by
+/// [`SureClient::assign_merchants`](crate::SureClient::assign_merchants)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerchantAssignment {
+    /// The transaction to update
+    pub transaction_id: TransactionId,
+    /// The transaction's name, as it came from the bank
+    pub transaction_name: String,
+    /// Canonical name of the merchant
+    pub merchant_name: String,
+    /// The merchant to attach; `None` in a dry run when the merchant does
+    /// not exist yet
+    pub merchant_id: Option<MerchantId>,
+    /// Whether the merchant was (or, in a dry run, would be) created
+    pub created: bool,
+}
+
+/// Report returned by [`SureClient::assign_merchants`](crate::SureClient::assign_merchants)
+#[derive(Debug)]
+pub struct MerchantAssignmentReport {
+    /// The merchant chosen for each transaction that changes
+    pub assignments: Vec<MerchantAssignment>,
+    /// Per-assignment results, in the same order as `assignments`; `None` for
+    /// a dry run
+    pub results: Option<BatchReport<Transaction>>,
+}
+
+impl MerchantAssignmentReport {
+    /// Returns `true` if this report is from a dry run
+    pub const fn is_dry_run(&self) -> bool {
+        self.results.is_none()
+    }
+}
+
+/// Merchants that look like the same business, suggested by
+/// [`MerchantIndex::suggest_merges`](crate::normalise::MerchantIndex::suggest_merges)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerchantMergeSuggestion {
+    /// The oldest merchant of the group, suggested as the one to keep
+    pub into: MerchantDetail,
+    /// The other merchants of the group, oldest first
+    pub from: Vec<MerchantDetail>,
+    /// The lowest name similarity between `into` and any of `from`, from 0 to 1
+    pub similarity: f64,
+}
+
+impl MerchantMergeSuggestion {
+    /// IDs of the merchants to merge away
+    pub fn from_ids(&self) -> Vec<MerchantId> {
+        self.from.iter().map(|merchant| merchant.id).collect()
+    }
+}
+
+/// What [`SureClient::merge_merchants`](crate::SureClient::merge_merchants)
+/// does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerchantMergePlan {
+    /// The merchant that receives the transactions and is kept
+    pub into: MerchantId,
+    /// The duplicate merchants, which are deleted
+    pub from: Vec<MerchantId>,
+    /// Transactions to move, each with the duplicate it currently references
+    pub transactions: Vec<(TransactionId, MerchantId)>,
+}
+
+impl MerchantMergePlan {
+    /// IDs of the transactions to move, in plan order
+    pub fn transaction_ids(&self) -> Vec<TransactionId> {
+        self.transactions.iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Number of transactions to move from `merchant`
+    pub fn count_from(&self, merchant: &MerchantId) -> usize {
+        self.transactions
+            .iter()
+            .filter(|(_, from)| from == merchant)
+            .count()
+    }
+}
+
+impl fmt::Display for MerchantMergePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} into {}, then delete them",
+            plural(self.transactions.len(), "transaction", "transactions"),
+            plural(self.from.len(), "merchant", "merchants"),
+            self.into
+        )
+    }
+}
+
+/// Transactions moved away from one duplicate merchant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MerchantMoveCount {
+    /// The duplicate merchant
+    pub merchant_id: MerchantId,
+    /// Transactions that referenced it when the merge started
+    pub planned: usize,
+    /// Transactions actually moved; 0 for a dry run
+    pub moved: usize,
+}
+
+/// Report returned by [`SureClient::merge_merchants`](crate::SureClient::merge_merchants)
+#[derive(Debug)]
+pub struct MerchantMergeReport {
+    /// What the merge does
+    pub plan: MerchantMergePlan,
+    /// Per-transaction results, in the same order as `plan.transactions`;
+    /// `None` for a dry run
+    pub transactions: Option<BatchReport<Transaction>>,
+    /// Per-merchant delete results, in the same order as `plan.from`; `None`
+    /// for a dry run, or when a move failed and the duplicates were kept so
+    /// the merge can be run again
+    pub deleted: Option<BatchReport<DeleteResponse>>,
+}
+
+impl MerchantMergeReport {
+    /// Returns `true` if this report is from a dry run
+    pub const fn is_dry_run(&self) -> bool {
+        self.transactions.is_none()
+    }
+
+    /// Returns `true` if every transaction moved and every duplicate was deleted
+    pub fn is_complete(&self) -> bool {
+        self.deleted.as_ref().is_some_and(BatchReport::is_success)
+    }
+
+    /// Planned and moved transaction counts for each duplicate, in the same
+    /// order as `plan.from`
+    pub fn moved(&self) -> Vec<MerchantMoveCount> {
+        let mut counts: Vec<_> = self
+            .plan
+            .from
+            .iter()
+            .map(|merchant_id| MerchantMoveCount {
+                merchant_id: *merchant_id,
+                planned: self.plan.count_from(merchant_id),
+                moved: 0,
+            })
+            .collect();
+        let succeeded = self.transactions.iter().flat_map(BatchReport::succeeded);
+        for (index, _) in succeeded {
+            let Some((_, from)) = self.plan.transactions.get(index) else {
+                continue;
+            };
+            if let Some(count) = counts.iter_mut().find(|count| count.merchant_id == *from) {
+                count.moved = count.moved.saturating_add(1);
+            }
+        }
+        counts
+    }
 }
@@ -1,9 +1,12 @@
 pub mod account;
 pub mod auth;
+pub mod batch;
 pub mod category;
+pub mod category_tree;
 pub mod chat;
 pub mod merchant;
 pub mod sync;
+pub mod tag;
 pub mod transaction;
 pub mod usage;
 pub mod valuation;
@@ -56,3 +59,9 @@ pub struct ErrorResponse {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub details: Option<serde_json::Value>,
 }
+
+/// `count` followed by the singular or plural form of a noun, for summaries
+pub(crate) fn plural(count: usize, singular: &str, plural: &str) -> String {
+    let noun = if count == 1 { singular } else { plural };
+    format!("{count} {noun}")
+}
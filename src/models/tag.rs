@@ -0,0 +1,67 @@
+use crate::style::HexColor;
+use crate::types::TagId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Detailed tag information
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct TagDetail {
+    /// Unique identifier
+    pub id: TagId,
+    /// Tag name
+    pub name: String,
+    /// Color for UI display (hex code)
+    pub color: String,
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Collection of tags with pagination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct TagCollection {
+    /// List of tags
+    pub tags: Vec<TagDetail>,
+}
+
+/// Request to create a new tag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub(crate) struct CreateTagRequest {
+    /// Tag data
+    pub tag: CreateTagData,
+}
+
+/// Data for creating a new tag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub(crate) struct CreateTagData {
+    /// Tag name
+    pub name: String,
+    /// Tag color; Sure picks one when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<HexColor>,
+}
+
+/// Request to update an existing tag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub(crate) struct UpdateTagRequest {
+    /// Tag data
+    pub tag: UpdateTagData,
+}
+
+/// Data for updating a tag
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub(crate) struct UpdateTagData {
+    /// Tag name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Tag color
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<HexColor>,
+}
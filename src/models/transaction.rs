@@ -1,4 +1,7 @@
-use crate::types::{AccountId, CategoryId, MerchantId, TagId, TransactionId};
+use crate::models::account::AccountClassification;
+use crate::models::category::Classification;
+use crate::types::{AccountId, CategoryId, MerchantId, Patch, TagId, TransactionId};
+use bon::Builder;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -17,9 +20,9 @@ pub struct Account {
     /// Currency code (e.g. "USD")
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub currency: Option<iso_currency::Currency>,
-    /// Account classification (e.g. "asset", "liability")
+    /// Whether the account is an asset or a liability
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub classification: Option<String>,
+    pub classification: Option<AccountClassification>,
     /// Accountable type (e.g. "depository", "investment", "credit_card")
     pub account_type: String,
 }
@@ -40,7 +43,7 @@ pub struct Category {
     /// deployments still render it on transaction.category; newer ones omit
     /// it. See [`crate::models::category::CategoryDetail::classification`].
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub classification: Option<String>,
+    pub classification: Option<Classification>,
 }
 
 /// Merchant information
@@ -225,8 +228,8 @@ pub struct Transaction {
     /// Additional notes
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
-    /// Classification (income/expense)
-    pub classification: String,
+    /// Whether the transaction is income or an expense
+    pub classification: Classification,
     /// Associated account
     pub account: Account,
     /// Associated category
@@ -246,6 +249,19 @@ pub struct Transaction {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Transaction {
+    /// Signed transaction amount in major units (e.g. dollars). Positive for
+    /// income, negative for expenses.
+    pub fn signed_amount(&self) -> Decimal {
+        minor_units_to_decimal(self.signed_amount_cents, self.currency)
+    }
+}
+
+/// Convert an amount in a currency's minor unit into major units
+pub(crate) fn minor_units_to_decimal(amount: i64, currency: iso_currency::Currency) -> Decimal {
+    Decimal::new(amount, u32::from(currency.exponent().unwrap_or(2)))
+}
+
 /// Collection of transactions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
@@ -262,10 +278,14 @@ pub(crate) struct CreateTransactionRequest {
     pub transaction: CreateTransactionData,
 }
 
-/// Transaction data for creation
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Transaction data for creation.
+///
+/// This is the input accepted by batch operations such as
+/// [`SureClient::create_transactions`](crate::SureClient::create_transactions),
+/// and can be built with [`CreateTransactionData::builder`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Builder)]
 #[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
-pub(crate) struct CreateTransactionData {
+pub struct CreateTransactionData {
     /// Account ID (required)
     pub account_id: AccountId,
     /// Transaction date (required)
@@ -294,6 +314,22 @@ pub(crate) struct CreateTransactionData {
     pub tag_ids: Option<Vec<TagId>>,
 }
 
+impl CreateTransactionData {
+    /// Signed amount in the same convention as [`Transaction::signed_amount`]:
+    /// positive for income, negative for expenses.
+    ///
+    /// When `nature` is set, it decides the sign and `amount` is treated as a
+    /// magnitude. Without a nature, Sure stores `amount` as given, where a
+    /// positive amount is an outflow, so the sign is flipped.
+    pub fn signed_amount(&self) -> Decimal {
+        match self.nature {
+            Some(TransactionNature::Income) => self.amount.abs(),
+            Some(TransactionNature::Expense) => Decimal::ZERO.saturating_sub(self.amount.abs()),
+            None => Decimal::ZERO.saturating_sub(self.amount),
+        }
+    }
+}
+
 /// Request body for updating a transaction
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
@@ -302,10 +338,15 @@ pub(crate) struct UpdateTransactionRequest {
     pub transaction: UpdateTransactionData,
 }
 
-/// Transaction data for updates
-#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// Transaction data for updates.
+///
+/// Every field is a [`Patch`], so a value can be left unchanged, cleared or set.
+/// This is the change set accepted by bulk operations such as
+/// [`SureClient::update_transactions_matching`](crate::SureClient::update_transactions_matching),
+/// and can be built with [`UpdateTransactionData::builder`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, Builder)]
 #[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
-pub(crate) struct UpdateTransactionData {
+pub struct UpdateTransactionData {
     /// Transaction date
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub date: Option<DateTime<Utc>>,
@@ -316,21 +357,127 @@ pub(crate) struct UpdateTransactionData {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     /// Additional notes
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    #[builder(default, into)]
+    pub notes: Patch<String>,
     /// Currency code
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub currency: Option<iso_currency::Currency>,
     /// Category ID
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub category_id: Option<CategoryId>,
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    #[builder(default, into)]
+    pub category_id: Patch<CategoryId>,
     /// Merchant ID
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub merchant_id: Option<MerchantId>,
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    #[builder(default, into)]
+    pub merchant_id: Patch<MerchantId>,
     /// Transaction nature
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nature: Option<TransactionNature>,
     /// Tag IDs
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    #[builder(default, into)]
+    pub tag_ids: Patch<Vec<TagId>>,
+}
+
+impl UpdateTransactionData {
+    /// Returns `true` if no field would be changed
+    pub const fn is_empty(&self) -> bool {
+        self.date.is_none()
+            && self.amount.is_none()
+            && self.name.is_none()
+            && self.notes.is_unchanged()
+            && self.currency.is_none()
+            && self.category_id.is_unchanged()
+            && self.merchant_id.is_unchanged()
+            && self.nature.is_none()
+            && self.tag_ids.is_unchanged()
+    }
+}
+
+/// Filter selecting a set of transactions.
+///
+/// Mirrors the filters accepted by
+/// [`SureClient::get_transactions`](crate::SureClient::get_transactions), but
+/// owns its values so it can be stored and reused by operations that walk every
+/// page of results, such as
+/// [`SureClient::get_all_transactions`](crate::SureClient::get_all_transactions).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Builder)]
+pub struct TransactionFilter {
+    /// Filter by single account ID
+    pub account_id: Option<AccountId>,
+    /// Filter by multiple account IDs
+    pub account_ids: Option<Vec<AccountId>>,
+    /// Filter by single category ID
+    pub category_id: Option<CategoryId>,
+    /// Filter by multiple category IDs
+    pub category_ids: Option<Vec<CategoryId>>,
+    /// Filter by single merchant ID
+    pub merchant_id: Option<MerchantId>,
+    /// Filter by multiple merchant IDs
+    pub merchant_ids: Option<Vec<MerchantId>>,
+    /// Filter by tag IDs
     pub tag_ids: Option<Vec<TagId>>,
+    /// Filter transactions from this date (inclusive)
+    pub start_date: Option<DateTime<Utc>>,
+    /// Filter transactions until this date (inclusive)
+    pub end_date: Option<DateTime<Utc>>,
+    /// Filter by minimum amount
+    pub min_amount: Option<Decimal>,
+    /// Filter by maximum amount
+    pub max_amount: Option<Decimal>,
+    /// Filter by transaction type (income or expense)
+    pub transaction_type: Option<TransactionType>,
+    /// Search by name, notes, or merchant name
+    #[builder(into)]
+    pub search: Option<String>,
+}
+
+impl TransactionFilter {
+    /// Returns `true` if the filter matches every transaction
+    ///
+    /// Empty ID lists and a blank search are not sent to the server, so they
+    /// do not narrow the filter either.
+    pub fn is_empty(&self) -> bool {
+        self.account_id.is_none()
+            && self.account_ids.as_ref().is_none_or(Vec::is_empty)
+            && self.category_id.is_none()
+            && self.category_ids.as_ref().is_none_or(Vec::is_empty)
+            && self.merchant_id.is_none()
+            && self.merchant_ids.as_ref().is_none_or(Vec::is_empty)
+            && self.tag_ids.as_ref().is_none_or(Vec::is_empty)
+            && self.start_date.is_none()
+            && self.end_date.is_none()
+            && self.min_amount.is_none()
+            && self.max_amount.is_none()
+            && self.transaction_type.is_none()
+            && self
+                .search
+                .as_deref()
+                .is_none_or(|search| search.trim().is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn filter_without_criteria_is_empty() {
+        assert!(TransactionFilter::default().is_empty());
+        assert!(
+            TransactionFilter::builder()
+                .account_ids(Vec::new())
+                .search("  ")
+                .build()
+                .is_empty()
+        );
+        assert!(
+            !TransactionFilter::builder()
+                .merchant_ids(vec![MerchantId::new(Uuid::nil())])
+                .build()
+                .is_empty()
+        );
+    }
 }
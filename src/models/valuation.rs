@@ -7,12 +7,12 @@ use serde::{Deserialize, Serialize};
 // reference type from the transaction module rather than redefining it.
 use crate::models::transaction::Account;
 use crate::serde::deserialize_flexible_decimal;
-use crate::types::{AccountId, ValuationId};
+use crate::types::{AccountId, Patch, ValuationId};
 
 /// The kind of valuation entry. Most user-driven valuations are `reconciliation`
 /// entries; the others are anchor entries that mark the opening or current balance
 /// of an account.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ValuationKind {
     /// A user-driven correction or periodic re-valuation of an account's balance.
@@ -23,6 +23,16 @@ pub enum ValuationKind {
     CurrentAnchor,
 }
 
+impl std::fmt::Display for ValuationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Reconciliation => write!(f, "reconciliation"),
+            Self::OpeningAnchor => write!(f, "opening_anchor"),
+            Self::CurrentAnchor => write!(f, "current_anchor"),
+        }
+    }
+}
+
 /// A valuation entry representing a point-in-time balance for an account.
 ///
 /// Valuations are commonly used to record property re-valuations, investment
@@ -52,6 +62,14 @@ pub struct Valuation {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Collection of valuations with pagination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ValuationCollection {
+    /// List of valuations.
+    pub valuations: Vec<Valuation>,
+}
+
 /// Request body for creating a valuation.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
@@ -96,6 +114,25 @@ pub(crate) struct UpdateValuationData {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub date: Option<NaiveDate>,
     /// Updated notes.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    pub notes: Patch<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_displays_as_its_query_value() {
+        for kind in [
+            ValuationKind::Reconciliation,
+            ValuationKind::OpeningAnchor,
+            ValuationKind::CurrentAnchor,
+        ] {
+            assert_eq!(
+                serde_json::to_string(&kind).ok(),
+                Some(format!("\"{kind}\""))
+            );
+        }
+    }
 }
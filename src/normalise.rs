@@ -0,0 +1,474 @@
+//! Text normalisation helpers shared by importers and analyses
+//!
+//! [`normalise_name`] is the comparison key used throughout the crate.
+//! [`MerchantNormaliser`] goes further and reduces a raw bank description to
+//! a canonical merchant name, and [`MerchantIndex`] caches a family's
+//! merchants by that name for
+//! [`SureClient::find_or_create_merchant`](crate::SureClient::find_or_create_merchant)
+//! and suggests duplicates for
+//! [`SureClient::merge_merchants`](crate::SureClient::merge_merchants).
+
+use std::collections::{HashMap, HashSet};
+
+use crate::models::merchant::{MerchantDetail, MerchantMergeSuggestion};
+
+/// Words that banks and payment processors put in front of the merchant name
+const DEFAULT_PREFIXES: &[&str] = &[
+    "pos",
+    "w",
+    "d",
+    "wd",
+    "eftpos",
+    "visa",
+    "mastercard",
+    "debit",
+    "credit",
+    "card",
+    "purchase",
+    "payment",
+    "pymt",
+    "contactless",
+    "pending",
+    "sq",
+    "paypal",
+    "pp",
+    "tst",
+    "sp",
+    "sumup",
+    "zettle",
+];
+
+/// Words that follow the merchant name without identifying it: country codes
+/// and company suffixes
+const DEFAULT_SUFFIXES: &[&str] = &[
+    "nz", "nzl", "au", "aus", "us", "usa", "uk", "gb", "gbr", "ltd", "limited", "inc", "llc",
+    "pty", "plc",
+];
+
+/// Trailing tokens with at least this many digits are treated as card or
+/// store references
+const REFERENCE_MIN_DIGITS: usize = 3;
+
+/// Default [`name_similarity`] at which [`MerchantIndex::suggest_merges`]
+/// groups two merchants
+pub const DEFAULT_MERGE_SIMILARITY: f64 = 0.75;
+
+/// Normalise a transaction or merchant name for comparison.
+///
+/// Lowercases the name, treats every non-alphanumeric character as a word
+/// separator, and collapses runs of whitespace, so `"COFFEE-CO  Ltd."` and
+/// `"coffee co ltd"` compare equal.
+pub fn normalise_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How alike two names are, from 0 (nothing in common) to 1 (the same)
+///
+/// Both names are [normalised](normalise_name) and compared by their letter
+/// pairs, ignoring spaces, so `"Pak n Save"` and `"PAKNSAVE"` score 1 and
+/// `"Countdown"` and `"Countdwn"` score highly.
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (letter_pairs(a), letter_pairs(b));
+    let total = a.values().chain(b.values()).sum::<usize>();
+    if total == 0 {
+        return 0.0;
+    }
+    let shared: usize = a
+        .iter()
+        .map(|(pair, count)| b.get(pair).map_or(0, |other| (*count).min(*other)))
+        .sum();
+    shared.saturating_mul(2) as f64 / total as f64
+}
+
+/// Reduces raw transaction descriptions to canonical merchant names.
+///
+/// [`canonical_name`](Self::canonical_name) drops, in order:
+/// - trailing card, terminal and store references: words with three or more
+///   digits, such as `METL-1234` or `#00412`, mixed in any order with
+///   trailing locations and suffixes: country codes, company suffixes such as
+///   `Ltd`, and any locations added with [`with_location`](Self::with_location);
+/// - leading payment processor and card scheme words, such as `POS`, `W/D`,
+///   `EFTPOS` or `SQ *`, unless they are all that is left, so merchants such
+///   as `PayPal` or `Visa` keep their name.
+///
+/// Shouted words are title-cased, so `"POS W/D COUNTDOWN METL-1234"` becomes
+/// `"Countdown"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerchantNormaliser {
+    prefixes: HashSet<String>,
+    suffixes: HashSet<String>,
+}
+
+impl Default for MerchantNormaliser {
+    fn default() -> Self {
+        Self {
+            prefixes: DEFAULT_PREFIXES
+                .iter()
+                .map(|word| (*word).to_string())
+                .collect(),
+            suffixes: DEFAULT_SUFFIXES
+                .iter()
+                .map(|word| (*word).to_string())
+                .collect(),
+        }
+    }
+}
+
+impl MerchantNormaliser {
+    /// A normaliser with the default prefixes and suffixes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also strip `prefix` from the start of names, e.g. a bank-specific
+    /// `"DIRECT DEBIT"`
+    #[must_use]
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefixes.extend(words(prefix));
+        self
+    }
+
+    /// Also strip `location` from the end of names, e.g. `"Ponsonby"`
+    #[must_use]
+    pub fn with_location(mut self, location: &str) -> Self {
+        self.suffixes.extend(words(location));
+        self
+    }
+
+    /// The canonical merchant name for a transaction description
+    ///
+    /// A description made only of references and suffixes is kept whole.
+    /// Returns `None` only if the description has no letters or digits.
+    pub fn canonical_name(&self, description: &str) -> Option<String> {
+        let tokens: Vec<&str> = description
+            .split(|c: char| c.is_whitespace() || c == '*')
+            .map(|token| token.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|token| !token.is_empty())
+            .collect();
+
+        let is_all = |token: &str, set: &HashSet<String>| {
+            let words = words(token);
+            !words.is_empty() && words.iter().all(|word| set.contains(word))
+        };
+        let is_reference = |token: &str| {
+            token.chars().filter(char::is_ascii_digit).count() >= REFERENCE_MIN_DIGITS
+        };
+        let end = tokens
+            .iter()
+            .rposition(|token| !is_reference(token) && !is_all(token, &self.suffixes))
+            .unwrap_or_else(|| tokens.len().saturating_sub(1));
+        let tokens = tokens.get(..=end)?;
+        let start = tokens
+            .iter()
+            .position(|token| !is_all(token, &self.prefixes))
+            .unwrap_or(0);
+
+        let name = tokens
+            .get(start..)?
+            .iter()
+            .map(|token| title_case_if_shouted(token))
+            .collect::<Vec<_>>()
+            .join(" ");
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// The comparison key for a transaction description or merchant name
+    ///
+    /// Two names with the same key are the same merchant. Returns `None` when
+    /// [`canonical_name`](Self::canonical_name) does.
+    pub fn key(&self, name: &str) -> Option<String> {
+        self.canonical_name(name)
+            .map(|canonical| normalise_name(&canonical))
+    }
+}
+
+/// A family's merchants indexed by their [`MerchantNormaliser::key`]
+///
+/// Load one with
+/// [`SureClient::get_merchant_index`](crate::SureClient::get_merchant_index)
+/// and keep it for the length of an import or clean-up, so repeated lookups
+/// do not go back to the API.
+#[derive(Debug, Clone, Default)]
+pub struct MerchantIndex {
+    normaliser: MerchantNormaliser,
+    by_key: HashMap<String, Vec<MerchantDetail>>,
+}
+
+impl MerchantIndex {
+    /// Index `merchants` with `normaliser`
+    pub fn new<I>(normaliser: MerchantNormaliser, merchants: I) -> Self
+    where
+        I: IntoIterator<Item = MerchantDetail>,
+    {
+        let mut index = Self {
+            normaliser,
+            by_key: HashMap::new(),
+        };
+        for merchant in merchants {
+            index.insert(merchant);
+        }
+        index
+    }
+
+    /// The normaliser used for keys
+    pub const fn normaliser(&self) -> &MerchantNormaliser {
+        &self.normaliser
+    }
+
+    /// The merchant a name or transaction description belongs to
+    ///
+    /// When several merchants share the key, the oldest is returned.
+    pub fn get(&self, name: &str) -> Option<&MerchantDetail> {
+        self.by_key.get(&self.normaliser.key(name)?)?.first()
+    }
+
+    /// Add a merchant to the index
+    ///
+    /// Merchants whose names normalise to nothing are ignored.
+    pub fn insert(&mut self, merchant: MerchantDetail) {
+        let Some(key) = self.normaliser.key(&merchant.name) else {
+            return;
+        };
+        let merchants = self.by_key.entry(key).or_default();
+        merchants.push(merchant);
+        merchants.sort_by_key(|m| m.created_at);
+    }
+
+    /// Groups of merchants that normalise to the same name, oldest first
+    ///
+    /// These are near-duplicates such as `"COUNTDOWN"` and `"Countdown Ltd"`.
+    pub fn near_duplicates(&self) -> Vec<&[MerchantDetail]> {
+        let mut groups: Vec<_> = self
+            .by_key
+            .values()
+            .filter(|merchants| merchants.len() > 1)
+            .map(Vec::as_slice)
+            .collect();
+        groups.sort_by(|a, b| {
+            let name = |group: &[MerchantDetail]| group.first().map(|m| m.name.clone());
+            name(a).cmp(&name(b))
+        });
+        groups
+    }
+
+    /// Groups of merchants whose names look like the same business
+    ///
+    /// Merchants are grouped when their keys have a [`name_similarity`] of at
+    /// least `min_similarity` (see [`DEFAULT_MERGE_SIMILARITY`]), which also
+    /// catches misspellings and spacing differences that
+    /// [`near_duplicates`](Self::near_duplicates) misses. Each group suggests
+    /// keeping its oldest merchant.
+    pub fn suggest_merges(&self, min_similarity: f64) -> Vec<MerchantMergeSuggestion> {
+        let mut keys: Vec<&String> = self.by_key.keys().collect();
+        keys.sort();
+        // Group label of each key; labels are merged as similar pairs are found
+        let mut labels: Vec<usize> = (0..keys.len()).collect();
+        for (i, a) in keys.iter().enumerate() {
+            for (j, b) in keys.iter().enumerate().skip(i.saturating_add(1)) {
+                if name_similarity(a, b) < min_similarity {
+                    continue;
+                }
+                let (Some(&keep), Some(&merge)) = (labels.get(i), labels.get(j)) else {
+                    continue;
+                };
+                for label in &mut labels {
+                    if *label == merge {
+                        *label = keep;
+                    }
+                }
+            }
+        }
+
+        let mut groups: HashMap<usize, Vec<&MerchantDetail>> = HashMap::new();
+        for (key, label) in keys.iter().zip(&labels) {
+            groups
+                .entry(*label)
+                .or_default()
+                .extend(self.by_key.get(*key).into_iter().flatten());
+        }
+        let mut suggestions: Vec<_> = groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .filter_map(|mut group| {
+                group.sort_by_key(|merchant| merchant.created_at);
+                let (into, from) = group.split_first()?;
+                let key = |merchant: &MerchantDetail| {
+                    self.normaliser.key(&merchant.name).unwrap_or_default()
+                };
+                let similarity = from
+                    .iter()
+                    .map(|merchant| name_similarity(&key(into), &key(merchant)))
+                    .fold(1.0, f64::min);
+                Some(MerchantMergeSuggestion {
+                    into: (*into).clone(),
+                    from: from.iter().map(|merchant| (*merchant).clone()).collect(),
+                    similarity,
+                })
+            })
+            .collect();
+        suggestions.sort_by(|a, b| a.into.name.cmp(&b.into.name));
+        suggestions
+    }
+
+    /// Number of distinct merchant names in the index
+    pub fn len(&self) -> usize {
+        self.by_key.len()
+    }
+
+    /// Returns `true` if the index holds no merchants
+    pub fn is_empty(&self) -> bool {
+        self.by_key.is_empty()
+    }
+}
+
+/// The [`normalise_name`] words of `text`
+fn words(text: &str) -> Vec<String> {
+    normalise_name(text)
+        .split(' ')
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Counts of the adjacent letter pairs in a normalised name, ignoring spaces
+fn letter_pairs(name: &str) -> HashMap<(char, char), usize> {
+    let letters: Vec<char> = normalise_name(name)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let mut pairs = HashMap::new();
+    for pair in letters.windows(2) {
+        if let [first, second] = pair {
+            let count: &mut usize = pairs.entry((*first, *second)).or_default();
+            *count = count.saturating_add(1);
+        }
+    }
+    pairs
+}
+
+/// Title-case a word written entirely in capitals; leave others alone
+fn title_case_if_shouted(word: &str) -> String {
+    if word.chars().any(char::is_lowercase) {
+        return word.to_string();
+    }
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| {
+            first
+                .to_uppercase()
+                .chain(chars.flat_map(char::to_lowercase))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merchant(id: u128, name: &str, created_at: &str) -> MerchantDetail {
+        serde_json::from_value(serde_json::json!({
+            "id": uuid::Uuid::from_u128(id),
+            "name": name,
+            "created_at": created_at,
+            "updated_at": created_at
+        }))
+        .expect("valid merchant fixture")
+    }
+
+    #[test]
+    fn normalises_case_punctuation_and_whitespace() {
+        assert_eq!(normalise_name("COFFEE-CO  Ltd."), "coffee co ltd");
+        assert_eq!(normalise_name("  Pak'nSave   "), "pak nsave");
+        assert_eq!(normalise_name(""), "");
+    }
+
+    #[test]
+    fn reduces_descriptions_to_canonical_merchant_names() {
+        let normaliser = MerchantNormaliser::new().with_location("Ponsonby");
+
+        let canonical = |name| normaliser.canonical_name(name);
+        assert_eq!(
+            canonical("POS W/D COUNTDOWN METL-1234"),
+            Some("Countdown".to_string())
+        );
+        assert_eq!(
+            canonical("EFTPOS COUNTDOWN PONSONBY 4921 NZ"),
+            Some("Countdown".to_string())
+        );
+        assert_eq!(
+            canonical("SQ *Little Bird Cafe"),
+            Some("Little Bird Cafe".to_string())
+        );
+        assert_eq!(canonical("7-Eleven #04412"), Some("7-Eleven".to_string()));
+        assert_eq!(canonical("McDonald's Ltd"), Some("McDonald's".to_string()));
+        assert_eq!(
+            canonical("VISA PURCHASE 12/03/2024"),
+            Some("Visa Purchase".to_string())
+        );
+        assert_eq!(
+            canonical("Unit 101 Coffee Co 4921"),
+            Some("Unit 101 Coffee Co".to_string())
+        );
+        assert_eq!(canonical("PayPal"), Some("PayPal".to_string()));
+        assert_eq!(canonical("VISA"), Some("Visa".to_string()));
+        assert_eq!(canonical("Square"), Some("Square".to_string()));
+        assert_eq!(canonical("PAYPAL *NETFLIX"), Some("Netflix".to_string()));
+        assert_eq!(canonical("#04412"), Some("04412".to_string()));
+        assert_eq!(canonical(" ** "), None);
+        assert_eq!(
+            normaliser.key("COUNTDOWN LTD"),
+            Some("countdown".to_string())
+        );
+    }
+
+    #[test]
+    fn index_finds_merchants_by_key_and_groups_near_duplicates() {
+        let index = MerchantIndex::new(
+            MerchantNormaliser::new(),
+            [
+                merchant(1, "COUNTDOWN LTD", "2024-02-01T00:00:00Z"),
+                merchant(2, "Countdown", "2024-01-01T00:00:00Z"),
+                merchant(3, "Z Energy", "2024-01-01T00:00:00Z"),
+            ],
+        );
+
+        let found = index.get("POS W/D COUNTDOWN METL-1234").expect("countdown");
+        assert_eq!(found.name, "Countdown");
+        assert!(index.get("Mitre 10").is_none());
+        assert_eq!(index.len(), 2);
+        let groups = index.near_duplicates();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups.first().map(|group| group.len()), Some(2));
+    }
+
+    #[test]
+    fn suggests_merges_for_similar_names() {
+        assert!((name_similarity("Pak n Save", "PAKNSAVE") - 1.0).abs() < f64::EPSILON);
+        assert!(name_similarity("Countdown", "Countdwn") > DEFAULT_MERGE_SIMILARITY);
+        assert!(name_similarity("Countdown", "Z Energy") < 0.2);
+
+        let index = MerchantIndex::new(
+            MerchantNormaliser::new(),
+            [
+                merchant(1, "Countdwn", "2024-03-01T00:00:00Z"),
+                merchant(2, "COUNTDOWN LTD", "2024-02-01T00:00:00Z"),
+                merchant(3, "Countdown", "2024-01-01T00:00:00Z"),
+                merchant(4, "Z Energy", "2024-01-01T00:00:00Z"),
+            ],
+        );
+
+        let suggestions = index.suggest_merges(DEFAULT_MERGE_SIMILARITY);
+        assert_eq!(suggestions.len(), 1);
+        let suggestion = suggestions.first().expect("one suggestion");
+        assert_eq!(suggestion.into.name, "Countdown");
+        let from: Vec<_> = suggestion.from.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(from, ["COUNTDOWN LTD", "Countdwn"]);
+        assert!(suggestion.similarity >= DEFAULT_MERGE_SIMILARITY);
+    }
+}
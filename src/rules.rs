@@ -0,0 +1,657 @@
+//! Client-side rules for categorising transactions
+//!
+//! A [`RuleSet`] is an ordered list of [`Rule`]s, usually loaded from TOML:
+//!
+//! ```toml
+//! [[rules]]
+//! name = "Supermarkets"
+//! when.name = "(?i)countdown|pak ?n ?save|new world"
+//! when.nature = "expense"
+//! then.category_id = "550e8400-e29b-41d4-a716-446655440000"
+//!
+//! [[rules]]
+//! name = "Small coffee purchases"
+//! when.name = "(?i)coffee|espresso"
+//! when.max_amount = "15"
+//! then.notes = "Coffee"
+//! stop = true
+//! ```
+//!
+//! Every condition in `when` must hold for a rule to match; a rule without
+//! conditions matches every transaction. Rules are evaluated in order, and a
+//! field set by an earlier matching rule is never overwritten by a later one,
+//! even when the transaction already had the earlier rule's value. A rule
+//! with `stop = true` ends evaluation for the transactions it matches.
+//!
+//! Only fields whose value would actually change are updated, so applying the
+//! same rules twice changes nothing the second time.
+//! [`SureClient::apply_rules`](crate::SureClient::apply_rules) evaluates a
+//! rule set over the transactions matching a filter and sends the updates.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::error::RuleError;
+use crate::models::batch::BatchReport;
+use crate::models::transaction::{Transaction, TransactionNature, UpdateTransactionData};
+use crate::types::{AccountId, CategoryId, MerchantId, Patch, TagId, TransactionId};
+
+/// A regular expression that can be read from and written to rule files
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// Compile a pattern
+    ///
+    /// # Errors
+    /// Returns an error if `pattern` is not a valid regular expression.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self(Regex::new(pattern)?))
+    }
+
+    /// Returns `true` if the pattern matches anywhere in `text`
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+
+    /// The source of the pattern
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl Serialize for Pattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Conditions a transaction must meet for a rule to match
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConditions {
+    /// Pattern the transaction name must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<Pattern>,
+    /// Pattern the notes must match; transactions without notes never match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<Pattern>,
+    /// Smallest amount, inclusive, ignoring sign
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<Decimal>,
+    /// Largest amount, inclusive, ignoring sign
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<Decimal>,
+    /// Whether the transaction is income or an expense
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nature: Option<TransactionNature>,
+    /// The transaction must be in one of these accounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_ids: Option<Vec<AccountId>>,
+    /// The transaction's merchant must be one of these
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchant_ids: Option<Vec<MerchantId>>,
+    /// Whether the transaction must (or must not) already have a merchant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_merchant: Option<bool>,
+    /// Whether the transaction must (or must not) already have a category
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_category: Option<bool>,
+    /// Earliest date, inclusive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<NaiveDate>,
+    /// Latest date, inclusive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<NaiveDate>,
+}
+
+impl RuleConditions {
+    /// Returns `true` if the transaction meets every condition
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        let amount = transaction.signed_amount().abs();
+        let date = transaction.date.date_naive();
+        let merchant_id = transaction.merchant.as_ref().map(|merchant| merchant.id);
+
+        self.name
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&transaction.name))
+            && self.notes.as_ref().is_none_or(|pattern| {
+                transaction
+                    .notes
+                    .as_deref()
+                    .is_some_and(|notes| pattern.is_match(notes))
+            })
+            && self.min_amount.is_none_or(|min| amount >= min)
+            && self.max_amount.is_none_or(|max| amount <= max)
+            && self
+                .nature
+                .is_none_or(|nature| transaction.classification.nature() == Some(nature))
+            && self
+                .account_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&transaction.account.id))
+            && self
+                .merchant_ids
+                .as_ref()
+                .is_none_or(|ids| merchant_id.is_some_and(|id| ids.contains(&id)))
+            && self
+                .has_merchant
+                .is_none_or(|expected| merchant_id.is_some() == expected)
+            && self
+                .has_category
+                .is_none_or(|expected| transaction.category.is_some() == expected)
+            && self.from.is_none_or(|from| date >= from)
+            && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+/// Changes a rule makes to the transactions it matches
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleActions {
+    /// Set the category
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category_id: Option<CategoryId>,
+    /// Set the merchant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchant_id: Option<MerchantId>,
+    /// Replace the tags
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_ids: Option<Vec<TagId>>,
+    /// Set the notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Set whether the transaction is income or an expense
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nature: Option<TransactionNature>,
+}
+
+impl RuleActions {
+    /// Returns `true` if the rule would change nothing
+    pub const fn is_empty(&self) -> bool {
+        self.category_id.is_none()
+            && self.merchant_id.is_none()
+            && self.tag_ids.is_none()
+            && self.notes.is_none()
+            && self.nature.is_none()
+    }
+
+    /// Add the changes this rule makes to `transaction` to `update`.
+    ///
+    /// Every field the rule sets is added to `claimed`, even when it already
+    /// has the value, and fields an earlier rule claimed are skipped. Only
+    /// fields whose value changes are added to `update`.
+    fn apply(
+        &self,
+        transaction: &Transaction,
+        update: &mut UpdateTransactionData,
+        claimed: &mut HashSet<&'static str>,
+        diff: &mut Vec<FieldChange>,
+    ) {
+        if let Some(category_id) = self.category_id.filter(|_| claimed.insert("category_id")) {
+            let before = transaction.category.as_ref().map(|category| category.id);
+            if before != Some(category_id) {
+                update.category_id = Patch::Set(category_id);
+                diff.push(FieldChange::new(
+                    "category_id",
+                    before.map(|id| id.to_string()),
+                    category_id.to_string(),
+                ));
+            }
+        }
+
+        if let Some(merchant_id) = self.merchant_id.filter(|_| claimed.insert("merchant_id")) {
+            let before = transaction.merchant.as_ref().map(|merchant| merchant.id);
+            if before != Some(merchant_id) {
+                update.merchant_id = Patch::Set(merchant_id);
+                diff.push(FieldChange::new(
+                    "merchant_id",
+                    before.map(|id| id.to_string()),
+                    merchant_id.to_string(),
+                ));
+            }
+        }
+
+        if let Some(tag_ids) = self.tag_ids.as_ref().filter(|_| claimed.insert("tag_ids")) {
+            let mut before: Vec<TagId> = transaction.tags.iter().map(|tag| tag.id).collect();
+            let mut after = tag_ids.clone();
+            before.sort_by_key(|id| *id.as_uuid());
+            after.sort_by_key(|id| *id.as_uuid());
+            after.dedup();
+            if before != after {
+                update.tag_ids = Patch::Set(after.clone());
+                diff.push(FieldChange::new(
+                    "tag_ids",
+                    (!before.is_empty()).then(|| join_ids(&before)),
+                    join_ids(&after),
+                ));
+            }
+        }
+
+        if let Some(notes) = self.notes.as_ref().filter(|_| claimed.insert("notes")) {
+            if transaction.notes.as_ref() != Some(notes) {
+                update.notes = Patch::Set(notes.clone());
+                diff.push(FieldChange::new(
+                    "notes",
+                    transaction.notes.clone(),
+                    notes.clone(),
+                ));
+            }
+        }
+
+        if let Some(nature) = self.nature.filter(|_| claimed.insert("nature")) {
+            if transaction.classification.nature() != Some(nature) {
+                update.nature = Some(nature);
+                diff.push(FieldChange::new(
+                    "nature",
+                    Some(transaction.classification.to_string()),
+                    nature.to_string(),
+                ));
+            }
+        }
+    }
+}
+
+fn join_ids(ids: &[TagId]) -> String {
+    ids.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A named condition and the changes made to the transactions it matches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// Name used in reports
+    pub name: String,
+    /// Conditions a transaction must meet
+    #[serde(default)]
+    pub when: RuleConditions,
+    /// Changes made to matching transactions
+    pub then: RuleActions,
+    /// Stop evaluating later rules for transactions this rule matches
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stop: bool,
+}
+
+/// An ordered list of rules
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSet {
+    /// Rules, in evaluation order
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// Parse and validate rules from TOML
+    ///
+    /// # Errors
+    /// Returns `RuleError::TomlDeserialization` if the TOML is malformed or a
+    /// pattern is not a valid regular expression, or `RuleError::InvalidRule`
+    /// if a rule has no actions or an empty amount or date range.
+    pub fn from_toml(toml: &str) -> Result<Self, RuleError> {
+        let rules: Self = toml::from_str(toml)?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Serialize the rules as TOML
+    ///
+    /// # Errors
+    /// Returns `RuleError::TomlSerialization` if the rules cannot be written.
+    pub fn to_toml(&self) -> Result<String, RuleError> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Load rules from a TOML file
+    ///
+    /// # Errors
+    /// Returns `RuleError::Io` if the file cannot be read, or any error from
+    /// [`RuleSet::from_toml`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RuleError> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Save the rules to a TOML file
+    ///
+    /// # Errors
+    /// Returns `RuleError::Io` if the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RuleError> {
+        Ok(std::fs::write(path, self.to_toml()?)?)
+    }
+
+    /// Check every rule can match something and changes something
+    ///
+    /// # Errors
+    /// Returns `RuleError::InvalidRule` for the first invalid rule.
+    pub fn validate(&self) -> Result<(), RuleError> {
+        for rule in &self.rules {
+            let invalid = |message: &str| RuleError::InvalidRule {
+                rule: rule.name.clone(),
+                message: message.to_string(),
+            };
+            if rule.then.is_empty() {
+                return Err(invalid("it has no actions"));
+            }
+            if matches!((rule.when.min_amount, rule.when.max_amount), (Some(min), Some(max)) if min > max)
+            {
+                return Err(invalid("min_amount is greater than max_amount"));
+            }
+            if matches!((rule.when.from, rule.when.to), (Some(from), Some(to)) if from > to) {
+                return Err(invalid("from is after to"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluate the rules over `transactions` without changing anything
+    pub fn plan(&self, transactions: &[Transaction]) -> RulePlan {
+        let mut hits: Vec<RuleHits> = self
+            .rules
+            .iter()
+            .map(|rule| RuleHits {
+                rule: rule.name.clone(),
+                matched: 0,
+                changed: 0,
+            })
+            .collect();
+        let mut changes = Vec::new();
+
+        for transaction in transactions {
+            let mut update = UpdateTransactionData::default();
+            let mut claimed = HashSet::new();
+            let mut diff = Vec::new();
+            let mut rules = Vec::new();
+
+            for (rule, hits) in self.rules.iter().zip(hits.iter_mut()) {
+                if !rule.when.matches(transaction) {
+                    continue;
+                }
+                hits.matched = hits.matched.saturating_add(1);
+
+                let changed_before = diff.len();
+                rule.then
+                    .apply(transaction, &mut update, &mut claimed, &mut diff);
+                if diff.len() > changed_before {
+                    hits.changed = hits.changed.saturating_add(1);
+                    rules.push(rule.name.clone());
+                }
+
+                if rule.stop {
+                    break;
+                }
+            }
+
+            if !update.is_empty() {
+                changes.push(PlannedChange {
+                    transaction_id: transaction.id,
+                    transaction_name: transaction.name.clone(),
+                    rules,
+                    diff,
+                    update,
+                });
+            }
+        }
+
+        RulePlan { changes, hits }
+    }
+}
+
+/// A change to one field of a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// Name of the field, as sent in the update request
+    pub field: &'static str,
+    /// Current value, if any
+    pub before: Option<String>,
+    /// New value
+    pub after: String,
+}
+
+impl FieldChange {
+    const fn new(field: &'static str, before: Option<String>, after: String) -> Self {
+        Self {
+            field,
+            before,
+            after,
+        }
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let before = self.before.as_deref().unwrap_or("(none)");
+        write!(f, "{}: {before} -> {}", self.field, self.after)
+    }
+}
+
+/// The changes the rules make to one transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedChange {
+    /// The transaction to update
+    pub transaction_id: TransactionId,
+    /// Its name, for display
+    pub transaction_name: String,
+    /// Names of the rules that contributed a change, in evaluation order
+    pub rules: Vec<String>,
+    /// Every field that changes
+    pub diff: Vec<FieldChange>,
+    /// The update request that makes the changes
+    pub update: UpdateTransactionData,
+}
+
+impl fmt::Display for PlannedChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) [{}]",
+            self.transaction_name,
+            self.transaction_id,
+            self.rules.join(", ")
+        )?;
+        for change in &self.diff {
+            write!(f, "\n  {change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How often a rule matched and how often it changed something
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleHits {
+    /// Name of the rule
+    pub rule: String,
+    /// Number of transactions the rule matched
+    pub matched: usize,
+    /// Number of those transactions the rule changed. Lower than `matched`
+    /// when a transaction already had the values, or an earlier rule set them.
+    pub changed: usize,
+}
+
+/// The result of evaluating a rule set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RulePlan {
+    /// One entry per transaction that changes
+    pub changes: Vec<PlannedChange>,
+    /// One entry per rule, in rule order
+    pub hits: Vec<RuleHits>,
+}
+
+/// Report for applying a rule set
+#[derive(Debug)]
+pub struct RulesReport {
+    /// The changes the rules make and how often each rule matched
+    pub plan: RulePlan,
+    /// Per-transaction results, in the same order as `plan.changes`.
+    /// `None` for a dry run.
+    pub results: Option<BatchReport<Transaction>>,
+}
+
+impl RulesReport {
+    /// Returns `true` if this was a dry run and nothing was sent
+    pub const fn is_dry_run(&self) -> bool {
+        self.results.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use uuid::Uuid;
+
+    const RULES: &str = r#"
+        [[rules]]
+        name = "Supermarkets"
+        when.name = "(?i)countdown|pak ?n ?save"
+        when.nature = "expense"
+        then.category_id = "00000000-0000-0000-0000-000000000010"
+
+        [[rules]]
+        name = "Large supermarket shops"
+        when.name = "(?i)countdown"
+        when.min_amount = "100"
+        then.category_id = "00000000-0000-0000-0000-000000000011"
+        then.notes = "Big shop"
+        stop = true
+
+        [[rules]]
+        name = "Everything else"
+        when.from = "2024-01-01"
+        then.tag_ids = ["00000000-0000-0000-0000-000000000020"]
+    "#;
+
+    fn transaction(id: u128, name: &str, signed_amount_cents: i64) -> Transaction {
+        test_fixtures::transaction_with(
+            id,
+            "2024-03-01",
+            name,
+            signed_amount_cents,
+            serde_json::json!({
+                "category": {
+                    "id": Uuid::from_u128(0x10),
+                    "name": "Groceries",
+                    "color": "#e99537",
+                    "icon": "shopping-cart"
+                }
+            }),
+        )
+    }
+
+    #[test]
+    fn plans_changes_in_rule_order() {
+        let rules = RuleSet::from_toml(RULES).expect("valid rules");
+        let transactions = [
+            transaction(1, "COUNTDOWN PONSONBY", -15_000),
+            transaction(2, "Pak n Save", -4_000),
+            transaction(3, "Salary", 500_000),
+        ];
+
+        let plan = rules.plan(&transactions);
+
+        // The first rule matched the big shop and claimed the category it
+        // already had, so the second rule only set the notes, then stopped the
+        // third rule. The small shop was already categorised.
+        let big_shop = plan.changes.first().expect("big shop changes");
+        assert_eq!(big_shop.rules, ["Large supermarket shops"]);
+        assert_eq!(
+            big_shop.update,
+            UpdateTransactionData::builder()
+                .notes("Big shop".to_string())
+                .build()
+        );
+        assert_eq!(
+            big_shop.diff.first().map(ToString::to_string).as_deref(),
+            Some("notes: (none) -> Big shop")
+        );
+
+        let tagged: Vec<TransactionId> = plan
+            .changes
+            .iter()
+            .skip(1)
+            .map(|change| change.transaction_id)
+            .collect();
+        assert_eq!(
+            tagged,
+            [
+                TransactionId::new(Uuid::from_u128(2)),
+                TransactionId::new(Uuid::from_u128(3))
+            ]
+        );
+
+        let hits: Vec<(usize, usize)> = plan
+            .hits
+            .iter()
+            .map(|hits| (hits.matched, hits.changed))
+            .collect();
+        assert_eq!(hits, [(2, 0), (1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn earlier_rule_keeps_a_field_it_did_not_change() {
+        let rules = RuleSet::from_toml(
+            r#"
+            [[rules]]
+            name = "Groceries"
+            when.name = "(?i)countdown"
+            then.category_id = "00000000-0000-0000-0000-000000000010"
+
+            [[rules]]
+            name = "Everything categorised"
+            when.has_category = true
+            then.category_id = "00000000-0000-0000-0000-000000000011"
+            "#,
+        );
+        let rules = rules.expect("valid rules");
+
+        // Already in the first rule's category, so the second rule must not
+        // move it; otherwise each run would flip it back and forth
+        let plan = rules.plan(&[transaction(1, "COUNTDOWN", -1_000)]);
+        assert!(plan.changes.is_empty(), "{:?}", plan.changes);
+    }
+
+    #[test]
+    fn round_trips_rules_through_toml() {
+        let rules = RuleSet::from_toml(RULES).expect("valid rules");
+        let toml = rules.to_toml().expect("serializes");
+        assert_eq!(RuleSet::from_toml(&toml).expect("reparses"), rules);
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let bad_pattern = "[[rules]]\nname = \"Bad\"\nwhen.name = \"(\"\nthen.notes = \"x\"";
+        assert!(matches!(
+            RuleSet::from_toml(bad_pattern),
+            Err(RuleError::TomlDeserialization(_))
+        ));
+
+        let no_actions = "[[rules]]\nname = \"Empty\"\nwhen.name = \"coffee\"\nthen = {}";
+        assert!(matches!(
+            RuleSet::from_toml(no_actions),
+            Err(RuleError::InvalidRule { rule, .. }) if rule == "Empty"
+        ));
+    }
+}
@@ -135,110 +135,98 @@ where
                 .ok_or_else(|| E::custom(format!("invalid float value: {}", value)))
         }
 
-        #[allow(
-            clippy::else_if_without_else,
-            clippy::string_slice,
-            clippy::arithmetic_side_effects,
-            reason = "Character filtering logic with safe string operations - indices checked before use"
-        )]
         fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
         where
             E: de::Error,
         {
-            let mut s = v.trim();
-            let is_negative = (s.starts_with('(') && s.ends_with(')')) || s.starts_with('-');
-            if s.starts_with('(') && s.ends_with(')') {
-                s = &s[1..s.len() - 1];
-            }
+            parse_flexible_decimal(v).map_err(de::Error::custom)
+        }
+    }
 
-            // Determine decimal and thousands separators
-            let last_dot = s.rfind('.');
-            let last_comma = s.rfind(',');
-
-            let mut final_str = String::with_capacity(s.len());
-
-            match (last_dot, last_comma) {
-                (Some(dot_pos), Some(comma_pos)) if dot_pos > comma_pos => {
-                    for c in s.chars() {
-                        if c.is_ascii_digit() {
-                            final_str.push(c);
-                        } else if c == '.' {
-                            final_str.push('.');
-                        }
-                    }
-                }
-                (Some(dot_pos), Some(comma_pos)) if comma_pos > dot_pos => {
-                    for c in s.chars() {
-                        if c.is_ascii_digit() {
-                            final_str.push(c);
-                        } else if c == ',' {
-                            final_str.push('.'); // Convert decimal comma to dot
-                        }
-                    }
-                }
-                (None, Some(_)) => {
-                    if s.matches(',').count() > 1 || (s.rfind(',').unwrap_or(0) < s.len() - 3) {
-                        // Treat all commas as thousands separators
-                        for c in s.chars() {
-                            if c.is_ascii_digit() {
-                                final_str.push(c);
-                            }
-                        }
-                    } else {
-                        // Treat the single comma as a decimal separator
-                        for c in s.chars() {
-                            if c.is_ascii_digit() {
-                                final_str.push(c);
-                            } else if c == ',' {
-                                final_str.push('.');
-                            }
-                        }
-                    }
-                }
-                (Some(_), None) => {
-                    if s.matches('.').count() > 1 || (s.rfind('.').unwrap_or(0) < s.len() - 3) {
-                        // Treat all dots as thousands separators
-                        for c in s.chars() {
-                            if c.is_ascii_digit() {
-                                final_str.push(c);
-                            }
-                        }
-                    } else {
-                        // Treat the single dot as a decimal separator
-                        for c in s.chars() {
-                            if c.is_ascii_digit() {
-                                final_str.push(c);
-                            } else if c == '.' {
-                                final_str.push('.');
-                            }
-                        }
-                    }
-                }
-                (None, None) => {
-                    for c in s.chars() {
-                        if c.is_ascii_digit() {
-                            final_str.push(c);
-                        }
-                    }
-                }
-                (Some(_), Some(_)) => unreachable!(),
-            }
+    deserializer.deserialize_any(FlexibleDecimalVisitor)
+}
 
-            // Prepend minus sign if negative
-            if is_negative {
-                final_str.insert(0, '-');
-            }
+/// Parse a `Decimal` from a loosely formatted string.
+///
+/// Accepts the same formats as [`deserialize_flexible_decimal`]: thousands
+/// separators, decimal commas, currency symbols and negatives written with a
+/// leading minus or parentheses.
+///
+/// When both `.` and `,` appear, the last one is the decimal separator. A
+/// single separator followed by exactly three digits is read as a thousands
+/// separator (`1.000` is 1000) unless the whole part is zero (`0.125`).
+/// Callers that know the separator should use [`parse_decimal`] instead.
+///
+/// # Errors
+/// Returns an error if the remaining digits do not form a valid decimal.
+pub fn parse_flexible_decimal(v: &str) -> Result<Decimal, rust_decimal::Error> {
+    let decimal_separator = match (v.rfind('.'), v.rfind(',')) {
+        (Some(dot_pos), Some(comma_pos)) => Some(if dot_pos > comma_pos { '.' } else { ',' }),
+        (Some(_), None) => Some('.').filter(|_| is_decimal_separator(v, '.')),
+        (None, Some(_)) => Some(',').filter(|_| is_decimal_separator(v, ',')),
+        (None, None) => None,
+    };
+    parse_digits(v, decimal_separator)
+}
 
-            // Handle cases like ".50" or ",50" which become ".50"
-            if final_str.starts_with('.') {
-                final_str.insert(0, '0');
-            }
+/// Parse a `Decimal` whose decimal separator is known.
+///
+/// Every other non-digit character, such as thousands separators and
+/// currency symbols, is ignored. Negatives may be written with a leading
+/// minus or parentheses.
+///
+/// # Errors
+/// Returns an error if the remaining digits do not form a valid decimal, for
+/// example when `decimal_separator` appears more than once.
+pub fn parse_decimal(v: &str, decimal_separator: char) -> Result<Decimal, rust_decimal::Error> {
+    parse_digits(v, Some(decimal_separator))
+}
+
+/// Whether the only `separator` in `s` separates decimals rather than thousands
+fn is_decimal_separator(s: &str, separator: char) -> bool {
+    let Some((whole, fraction)) = s.rsplit_once(separator) else {
+        return false;
+    };
+    if whole.contains(separator) {
+        return false;
+    }
+    let fraction_digits = fraction.chars().filter(char::is_ascii_digit).count();
+    let whole_is_zero = whole.chars().filter(char::is_ascii_digit).all(|c| c == '0');
+    fraction_digits != 3 || whole_is_zero
+}
+
+/// Keep the digits of `v`, turning `decimal_separator` into `.`
+fn parse_digits(v: &str, decimal_separator: Option<char>) -> Result<Decimal, rust_decimal::Error> {
+    let s = v.trim();
+    let is_negative = (s.starts_with('(') && s.ends_with(')')) || s.starts_with('-');
 
-            Decimal::from_str(&final_str).map_err(de::Error::custom)
+    let mut final_str = String::with_capacity(s.len().saturating_add(2));
+    if is_negative {
+        final_str.push('-');
+    }
+    let mut has_digits = false;
+    for c in s.chars() {
+        match c {
+            '0'..='9' => {
+                has_digits = true;
+                final_str.push(c);
+            }
+            _ if Some(c) == decimal_separator => {
+                // Handle cases like ".50" or ",50" which become "0.50"
+                if !has_digits {
+                    final_str.push('0');
+                }
+                final_str.push('.');
+            }
+            _ => {}
         }
     }
+    // Handle cases like "5," which become "5"
+    if final_str.ends_with('.') {
+        final_str.pop();
+    }
 
-    deserializer.deserialize_any(FlexibleDecimalVisitor)
+    Decimal::from_str(&final_str)
 }
 
 #[cfg(test)]
@@ -327,4 +315,31 @@ mod tests {
         test_parsing(r#"{"balance": ",50"}"#, Decimal::from_f64(0.50).unwrap());
         test_parsing(r#"{"balance": "0,50"}"#, Decimal::from_f64(0.50).unwrap());
     }
+
+    #[test]
+    fn flexible_decimal_handles_short_and_zero_led_values() {
+        assert_eq!(parse_flexible_decimal(".5"), Ok(Decimal::new(5, 1)));
+        assert_eq!(parse_flexible_decimal("5,"), Ok(Decimal::from(5)));
+        assert_eq!(parse_flexible_decimal("0.125"), Ok(Decimal::new(125, 3)));
+        assert_eq!(parse_flexible_decimal("-0,125"), Ok(Decimal::new(-125, 3)));
+        assert_eq!(parse_flexible_decimal("1.2345"), Ok(Decimal::new(12345, 4)));
+        // Ambiguous: one separator and three digits reads as thousands
+        assert_eq!(parse_flexible_decimal("1.234"), Ok(Decimal::from(1234)));
+        parse_flexible_decimal("").expect_err("no digits");
+    }
+
+    #[test]
+    fn decimal_with_known_separator_is_not_guessed() {
+        assert_eq!(parse_decimal("1.234", '.'), Ok(Decimal::new(1234, 3)));
+        assert_eq!(parse_decimal("1.234", ','), Ok(Decimal::from(1234)));
+        assert_eq!(parse_decimal("1.234,56", ','), Ok(Decimal::new(123_456, 2)));
+        assert_eq!(
+            parse_decimal("$1,234.56", '.'),
+            Ok(Decimal::new(123_456, 2))
+        );
+        assert_eq!(parse_decimal("(0.125)", '.'), Ok(Decimal::new(-125, 3)));
+        assert_eq!(parse_decimal(".5", '.'), Ok(Decimal::new(5, 1)));
+        assert_eq!(parse_decimal("5,", ','), Ok(Decimal::from(5)));
+        parse_decimal("1.2.3", '.').expect_err("two decimal separators");
+    }
 }
@@ -0,0 +1,577 @@
+//! Validated colours and icons for categories, merchants and tags
+//!
+//! Sure rejects a malformed colour or an icon outside its set with a `422`.
+//! [`HexColor`] and [`Icon`] check these values before a request is sent, and
+//! [`ColorPalette`] picks colours for categories created on the user's behalf.
+//! [`Icon::custom`] skips the check for icons the server accepts beyond
+//! [`SURE_ICONS`].
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// The colours Sure offers when creating a category, in the order it offers them
+pub const SURE_PALETTE: [HexColor; 10] = [
+    HexColor::new(0xe9, 0x95, 0x37),
+    HexColor::new(0x4d, 0xa5, 0x68),
+    HexColor::new(0x64, 0x71, 0xeb),
+    HexColor::new(0xdb, 0x5a, 0x54),
+    HexColor::new(0xdf, 0x4e, 0x92),
+    HexColor::new(0xc4, 0x4f, 0xe9),
+    HexColor::new(0xeb, 0x54, 0x29),
+    HexColor::new(0x61, 0xc9, 0xea),
+    HexColor::new(0x80, 0x5d, 0xee),
+    HexColor::new(0x6a, 0xd2, 0x8a),
+];
+
+/// The Lucide icon names Sure offers in its category icon picker
+///
+/// The server stores any Lucide name it is sent; use [`Icon::custom`] for
+/// icons outside this list.
+pub const SURE_ICONS: &[&str] = &[
+    "ambulance",
+    "apple",
+    "award",
+    "baby",
+    "banknote",
+    "barcode",
+    "bath",
+    "battery",
+    "bed-single",
+    "beer",
+    "bike",
+    "bluetooth",
+    "bone",
+    "book",
+    "book-open",
+    "briefcase",
+    "building",
+    "bus",
+    "cake",
+    "calculator",
+    "calendar-range",
+    "camera",
+    "car",
+    "cat",
+    "circle-dollar-sign",
+    "coffee",
+    "coins",
+    "compass",
+    "cookie",
+    "cooking-pot",
+    "credit-card",
+    "dices",
+    "dog",
+    "drama",
+    "drill",
+    "droplet",
+    "drum",
+    "dumbbell",
+    "film",
+    "flame",
+    "flower",
+    "fuel",
+    "gamepad-2",
+    "gift",
+    "glasses",
+    "globe",
+    "graduation-cap",
+    "hammer",
+    "hand-helping",
+    "headphones",
+    "heart",
+    "heart-pulse",
+    "home",
+    "ice-cream-cone",
+    "key",
+    "landmark",
+    "laptop",
+    "leaf",
+    "lightbulb",
+    "luggage",
+    "mail",
+    "map-pin",
+    "mic",
+    "monitor",
+    "moon",
+    "music",
+    "package",
+    "palette",
+    "paw-print",
+    "pen",
+    "pencil",
+    "phone",
+    "piggy-bank",
+    "pill",
+    "pizza",
+    "plane",
+    "plug",
+    "power",
+    "printer",
+    "puzzle",
+    "receipt",
+    "receipt-text",
+    "ribbon",
+    "scale",
+    "scissors",
+    "settings",
+    "shapes",
+    "shield",
+    "shield-plus",
+    "shirt",
+    "shopping-bag",
+    "shopping-cart",
+    "smartphone",
+    "sparkles",
+    "sprout",
+    "stethoscope",
+    "store",
+    "sun",
+    "tag",
+    "target",
+    "tent",
+    "thermometer",
+    "ticket",
+    "train",
+    "trash-2",
+    "tree-palm",
+    "trees",
+    "trending-up",
+    "trophy",
+    "truck",
+    "tv",
+    "umbrella",
+    "undo-2",
+    "unplug",
+    "users",
+    "utensils",
+    "video",
+    "wallet",
+    "wallet-cards",
+    "waves",
+    "wifi",
+    "wine",
+    "wrench",
+    "zap",
+];
+
+/// Hue step between generated colours, in degrees; the golden angle keeps
+/// successive hues far apart
+const GOLDEN_ANGLE: f64 = 137.507_764;
+
+/// Saturation and lightness of generated colours, close to Sure's own palette
+const GENERATED_SATURATION: f64 = 0.7;
+const GENERATED_LIGHTNESS: f64 = 0.6;
+
+/// An RGB colour written as a `#rrggbb` hex code
+///
+/// Parsing accepts `#rrggbb` and the `#rgb` shorthand in either case. The
+/// colour is written back as `#rrggbb` in the case it was given, so the server
+/// stores what the caller typed; colours built with [`new`](Self::new) are
+/// lowercase. Two colours are equal when their components are, whatever the
+/// case.
+///
+/// ```
+/// use sure_client_rs::HexColor;
+///
+/// let color: HexColor = "#FF5733".parse()?;
+/// assert_eq!(color.rgb(), (0xff, 0x57, 0x33));
+/// assert_eq!(color.to_string(), "#FF5733");
+/// assert_eq!(color, HexColor::new(0xff, 0x57, 0x33));
+/// assert!("red".parse::<HexColor>().is_err());
+/// # Ok::<(), sure_client_rs::style::ParseHexColorError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HexColor {
+    red: u8,
+    green: u8,
+    blue: u8,
+    uppercase: bool,
+}
+
+impl HexColor {
+    /// A colour from its red, green and blue components
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            uppercase: false,
+        }
+    }
+
+    /// The red, green and blue components
+    pub const fn rgb(self) -> (u8, u8, u8) {
+        (self.red, self.green, self.blue)
+    }
+
+    /// A colour from its hue (degrees), saturation and lightness (`0.0..=1.0`)
+    ///
+    /// Out-of-range saturation and lightness are clamped, and the hue wraps.
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - 2.0_f64.mul_add(lightness, -1.0).abs()) * saturation;
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let second = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+        let (red, green, blue) = match sector {
+            s if s < 1.0 => (chroma, second, 0.0),
+            s if s < 2.0 => (second, chroma, 0.0),
+            s if s < 3.0 => (0.0, chroma, second),
+            s if s < 4.0 => (0.0, second, chroma),
+            s if s < 5.0 => (second, 0.0, chroma),
+            _ => (chroma, 0.0, second),
+        };
+        let offset = lightness - chroma / 2.0;
+        // Float-to-int casts saturate, so rounding error cannot wrap around
+        let channel = |value: f64| ((value + offset) * 255.0).round() as u8;
+        Self::new(channel(red), channel(green), channel(blue))
+    }
+}
+
+impl PartialEq for HexColor {
+    fn eq(&self, other: &Self) -> bool {
+        self.rgb() == other.rgb()
+    }
+}
+
+impl Eq for HexColor {}
+
+impl Hash for HexColor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rgb().hash(state);
+    }
+}
+
+impl fmt::Display for HexColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.uppercase {
+            write!(f, "#{:02X}{:02X}{:02X}", self.red, self.green, self.blue)
+        } else {
+            write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+        }
+    }
+}
+
+/// Error returned when parsing a `HexColor` from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHexColorError(String);
+
+impl fmt::Display for ParseHexColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid hex color (expected #rrggbb): {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseHexColorError {}
+
+impl FromStr for HexColor {
+    type Err = ParseHexColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseHexColorError(s.to_string());
+        let digits = s.strip_prefix('#').ok_or_else(error)?;
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+        let digits = match digits.len() {
+            6 => digits.to_string(),
+            3 => digits.chars().flat_map(|c| [c, c]).collect(),
+            _ => return Err(error()),
+        };
+        let [_, red, green, blue] = u32::from_str_radix(&digits, 16)
+            .map_err(|_| error())?
+            .to_be_bytes();
+        Ok(Self {
+            uppercase: digits.chars().any(|c| c.is_ascii_uppercase()),
+            ..Self::new(red, green, blue)
+        })
+    }
+}
+
+impl TryFrom<&str> for HexColor {
+    type Error = ParseHexColorError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for HexColor {
+    type Error = ParseHexColorError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<HexColor> for String {
+    fn from(color: HexColor) -> Self {
+        color.to_string()
+    }
+}
+
+/// An endless supply of colours for new categories
+///
+/// Yields [`SURE_PALETTE`] first, then generates further colours by stepping
+/// the hue round the colour wheel. Colours already in use can be skipped with
+/// [`excluding`](Self::excluding), so new categories stand apart from existing
+/// ones for as long as the palette allows.
+///
+/// ```
+/// use sure_client_rs::style::{ColorPalette, SURE_PALETTE};
+///
+/// let mut palette = ColorPalette::new().excluding([SURE_PALETTE[0]]);
+/// assert_eq!(palette.next(), Some(SURE_PALETTE[1]));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ColorPalette {
+    position: usize,
+    generated: u32,
+    used: HashSet<HexColor>,
+}
+
+impl ColorPalette {
+    /// A palette starting at the first of Sure's colours
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip colours that are already in use
+    #[must_use]
+    pub fn excluding<I>(mut self, used: I) -> Self
+    where
+        I: IntoIterator<Item = HexColor>,
+    {
+        self.used.extend(used);
+        self
+    }
+
+    /// The next colour, ignoring colours in use
+    fn next_color(&mut self) -> HexColor {
+        if let Some(color) = SURE_PALETTE.get(self.position) {
+            self.position = self.position.saturating_add(1);
+            return *color;
+        }
+        let hue = f64::from(self.generated) * GOLDEN_ANGLE;
+        self.generated = self.generated.wrapping_add(1);
+        HexColor::from_hsl(hue, GENERATED_SATURATION, GENERATED_LIGHTNESS)
+    }
+
+    /// The next colour not already in use, which is then marked as used
+    pub fn pick(&mut self) -> HexColor {
+        let mut color = self.next_color();
+        // Generated hues eventually repeat; give up on avoiding used colours
+        // rather than loop forever once every colour is taken
+        for _ in 0..=self.used.len() {
+            if !self.used.contains(&color) {
+                break;
+            }
+            color = self.next_color();
+        }
+        self.used.insert(color);
+        color
+    }
+}
+
+impl Iterator for ColorPalette {
+    type Item = HexColor;
+
+    fn next(&mut self) -> Option<HexColor> {
+        Some(self.pick())
+    }
+}
+
+/// A Lucide icon name for a category
+///
+/// Parsing only accepts the names in [`SURE_ICONS`]; [`Icon::custom`] takes
+/// any other name the server is known to accept. Deserialising keeps whatever
+/// name was stored.
+///
+/// ```
+/// use sure_client_rs::Icon;
+///
+/// let icon: Icon = "shopping-cart".parse()?;
+/// assert_eq!(icon.as_str(), "shopping-cart");
+/// assert!("folder".parse::<Icon>().is_err());
+/// assert_eq!(Icon::custom("folder").as_str(), "folder");
+/// # Ok::<(), sure_client_rs::style::ParseIconError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Icon(Cow<'static, str>);
+
+impl Icon {
+    /// The icon Sure gives categories created without one
+    pub const DEFAULT: Self = Self(Cow::Borrowed("shapes"));
+
+    /// An icon name sent as given, without checking it against [`SURE_ICONS`]
+    pub fn custom<S: Into<String>>(name: S) -> Self {
+        Self(Cow::Owned(name.into()))
+    }
+
+    /// The icon name
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Icon {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl fmt::Display for Icon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Icon {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Serialize for Icon {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Icon {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(Self::custom)
+    }
+}
+
+/// Error returned when parsing an `Icon` from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIconError(String);
+
+impl fmt::Display for ParseIconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown icon: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseIconError {}
+
+impl FromStr for Icon {
+    type Err = ParseIconError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SURE_ICONS
+            .iter()
+            .find(|icon| **icon == s)
+            .map(|icon| Self(Cow::Borrowed(icon)))
+            .ok_or_else(|| ParseIconError(s.to_string()))
+    }
+}
+
+impl TryFrom<&str> for Icon {
+    type Error = ParseIconError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for Icon {
+    type Error = ParseIconError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Icon> for String {
+    fn from(icon: Icon) -> Self {
+        icon.0.into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_hex_colors() {
+        let parse = |s: &str| s.parse::<HexColor>();
+        assert_eq!(parse("#FF5733"), Ok(HexColor::new(0xff, 0x57, 0x33)));
+        assert_eq!(parse("#abc"), Ok(HexColor::new(0xaa, 0xbb, 0xcc)));
+        assert_eq!(
+            parse("#FF5733").map(|c| c.to_string()),
+            Ok("#FF5733".into())
+        );
+        assert_eq!(parse("#abc").map(|c| c.to_string()), Ok("#aabbcc".into()));
+        for bad in ["FF5733", "#FF573", "#FF57330", "#GG5733", "#+F5733", ""] {
+            assert_eq!(parse(bad), Err(ParseHexColorError(bad.to_string())));
+        }
+        assert_eq!(
+            serde_json::to_string(&HexColor::new(0, 0x70, 0x4a)).ok(),
+            Some(r##""#00704a""##.to_string())
+        );
+    }
+
+    #[test]
+    fn converts_hsl() {
+        assert_eq!(HexColor::from_hsl(0.0, 1.0, 0.5), HexColor::new(255, 0, 0));
+        assert_eq!(
+            HexColor::from_hsl(120.0, 1.0, 0.5),
+            HexColor::new(0, 255, 0)
+        );
+        assert_eq!(
+            HexColor::from_hsl(-120.0, 1.0, 0.5),
+            HexColor::new(0, 0, 255)
+        );
+        assert_eq!(
+            HexColor::from_hsl(42.0, 0.0, 1.0),
+            HexColor::new(255, 255, 255)
+        );
+    }
+
+    #[test]
+    fn palette_skips_used_colors_and_never_runs_out() {
+        let used = SURE_PALETTE.get(..3).unwrap_or_default().to_vec();
+        let colors: Vec<_> = ColorPalette::new()
+            .excluding(used.clone())
+            .take(40)
+            .collect();
+
+        assert_eq!(colors.first(), SURE_PALETTE.get(3));
+        assert!(colors.iter().all(|color| !used.contains(color)));
+        let distinct: HashSet<_> = colors.iter().collect();
+        assert_eq!(distinct.len(), colors.len());
+    }
+
+    #[test]
+    fn icons_must_be_in_sures_set() {
+        assert_eq!("tag".parse::<Icon>(), Ok(Icon::custom("tag")));
+        assert_eq!(
+            "Tag".parse::<Icon>(),
+            Err(ParseIconError("Tag".to_string()))
+        );
+        assert!(SURE_ICONS.contains(&Icon::DEFAULT.as_str()));
+    }
+
+    #[test]
+    fn custom_icons_skip_the_check() {
+        let icon = Icon::custom("folder");
+        assert_eq!(
+            serde_json::to_string(&icon).ok().as_deref(),
+            Some(r#""folder""#)
+        );
+        assert_eq!(serde_json::from_str::<Icon>(r#""folder""#).ok(), Some(icon));
+    }
+}
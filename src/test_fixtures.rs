@@ -0,0 +1,44 @@
+//! Fixtures shared by the unit tests
+
+use rust_decimal::Decimal;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::transaction::Transaction;
+
+/// A transaction as the API renders it, in the `Everyday` account with the
+/// nil ID.
+///
+/// The sign of `signed_amount_cents` sets the classification.
+pub fn transaction(id: u128, date: &str, name: &str, signed_amount_cents: i64) -> Transaction {
+    transaction_with(id, date, name, signed_amount_cents, Value::Null)
+}
+
+/// A [`transaction`] with the fields in `extra`, such as `category` or
+/// `account`, added to or replacing the defaults
+pub fn transaction_with(
+    id: u128,
+    date: &str,
+    name: &str,
+    signed_amount_cents: i64,
+    extra: Value,
+) -> Transaction {
+    let mut value = serde_json::json!({
+        "id": Uuid::from_u128(id),
+        "date": date,
+        "amount": format!("NZ${}", Decimal::new(signed_amount_cents.abs(), 2)),
+        "amount_cents": signed_amount_cents.abs(),
+        "signed_amount_cents": signed_amount_cents,
+        "currency": "NZD",
+        "name": name,
+        "classification": if signed_amount_cents < 0 { "expense" } else { "income" },
+        "account": { "id": Uuid::nil(), "name": "Everyday", "account_type": "depository" },
+        "tags": [],
+        "created_at": "2024-03-01T00:00:00Z",
+        "updated_at": "2024-03-01T00:00:00Z"
+    });
+    if let (Some(value), Some(extra)) = (value.as_object_mut(), extra.as_object()) {
+        value.extend(extra.clone());
+    }
+    serde_json::from_value(value).expect("valid transaction fixture")
+}
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{self, Display};
 use std::ops::Deref;
 use uuid::Uuid;
@@ -160,6 +160,119 @@ newtype_uuid!(
     pub ValuationId
 );
 
+/// A tri-state field used by update requests.
+///
+/// Update endpoints distinguish between a field that is left alone, a field
+/// that is explicitly cleared, and a field that is set to a new value. A plain
+/// `Option<T>` can only express two of those states, so update request fields
+/// that the server allows to be null use `Patch<T>` instead:
+///
+/// - [`Patch::Unchanged`] is omitted from the request body entirely
+/// - [`Patch::Clear`] is serialised as `null`
+/// - [`Patch::Set`] is serialised as the wrapped value
+///
+/// Fields using this type must be annotated with
+/// `#[serde(default, skip_serializing_if = "Patch::is_unchanged")]` so that
+/// unchanged fields are left out of the body. Fields that cannot be null,
+/// such as names, dates and amounts, stay `Option<T>` so that clearing them
+/// does not compile.
+///
+/// Any value converts into `Patch::Set`, so builder setters accept either a
+/// plain value or an explicit `Patch`:
+///
+/// ```no_run
+/// use sure_client_rs::{Patch, SureClient, TransactionId};
+/// use uuid::Uuid;
+///
+/// # async fn example(client: SureClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let transaction_id = TransactionId::new(Uuid::new_v4());
+///
+/// client.update_transaction()
+///     .id(&transaction_id)
+///     .name("Renamed".to_string())
+///     .notes(Patch::Clear)
+///     .category_id(Patch::Clear)
+///     .call()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Patch<T> {
+    /// Leave the field as it is (omitted from the request)
+    #[default]
+    Unchanged,
+    /// Clear the field (sent as `null`)
+    Clear,
+    /// Set the field to a new value
+    Set(T),
+}
+
+impl<T> Patch<T> {
+    /// Returns `true` if the field should be left unchanged
+    pub const fn is_unchanged(&self) -> bool {
+        matches!(self, Self::Unchanged)
+    }
+
+    /// Returns `true` if the field should be cleared
+    pub const fn is_clear(&self) -> bool {
+        matches!(self, Self::Clear)
+    }
+
+    /// Returns `true` if the field should be set to a new value
+    pub const fn is_set(&self) -> bool {
+        matches!(self, Self::Set(_))
+    }
+
+    /// Returns a reference to the new value, if one is being set
+    pub const fn as_set(&self) -> Option<&T> {
+        match self {
+            Self::Set(value) => Some(value),
+            Self::Unchanged | Self::Clear => None,
+        }
+    }
+
+    /// Maps the new value, if any, leaving `Unchanged` and `Clear` as they are
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Patch<U> {
+        match self {
+            Self::Unchanged => Patch::Unchanged,
+            Self::Clear => Patch::Clear,
+            Self::Set(value) => Patch::Set(f(value)),
+        }
+    }
+}
+
+impl<T> From<T> for Patch<T> {
+    fn from(value: T) -> Self {
+        Self::Set(value)
+    }
+}
+
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            // `Unchanged` is normally skipped by `skip_serializing_if`; if it
+            // is serialised anyway, it is indistinguishable from `Clear`.
+            Self::Unchanged | Self::Clear => serializer.serialize_none(),
+            Self::Set(value) => serializer.serialize_some(value),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    /// A present `null` becomes `Clear` and a present value becomes `Set`. A
+    /// missing field only becomes `Unchanged` when paired with `#[serde(default)]`.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<T>::deserialize(deserializer)?.map_or(Self::Clear, Self::Set))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,4 +296,39 @@ mod tests {
             .expect("UUID parsing should succeed for valid UUID string");
         assert_eq!(parsed, account_id);
     }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct PatchBody {
+        #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+        notes: Patch<String>,
+    }
+
+    #[test]
+    fn test_patch_serialization() {
+        let unchanged = PatchBody {
+            notes: Patch::Unchanged,
+        };
+        let clear = PatchBody {
+            notes: Patch::Clear,
+        };
+        let set = PatchBody {
+            notes: Patch::Set("hello".to_string()),
+        };
+
+        assert_eq!(serde_json::to_string(&unchanged).expect("serialise"), "{}");
+        assert_eq!(
+            serde_json::to_string(&clear).expect("serialise"),
+            r#"{"notes":null}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&set).expect("serialise"),
+            r#"{"notes":"hello"}"#
+        );
+
+        for body in [unchanged, clear, set] {
+            let json = serde_json::to_string(&body).expect("serialise");
+            let parsed: PatchBody = serde_json::from_str(&json).expect("deserialise");
+            assert_eq!(parsed, body);
+        }
+    }
 }
@@ -14,7 +14,8 @@
 use chrono::Utc;
 use rust_decimal::Decimal;
 use sure_client_rs::models::account::{
-    AccountableAttributes, DepositoryAttributes, DepositorySubtype, OtherAssetAttributes,
+    AccountStatus, AccountableAttributes, DepositoryAttributes, DepositorySubtype,
+    OtherAssetAttributes,
 };
 use sure_client_rs::{Auth, SureClient};
 
@@ -58,7 +59,7 @@ async fn test_account_crud_lifecycle() {
     assert_eq!(created.name, format!("Test Account {}", timestamp));
     assert_eq!(created.currency, iso_currency::Currency::NZD);
     // Note: subtype may not be returned by the API
-    assert_eq!(created.status.as_deref(), Some("active"));
+    assert_eq!(created.status, Some(AccountStatus::Active));
     println!("✓ Created account: {} (ID: {})", created.name, created.id);
 
     // Get the account by ID
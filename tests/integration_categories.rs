@@ -12,7 +12,7 @@
 )]
 
 use chrono::Utc;
-use sure_client_rs::{Auth, SureClient};
+use sure_client_rs::{Auth, HexColor, Icon, SureClient};
 
 /// Helper function to create a test client
 fn create_test_client() -> SureClient {
@@ -36,8 +36,8 @@ async fn test_category_crud_lifecycle() {
     let created = client
         .create_category()
         .name(format!("Test Category {}", timestamp))
-        .color("#FF5733".to_string())
-        .lucide_icon("shopping-cart".to_string())
+        .color("#FF5733".parse::<HexColor>().expect("valid color"))
+        .lucide_icon("shopping-cart".parse::<Icon>().expect("valid icon"))
         .call()
         .await
         .expect("Failed to create category");
@@ -62,8 +62,8 @@ async fn test_category_crud_lifecycle() {
         .update_category()
         .id(&created.id)
         .name(format!("Updated Category {}", timestamp))
-        .color("#3366FF".to_string())
-        .lucide_icon("tag".to_string())
+        .color("#3366FF".parse::<HexColor>().expect("valid color"))
+        .lucide_icon("tag".parse::<Icon>().expect("valid icon"))
         .call()
         .await
         .expect("Failed to update category");
@@ -114,7 +114,7 @@ async fn test_create_category_minimal() {
     let created = client
         .create_category()
         .name(format!("Minimal Category {}", timestamp))
-        .color("#00FF00".to_string())
+        .color("#00FF00".parse::<HexColor>().expect("valid color"))
         .call()
         .await
         .expect("Failed to create minimal category");
@@ -139,8 +139,8 @@ async fn test_category_with_parent() {
     let parent = client
         .create_category()
         .name(format!("Parent Category {}", timestamp))
-        .color("#FF0000".to_string())
-        .lucide_icon("folder".to_string())
+        .color("#FF0000".parse::<HexColor>().expect("valid color"))
+        .lucide_icon(Icon::custom("folder"))
         .call()
         .await
         .expect("Failed to create parent category");
@@ -151,8 +151,8 @@ async fn test_category_with_parent() {
     let child = client
         .create_category()
         .name(format!("Child Category {}", timestamp))
-        .color("#FFA500".to_string())
-        .lucide_icon("file".to_string())
+        .color("#FFA500".parse::<HexColor>().expect("valid color"))
+        .lucide_icon(Icon::custom("file"))
         .parent_id(parent.id.clone())
         .call()
         .await
@@ -221,3 +221,59 @@ async fn test_list_categories_pagination() {
         println!("✓ Page 2: {} categories", page2.items.categories.len());
     }
 }
+
+#[tokio::test]
+async fn test_merge_into_subcategory_makes_subcategories_roots() {
+    let client = create_test_client();
+    let timestamp = Utc::now().timestamp();
+
+    let create = |name: String, parent: Option<sure_client_rs::CategoryId>| {
+        let client = &client;
+        async move {
+            client
+                .create_category()
+                .name(name)
+                .color("#FF0000".parse::<HexColor>().expect("valid color"))
+                .maybe_parent_id(parent)
+                .call()
+                .await
+                .expect("Failed to create category")
+        }
+    };
+    let from = create(format!("Merge From {}", timestamp), None).await;
+    let from_child = create(format!("Merge From Child {}", timestamp), Some(from.id)).await;
+    let into_parent = create(format!("Merge Into Parent {}", timestamp), None).await;
+    let into = create(format!("Merge Into {}", timestamp), Some(into_parent.id)).await;
+    println!("✓ Created categories to merge");
+
+    let report = client
+        .merge_categories()
+        .from(from.id)
+        .into(into.id)
+        .call()
+        .await
+        .expect("Failed to merge categories");
+
+    assert!(
+        !report.plan.move_subcategories,
+        "Subcategories cannot move under a subcategory"
+    );
+    assert!(report.is_complete(), "Merge should complete");
+    let promoted = client
+        .get_category(&from_child.id)
+        .await
+        .expect("Failed to fetch former subcategory");
+    assert!(
+        promoted.parent.is_none(),
+        "Subcategory should be a root now"
+    );
+    println!("✓ Merged into a subcategory and promoted subcategories");
+
+    for id in [&from_child.id, &into.id, &into_parent.id] {
+        client
+            .delete_category(id)
+            .await
+            .expect("Failed to delete category");
+    }
+    println!("✓ Cleaned up categories");
+}
@@ -12,7 +12,12 @@
 )]
 
 use chrono::Utc;
-use sure_client_rs::{Auth, SureClient};
+use rust_decimal::Decimal;
+use sure_client_rs::models::account::{
+    AccountableAttributes, DepositoryAttributes, DepositorySubtype,
+};
+use sure_client_rs::models::transaction::{TransactionFilter, TransactionNature};
+use sure_client_rs::{Auth, HexColor, SureClient};
 
 /// Helper function to create a test client
 fn create_test_client() -> SureClient {
@@ -37,7 +42,7 @@ async fn test_merchant_crud_lifecycle() {
     let created = client
         .create_merchant()
         .name(format!("Test Merchant {}", timestamp))
-        .color("#FF5733".to_string())
+        .color("#FF5733".parse::<HexColor>().expect("valid color"))
         .call()
         .await
         .expect("Failed to create merchant");
@@ -61,7 +66,7 @@ async fn test_merchant_crud_lifecycle() {
         .update_merchant()
         .id(&created.id)
         .name(format!("Updated Merchant {}", timestamp))
-        .color("#3366FF".to_string())
+        .color("#3366FF".parse::<HexColor>().expect("valid color"))
         .call()
         .await
         .expect("Failed to update merchant");
@@ -183,7 +188,7 @@ async fn test_update_merchant_name_only() {
     let created = client
         .create_merchant()
         .name(format!("Original Name {}", timestamp))
-        .color("#FF0000".to_string())
+        .color("#FF0000".parse::<HexColor>().expect("valid color"))
         .call()
         .await
         .expect("Failed to create merchant");
@@ -221,7 +226,7 @@ async fn test_update_merchant_color_only() {
     let created = client
         .create_merchant()
         .name(format!("Color Test Merchant {}", timestamp))
-        .color("#00FF00".to_string())
+        .color("#00FF00".parse::<HexColor>().expect("valid color"))
         .call()
         .await
         .expect("Failed to create merchant");
@@ -233,7 +238,7 @@ async fn test_update_merchant_color_only() {
     let updated = client
         .update_merchant()
         .id(&created.id)
-        .color("#0000FF".to_string())
+        .color("#0000FF".parse::<HexColor>().expect("valid color"))
         .call()
         .await
         .expect("Failed to update merchant color");
@@ -249,3 +254,90 @@ async fn test_update_merchant_color_only() {
         .expect("Failed to delete merchant");
     println!("✓ Cleaned up test merchant");
 }
+
+#[tokio::test]
+async fn test_merge_merchants_moves_every_duplicate() {
+    let client = create_test_client();
+    let timestamp = Utc::now().timestamp();
+
+    let account = client
+        .create_account()
+        .name(format!("Merchant Merge Account {}", timestamp))
+        .attributes(AccountableAttributes::Depository(DepositoryAttributes {
+            subtype: Some(DepositorySubtype::Checking),
+            locked_attributes: None,
+        }))
+        .balance(Decimal::new(100_000, 2))
+        .currency(iso_currency::Currency::NZD)
+        .call()
+        .await
+        .expect("Failed to create test account");
+
+    let mut merchants = Vec::new();
+    for name in ["Merge Into", "Merge Duplicate A", "Merge Duplicate B"] {
+        let merchant = client
+            .create_merchant()
+            .name(format!("{} {}", name, timestamp))
+            .call()
+            .await
+            .expect("Failed to create merchant");
+        merchants.push(merchant.id);
+    }
+    let (into, duplicates) = (merchants[0], &merchants[1..]);
+    println!("✓ Created merchants to merge");
+
+    for (index, merchant_id) in duplicates.iter().enumerate() {
+        for _ in 0..2 {
+            client
+                .create_transaction()
+                .account_id(account.id)
+                .date(Utc::now())
+                .amount(Decimal::new(1000, 2))
+                .name(format!("Merge Transaction {} {}", index, timestamp))
+                .currency(iso_currency::Currency::NZD)
+                .merchant_id(*merchant_id)
+                .nature(TransactionNature::Expense)
+                .call()
+                .await
+                .expect("Failed to create transaction");
+        }
+    }
+    println!("✓ Created transactions for each duplicate");
+
+    let report = client
+        .merge_merchants()
+        .into(into)
+        .from(duplicates)
+        .call()
+        .await
+        .expect("Failed to merge merchants");
+
+    assert!(report.is_complete(), "Merge should complete");
+    let moved = report.moved();
+    assert_eq!(moved.len(), 2);
+    for count in &moved {
+        assert_eq!(
+            (count.planned, count.moved),
+            (2, 2),
+            "Every transaction of {} should be fetched and moved",
+            count.merchant_id
+        );
+    }
+    let filter = TransactionFilter::builder().merchant_id(into).build();
+    let merged = client
+        .get_all_transactions(&filter)
+        .await
+        .expect("Failed to list merged transactions");
+    assert_eq!(merged.len(), 4);
+    println!("✓ Merged both duplicates");
+
+    client
+        .delete_account(&account.id)
+        .await
+        .expect("Failed to delete account");
+    client
+        .delete_merchant(&into)
+        .await
+        .expect("Failed to delete merchant");
+    println!("✓ Cleaned up test data");
+}
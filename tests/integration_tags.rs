@@ -0,0 +1,155 @@
+//! Integration tests for tag endpoints
+//!
+//! These tests require a running Sure API server and valid credentials.
+//! Set SURE_BASE_URL and SURE_TOKEN environment variables in the .env file.
+
+#![allow(
+    clippy::tests_outside_test_module,
+    clippy::unwrap_used,
+    clippy::too_many_lines,
+    clippy::indexing_slicing,
+    reason = "Integration tests are correctly placed outside cfg(test) modules"
+)]
+
+use chrono::Utc;
+use sure_client_rs::{Auth, HexColor, SureClient};
+
+/// Helper function to create a test client
+fn create_test_client() -> SureClient {
+    dotenvy::dotenv().ok();
+
+    let base_url = std::env::var("SURE_BASE_URL")
+        .unwrap_or_else(|_| "http://localhost:3000".to_string())
+        .parse()
+        .unwrap();
+    let token = std::env::var("SURE_TOKEN").expect("SURE_TOKEN must be set in .env file");
+
+    SureClient::new(reqwest::Client::new(), Auth::api_key(token), base_url)
+}
+
+#[tokio::test]
+
+async fn test_tag_crud_lifecycle() {
+    let client = create_test_client();
+    let timestamp = Utc::now().timestamp();
+
+    // Create a tag
+    let created = client
+        .create_tag()
+        .name(format!("Test Tag {}", timestamp))
+        .color("#E99537".parse::<HexColor>().expect("valid color"))
+        .call()
+        .await
+        .expect("Failed to create tag");
+
+    assert_eq!(created.name, format!("Test Tag {}", timestamp));
+    assert_eq!(created.color, "#E99537");
+    println!("✓ Created tag: {} (ID: {})", created.name, created.id);
+
+    // Get the tag by ID
+    let fetched = client
+        .get_tag(&created.id)
+        .await
+        .expect("Failed to fetch tag");
+
+    assert_eq!(fetched.id, created.id);
+    assert_eq!(fetched.name, created.name);
+    println!("✓ Fetched tag: {}", fetched.name);
+
+    // Rename and recolour the tag
+    let updated = client
+        .update_tag()
+        .id(&created.id)
+        .name(format!("Updated Tag {}", timestamp))
+        .color("#805dee".parse::<HexColor>().expect("valid color"))
+        .call()
+        .await
+        .expect("Failed to update tag");
+
+    assert_eq!(updated.name, format!("Updated Tag {}", timestamp));
+    assert_eq!(updated.color, "#805dee");
+    println!("✓ Updated tag: {}", updated.name);
+
+    // The tag is listed
+    let tags = client.get_all_tags().await.expect("Failed to list tags");
+    assert!(tags.iter().any(|tag| tag.id == created.id));
+    println!("✓ Listed {} tags", tags.len());
+
+    // Delete the tag
+    client
+        .delete_tag(&created.id)
+        .await
+        .expect("Failed to delete tag");
+    println!("✓ Deleted tag: {}", created.id);
+
+    // Verify tag is deleted (should return 404)
+    let result = client.get_tag(&created.id).await;
+    assert!(result.is_err(), "Deleted tag should not be fetchable");
+    println!("✓ Verified tag deletion");
+}
+
+#[tokio::test]
+
+async fn test_find_or_create_tag() {
+    let client = create_test_client();
+    let timestamp = Utc::now().timestamp();
+    let mut tags = client.get_all_tags().await.expect("Failed to list tags");
+    let count = tags.len();
+
+    let created = client
+        .find_or_create_tag()
+        .tags(&mut tags)
+        .name(&format!("Find Me {}", timestamp))
+        .call()
+        .await
+        .expect("Failed to create tag");
+    assert_eq!(tags.len(), count + 1);
+    println!("✓ Created tag: {}", created.name);
+
+    // A differently written name finds the same tag without creating another
+    let found = client
+        .find_or_create_tag()
+        .tags(&mut tags)
+        .name(&format!("find-me {}", timestamp))
+        .call()
+        .await
+        .expect("Failed to find tag");
+    assert_eq!(found.id, created.id);
+    assert_eq!(tags.len(), count + 1);
+    println!("✓ Found existing tag: {}", found.name);
+
+    // Cleanup
+    client
+        .delete_tag(&created.id)
+        .await
+        .expect("Failed to delete tag");
+    println!("✓ Cleaned up tag");
+}
+
+#[tokio::test]
+
+async fn test_list_tags_pagination() {
+    let client = create_test_client();
+
+    let page1 = client
+        .get_tags()
+        .page(1)
+        .per_page(10)
+        .call()
+        .await
+        .expect("Failed to get page 1");
+
+    println!("✓ Page 1: {} tags", page1.items.tags.len());
+
+    if page1.items.tags.len() == 10 {
+        let page2 = client
+            .get_tags()
+            .page(2)
+            .per_page(10)
+            .call()
+            .await
+            .expect("Failed to get page 2");
+
+        println!("✓ Page 2: {} tags", page2.items.tags.len());
+    }
+}
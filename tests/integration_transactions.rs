@@ -18,8 +18,11 @@ use rust_decimal::Decimal;
 use sure_client_rs::models::account::{
     AccountDetail, AccountableAttributes, DepositoryAttributes, DepositorySubtype,
 };
-use sure_client_rs::models::transaction::TransactionNature;
-use sure_client_rs::{Auth, SureClient};
+use sure_client_rs::models::category::Classification;
+use sure_client_rs::models::transaction::{
+    CreateTransactionData, TransactionFilter, TransactionNature, UpdateTransactionData,
+};
+use sure_client_rs::{Auth, Patch, SureClient};
 
 /// Helper function to create a test client
 fn create_test_client() -> SureClient {
@@ -74,7 +77,7 @@ async fn test_transaction_crud_lifecycle() {
         .expect("Failed to create transaction");
 
     assert_eq!(created.name, format!("Test Transaction {}", timestamp));
-    assert_eq!(created.classification, "expense");
+    assert_eq!(created.classification, Classification::Expense);
     println!(
         "✓ Created transaction: {} (ID: {})",
         created.name, created.id
@@ -294,7 +297,7 @@ async fn test_transaction_with_income_nature() {
         .await
         .expect("Failed to create income transaction");
 
-    assert_eq!(created.classification, "income");
+    assert_eq!(created.classification, Classification::Income);
     println!("✓ Created income transaction: {}", created.name);
 
     // Cleanup
@@ -338,3 +341,152 @@ async fn test_list_transactions_pagination() {
         println!("✓ Page 2: {} transactions", page2.items.transactions.len());
     }
 }
+
+#[tokio::test]
+
+async fn test_create_transactions_batch() {
+    let client = create_test_client();
+    let timestamp = Utc::now().timestamp();
+
+    // Create test account
+    let attributes = AccountableAttributes::Depository(DepositoryAttributes {
+        subtype: Some(DepositorySubtype::Checking),
+        locked_attributes: None,
+    });
+
+    let account = client
+        .create_account()
+        .name(format!("Batch Test Account {}", timestamp))
+        .balance(Decimal::new(0, 2))
+        .attributes(attributes)
+        .currency(iso_currency::Currency::NZD)
+        .call()
+        .await
+        .expect("Failed to create test account");
+
+    let transactions = (1..=5)
+        .map(|day| {
+            CreateTransactionData::builder()
+                .account_id(account.id)
+                .date(Utc.with_ymd_and_hms(2024, 2, day, 12, 0, 0).unwrap())
+                .amount(Decimal::new(1000 + i64::from(day), 2))
+                .name(format!("Batch Transaction {} {}", day, timestamp))
+                .currency(iso_currency::Currency::NZD)
+                .nature(TransactionNature::Expense)
+                .build()
+        })
+        .collect();
+
+    let report = client
+        .create_transactions()
+        .transactions(transactions)
+        .concurrency(2)
+        .call()
+        .await
+        .expect("Failed to run batch");
+
+    assert!(report.is_success(), "All batch items should succeed");
+    assert_eq!(report.results.len(), 5);
+    println!("✓ Created {} transactions in batch", report.results.len());
+
+    // Cleanup
+    for (_, transaction) in report.succeeded() {
+        client
+            .delete_transaction(&transaction.id)
+            .await
+            .expect("Failed to delete transaction");
+    }
+    client
+        .delete_account(&account.id)
+        .await
+        .expect("Failed to delete account");
+    println!("✓ Cleaned up test data");
+}
+
+#[tokio::test]
+
+async fn test_bulk_update_and_delete_matching() {
+    let client = create_test_client();
+    let timestamp = Utc::now().timestamp();
+
+    // Create test account
+    let attributes = AccountableAttributes::Depository(DepositoryAttributes {
+        subtype: Some(DepositorySubtype::Checking),
+        locked_attributes: None,
+    });
+
+    let account = client
+        .create_account()
+        .name(format!("Bulk Test Account {}", timestamp))
+        .balance(Decimal::new(0, 2))
+        .attributes(attributes)
+        .currency(iso_currency::Currency::NZD)
+        .call()
+        .await
+        .expect("Failed to create test account");
+
+    for day in 1..=3 {
+        client
+            .create_transaction()
+            .account_id(account.id)
+            .date(Utc.with_ymd_and_hms(2024, 4, day, 12, 0, 0).unwrap())
+            .amount(Decimal::new(999, 2))
+            .name(format!("Bulk Transaction {} {}", day, timestamp))
+            .notes("to be cleared".to_string())
+            .currency(iso_currency::Currency::NZD)
+            .nature(TransactionNature::Expense)
+            .call()
+            .await
+            .expect("Failed to create transaction");
+    }
+
+    let filter = TransactionFilter::builder().account_id(account.id).build();
+    let changes = UpdateTransactionData::builder().notes(Patch::Clear).build();
+
+    // Dry run reports the matches without changing anything
+    let preview = client
+        .update_transactions_matching()
+        .filter(&filter)
+        .changes(changes.clone())
+        .dry_run(true)
+        .call()
+        .await
+        .expect("Failed to plan bulk update");
+
+    assert!(preview.is_dry_run());
+    assert_eq!(preview.plan.transaction_ids.len(), 3);
+    println!("✓ Planned: {}", preview.plan);
+
+    // Apply the update
+    let report = client
+        .update_transactions_matching()
+        .filter(&filter)
+        .changes(changes)
+        .call()
+        .await
+        .expect("Failed to run bulk update");
+
+    let results = report.results.expect("Results for a non-dry run");
+    assert!(results.is_success(), "All updates should succeed");
+    for (_, transaction) in results.succeeded() {
+        assert_eq!(transaction.notes, None, "Notes should have been cleared");
+    }
+    println!("✓ Cleared notes on {} transactions", results.results.len());
+
+    // Delete everything that matched
+    let deleted = client
+        .delete_transactions_matching()
+        .filter(&filter)
+        .call()
+        .await
+        .expect("Failed to run bulk delete");
+
+    assert!(deleted.results.expect("Results").is_success());
+    println!("✓ Deleted matching transactions");
+
+    client
+        .delete_account(&account.id)
+        .await
+        .expect("Failed to delete account");
+    println!("✓ Cleaned up test data");
+}
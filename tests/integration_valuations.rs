@@ -25,6 +25,7 @@ use rust_decimal::Decimal;
 use sure_client_rs::models::account::{
     AccountableAttributes, Address, PropertyAttributes, PropertySubtype,
 };
+use sure_client_rs::models::valuation::ValuationKind;
 use sure_client_rs::{AccountId, Auth, SureClient};
 
 /// Helper to construct a client from the SURE_BASE_URL / SURE_TOKEN env vars.
@@ -193,6 +194,90 @@ async fn update_valuation_overwrites_amount_and_date() {
     );
 }
 
+#[tokio::test]
+async fn list_valuations_filters_by_account_date_and_kind() {
+    let client = create_test_client();
+    let account_id = create_test_property(&client, "Valuation List").await;
+
+    let january = NaiveDate::from_ymd_opt(2026, 1, 12).expect("valid date literal");
+    let february = NaiveDate::from_ymd_opt(2026, 2, 12).expect("valid date literal");
+    for (date, amount) in [
+        (january, Decimal::new(77_063_910, 2)),
+        (february, Decimal::new(77_128_321, 2)),
+    ] {
+        client
+            .create_valuation()
+            .account_id(account_id)
+            .amount(amount)
+            .date(date)
+            .call()
+            .await
+            .expect("create_valuation should succeed");
+    }
+
+    let history = client
+        .get_all_valuations()
+        .account_id(&account_id)
+        .kind(ValuationKind::Reconciliation)
+        .call()
+        .await
+        .expect("get_all_valuations should succeed");
+    let dates: Vec<_> = history.iter().map(|valuation| valuation.date).collect();
+    assert!(dates.contains(&january) && dates.contains(&february));
+    assert!(history.iter().all(|valuation| {
+        valuation.account.id == account_id && valuation.kind == ValuationKind::Reconciliation
+    }));
+    println!("✓ Listed {} reconciliations", history.len());
+
+    let february_only = client
+        .get_valuations()
+        .account_id(&account_id)
+        .start_date(february)
+        .end_date(february)
+        .call()
+        .await
+        .expect("get_valuations should succeed");
+    assert_eq!(february_only.items.valuations.len(), 1);
+    assert_eq!(
+        february_only.items.valuations.first().map(|v| v.date),
+        Some(february)
+    );
+    println!("✓ Date range narrowed the list to one valuation");
+}
+
+#[tokio::test]
+async fn delete_valuation_removes_entry() {
+    let client = create_test_client();
+    let account_id = create_test_property(&client, "Valuation Delete").await;
+
+    let date = NaiveDate::from_ymd_opt(2026, 5, 12).expect("valid date literal");
+    let created = client
+        .create_valuation()
+        .account_id(account_id)
+        .amount(Decimal::new(77_300_000, 2))
+        .date(date)
+        .call()
+        .await
+        .expect("create_valuation should succeed");
+
+    client
+        .delete_valuation(&created.id)
+        .await
+        .expect("delete_valuation should succeed");
+
+    let result = client.get_valuation(&created.id).await;
+    assert!(result.is_err(), "Deleted valuation should not be fetchable");
+
+    let remaining = client
+        .get_all_valuations()
+        .account_id(&account_id)
+        .call()
+        .await
+        .expect("get_all_valuations should succeed");
+    assert!(remaining.iter().all(|valuation| valuation.id != created.id));
+    println!("✓ Deleted valuation {}", created.id);
+}
+
 /// End-to-end regression test for the bug that bit `sync_property` in
 /// sure-akahu-sync-tool: posting several past-dated valuations in rapid
 /// succession causes Sure's deduped SyncJob to materialise balances for